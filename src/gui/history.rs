@@ -1,6 +1,7 @@
-use crate::utils::history_manager::HistoryManager;
+use crate::gui::theme;
+use crate::utils::{history_manager::HistoryManager, live_settings};
 use eframe::egui;
-use egui::{Color32, CornerRadius, Frame, Margin, ScrollArea};
+use egui::{CornerRadius, Frame, Margin, ScrollArea};
 
 pub struct HistoryApp {
     pub(crate) history_manager: HistoryManager,
@@ -18,33 +19,30 @@ impl HistoryApp {
     }
 }
 
-const BLUE_HIGHLIGHT: Color32 = Color32::from_rgb(50, 140, 255);
-
 impl eframe::App for HistoryApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let theme = theme::apply(ctx, &live_settings::current().theme);
+        let colors = &theme.colors;
+
         egui::CentralPanel::default()
             .frame(Frame {
-                fill: Color32::from_rgba_unmultiplied(20, 20, 24, 250),
-                corner_radius: CornerRadius::same(8),
-                inner_margin: Margin::same(16),
-                stroke: egui::Stroke::new(1.0, Color32::from_rgba_unmultiplied(60, 60, 70, 180)),
+                fill: colors.panel,
+                corner_radius: CornerRadius::same(theme.radius.section),
+                inner_margin: Margin::same(theme.padding.section),
+                stroke: egui::Stroke::new(1.0, colors.border),
                 ..Frame::default()
             })
             .show(ctx, |ui| {
                 // Header
                 ui.horizontal(|ui| {
-                    ui.heading(
-                        egui::RichText::new("📜 History")
-                            .size(22.0)
-                            .color(BLUE_HIGHLIGHT),
-                    );
+                    ui.heading(theme.heading("📜 History"));
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui
                             .button(
                                 egui::RichText::new("✕")
                                     .size(16.0)
-                                    .color(Color32::from_rgb(200, 80, 80)),
+                                    .color(colors.danger),
                             )
                             .clicked()
                         {
@@ -59,15 +57,13 @@ impl eframe::App for HistoryApp {
 
                 // Filter input
                 Frame {
-                    fill: Color32::from_rgba_unmultiplied(25, 25, 30, 255),
-                    corner_radius: CornerRadius::same(6),
-                    inner_margin: Margin::symmetric(10, 8),
-                    stroke: egui::Stroke::new(1.0, Color32::from_rgb(60, 60, 70)),
-                    ..Frame::default()
+                    corner_radius: CornerRadius::same(theme.radius.item),
+                    stroke: egui::Stroke::new(1.0, colors.border),
+                    ..theme.input_frame()
                 }
                 .show(ui, |ui| {
                     ui.horizontal(|ui| {
-                        ui.label(egui::RichText::new("🔍").size(16.0).color(BLUE_HIGHLIGHT));
+                        ui.label(egui::RichText::new("🔍").size(16.0).color(colors.accent));
 
                         ui.add(
                             egui::TextEdit::singleline(&mut self.search_filter)
@@ -83,7 +79,7 @@ impl eframe::App for HistoryApp {
                 // Action buttons
                 ui.horizontal(|ui| {
                     if ui
-                        .button(egui::RichText::new("🗑️ Clear All").size(12.0))
+                        .button(egui::RichText::new("🗑️ Clear All").size(theme.text.body))
                         .clicked()
                     {
                         self.clear_history();
@@ -92,8 +88,8 @@ impl eframe::App for HistoryApp {
                     let total_items = self.history_manager.load_history().len();
                     ui.label(
                         egui::RichText::new(format!("Total: {} items", total_items))
-                            .size(12.0)
-                            .color(Color32::from_rgb(150, 150, 160)),
+                            .size(theme.text.body)
+                            .color(colors.text_muted),
                     );
                 });
 
@@ -110,12 +106,12 @@ impl eframe::App for HistoryApp {
                         ui.label(
                             egui::RichText::new("📭 No history yet")
                                 .size(18.0)
-                                .color(Color32::from_rgb(150, 150, 160)),
+                                .color(colors.text_muted),
                         );
                         ui.label(
                             egui::RichText::new("Your searches will appear here.")
-                                .size(13.0)
-                                .color(Color32::from_rgb(110, 110, 120)),
+                                .size(theme.text.body)
+                                .color(colors.text_faint),
                         );
                     });
                 } else {
@@ -132,19 +128,13 @@ impl eframe::App for HistoryApp {
 
                                 // Background frame
                                 let frame = Frame {
-                                    fill: if is_selected {
-                                        Color32::from_rgba_unmultiplied(60, 65, 90, 220)
-                                    } else {
-                                        Color32::from_rgba_unmultiplied(30, 30, 36, 200)
-                                    },
-                                    corner_radius: CornerRadius::same(6),
-                                    inner_margin: Margin::symmetric(12, 10),
+                                    fill: if is_selected { colors.selected } else { colors.item },
                                     stroke: if is_selected {
-                                        egui::Stroke::new(1.0, BLUE_HIGHLIGHT)
+                                        egui::Stroke::new(1.0, colors.accent)
                                     } else {
                                         egui::Stroke::NONE
                                     },
-                                    ..Frame::default()
+                                    ..theme.row_frame(is_selected)
                                 };
 
                                 let response = frame.show(ui, |ui| {
@@ -152,8 +142,8 @@ impl eframe::App for HistoryApp {
                                         // ICON
                                         ui.label(
                                             egui::RichText::new(entry.result_icon.as_str())
-                                            .size(12.0)
-                                            .color(BLUE_HIGHLIGHT)
+                                            .size(theme.text.body)
+                                            .color(colors.accent)
                                             .monospace(),
                                         );
 
@@ -163,20 +153,20 @@ impl eframe::App for HistoryApp {
                                         ui.vertical(|ui| {
                                             ui.label(
                                                 egui::RichText::new(&entry.query)
-                                                    .size(14.0)
-                                                    .color(Color32::from_rgb(240, 240, 245)),
+                                                    .size(theme.text.title)
+                                                    .color(colors.text),
                                             );
 
                                             ui.label(
                                                 egui::RichText::new(&entry.result_title)
-                                                    .size(12.0)
-                                                    .color(Color32::from_rgb(180, 180, 190)),
+                                                    .size(theme.text.body)
+                                                    .color(colors.text_secondary),
                                             );
 
                                             ui.label(
                                                 egui::RichText::new(&entry.timestamp)
-                                                    .size(10.0)
-                                                    .color(Color32::from_rgb(120, 120, 130)),
+                                                    .size(theme.text.tiny)
+                                                    .color(colors.text_faint),
                                             );
                                         });
 
@@ -186,8 +176,8 @@ impl eframe::App for HistoryApp {
                                                 let delete_response = ui
                                                     .button(
                                                         egui::RichText::new("🗑")
-                                                            .size(13.0)
-                                                            .color(Color32::from_rgb(220, 80, 80)),
+                                                            .size(theme.text.body)
+                                                            .color(colors.danger),
                                                     )
                                                     .on_hover_text("Delete entry");
 
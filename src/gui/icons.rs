@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+use resvg::{tiny_skia, usvg};
+
+use crate::utils::icon_theme::IconTheme;
+
+const MAX_CACHED_ICONS: usize = 128;
+
+struct CachedIcon {
+    // None when the file could not be decoded, so it is not retried every frame
+    texture: Option<egui::TextureHandle>,
+    last_used: u64,
+}
+
+/// Resolves icon names through the icon theme and keeps the decoded textures in an LRU cache
+pub struct IconCache {
+    theme: IconTheme,
+    textures: HashMap<(PathBuf, u32), CachedIcon>,
+    clock: u64,
+}
+
+impl IconCache {
+    pub fn new() -> Self {
+        Self {
+            theme: IconTheme::new(),
+            textures: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Texture for the first of `names` that resolves, rendered for `size` points on screen
+    pub fn get(&mut self, ctx: &egui::Context, names: &[String], size: f32) -> Option<egui::TextureHandle> {
+        let scale = ctx.pixels_per_point().round().max(1.0) as u32;
+        let size = size.round() as u32;
+
+        let path = names
+            .iter()
+            .find_map(|name| self.theme.lookup(name, size, scale))?;
+
+        let pixels = size * scale;
+        let key = (path, pixels);
+        self.clock += 1;
+
+        if let Some(cached) = self.textures.get_mut(&key) {
+            cached.last_used = self.clock;
+            return cached.texture.clone();
+        }
+
+        let texture = Self::load_image(&key.0, pixels)
+            .map(|image| ctx.load_texture(key.0.to_string_lossy(), image, egui::TextureOptions::LINEAR));
+
+        if self.textures.len() >= MAX_CACHED_ICONS {
+            self.evict_least_recently_used();
+        }
+        self.textures.insert(key, CachedIcon { texture: texture.clone(), last_used: self.clock });
+
+        texture
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self
+            .textures
+            .iter()
+            .min_by_key(|(_, cached)| cached.last_used)
+            .map(|(key, _)| key.clone());
+
+        if let Some(key) = oldest {
+            self.textures.remove(&key);
+        }
+    }
+
+    fn load_image(path: &Path, pixels: u32) -> Option<egui::ColorImage> {
+        let data = std::fs::read(path).ok()?;
+
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg")) {
+            Self::render_svg(&data, pixels)
+        } else {
+            let image = image::load_from_memory_with_format(&data, image::ImageFormat::Png).ok()?;
+            let image = image.to_rgba8();
+            let size = [image.width() as usize, image.height() as usize];
+            Some(egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+        }
+    }
+
+    fn render_svg(data: &[u8], pixels: u32) -> Option<egui::ColorImage> {
+        let tree = usvg::Tree::from_data(data, &usvg::Options::default()).ok()?;
+        let mut pixmap = tiny_skia::Pixmap::new(pixels, pixels)?;
+
+        let svg_size = tree.size();
+        let scale = (pixels as f32 / svg_size.width()).min(pixels as f32 / svg_size.height());
+        resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+        let size = [pixels as usize, pixels as usize];
+        Some(egui::ColorImage::from_rgba_premultiplied(size, pixmap.data()))
+    }
+}
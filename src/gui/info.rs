@@ -1,9 +1,7 @@
 use eframe::egui;
-use egui::{Frame, CornerRadius, Color32, Margin, ScrollArea, RichText, Align};
-
-const BLUE_HIGHLIGHT: Color32 = Color32::from_rgb(50, 140, 255);
-const SECTION_BG: Color32 = Color32::from_rgb(28, 28, 32);
-const SECTION_BORDER: Color32 = Color32::from_rgb(60, 60, 70);
+use egui::{ScrollArea, RichText, Align};
+use crate::gui::theme::{self, Theme};
+use crate::utils::live_settings;
 
 pub struct InfoApp {}
 
@@ -15,26 +13,20 @@ impl InfoApp {
 
 impl eframe::App for InfoApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let theme = theme::apply(ctx, &live_settings::current().theme);
+        let colors = &theme.colors;
+
         egui::CentralPanel::default()
-            .frame(Frame {
-                fill: Color32::from_rgb(18, 18, 22),
-                corner_radius: CornerRadius::same(12),
-                inner_margin: Margin::same(20),
-                stroke: egui::Stroke::new(1.5, Color32::from_rgb(60, 60, 70)),
-                ..Default::default()
-            })
+            .frame(theme.window_frame())
             .show(ctx, |ui| {
                 // Header with close button
                 ui.horizontal(|ui| {
                     ui.add_space(4.0);
-                    ui.heading(RichText::new("ℹ️  About Quick Search")
-                        .size(24.0)
-                        .color(BLUE_HIGHLIGHT)
-                        .strong());
+                    ui.heading(theme.heading("ℹ️  About Quick Search"));
 
                     ui.with_layout(egui::Layout::right_to_left(Align::Center), |ui| {
                         if ui.add(egui::Button::new(RichText::new("✕").size(18.0))
-                            .fill(Color32::from_rgb(60, 30, 30))
+                            .fill(colors.danger_background)
                             .stroke(egui::Stroke::NONE))
                             .on_hover_text("Close")
                             .clicked() 
@@ -54,63 +46,52 @@ impl eframe::App for InfoApp {
                     .show(ui, |ui| {
                         ui.vertical_centered(|ui| {
                             // App info section
-                            Frame {
-                                fill: SECTION_BG,
-                                corner_radius: CornerRadius::same(8),
-                                inner_margin: Margin::same(16),
-                                stroke: egui::Stroke::new(1.0, SECTION_BORDER),
-                                ..Default::default()
-                            }
-                            .show(ui, |ui| {
+                            theme.section_frame().show(ui, |ui| {
                                 ui.set_max_width(520.0);
                                 ui.vertical_centered(|ui| {
                                     ui.label(RichText::new("Quick Search")
-                                        .size(20.0)
+                                        .size(theme.text.heading)
                                         .strong()
-                                        .color(Color32::from_rgb(240, 240, 250)));
+                                        .color(colors.text));
                                     ui.add_space(4.0);
                                     ui.label(RichText::new("Version 0.1.0")
-                                        .size(13.0)
-                                        .color(Color32::from_rgb(160, 160, 180)));
+                                        .size(theme.text.body)
+                                        .color(colors.text_secondary));
                                     ui.add_space(8.0);
                                     ui.label(RichText::new("A fast, lightweight application launcher")
-                                        .size(12.0)
-                                        .color(Color32::from_rgb(140, 140, 160)));
+                                        .size(theme.text.body)
+                                        .color(colors.text_muted));
                                 });
                             });
 
                             ui.add_space(20.0);
 
                             // Features grid
-                            self.render_section_centered(ui, "Features", 520.0, |ui| {
+                            self.render_section_centered(ui, &theme, "Features", 520.0, |ui| {
                                 let features = vec![
                                     ("🔍", "Search Applications", "Find and launch apps instantly"),
-                                    ("🌐", "Web Search", "Search the web or open URLs directly"),
-                                    ("📁", "File Browser", "Open files and folders by path"),
-                                    ("🔢", "Calculator", "Evaluate math expressions on the fly"),
+                                    ("🌐", "Web Search", "Search the web, a site by keyword (e.g. 'gh'), or open URLs"),
+                                    ("📁", "File Browser", "Find files by name or complete a typed path"),
+                                    ("🔢", "Calculator", "Math, percentages, units (5 km to mi) and bases (0xff to bin)"),
+                                    ("📋", "Clipboard History", "Find and copy again what you copied, e.g. 'cb foo' (daemon mode)"),
+                                    ("💱", "Currencies", "Convert amounts offline, e.g. '100 usd to eur'"),
+                                    ("🕒", "Date & Time", "Time zones, date math and timestamps, e.g. 'now in Tokyo'"),
                                     ("📜", "History", "Access your search history"),
+                                    ("🎨", "Themes", "Dark, light, high contrast or your own, picked in settings"),
                                     ("⚙️", "Settings", "Customize your experience"),
                                 ];
 
                                 for (icon, title, desc) in features {
-                                    Frame {
-                                        fill: Color32::from_rgba_unmultiplied(35, 35, 42, 200),
-                                        corner_radius: CornerRadius::same(6),
-                                        inner_margin: Margin::symmetric(12, 10),
-                                        ..Default::default()
-                                    }
-                                    .show(ui, |ui| {
+                                    theme.item_frame().show(ui, |ui| {
                                         ui.horizontal(|ui| {
                                             ui.label(RichText::new(icon).size(18.0));
                                             ui.add_space(10.0);
                                             ui.vertical(|ui| {
                                                 ui.label(RichText::new(title)
-                                                    .size(13.5)
+                                                    .size(theme.text.title)
                                                     .strong()
-                                                    .color(Color32::from_rgb(220, 220, 235)));
-                                                ui.label(RichText::new(desc)
-                                                    .size(11.5)
-                                                    .color(Color32::from_rgb(150, 150, 165)));
+                                                    .color(colors.text));
+                                                ui.label(theme.note(desc));
                                             });
                                         });
                                     });
@@ -121,34 +102,36 @@ impl eframe::App for InfoApp {
                             ui.add_space(20.0);
 
                             // Keyboard shortcuts
-                            self.render_section_centered(ui, "Keyboard Shortcuts", 520.0, |ui| {
+                            self.render_section_centered(ui, &theme, "Keyboard Shortcuts", 520.0, |ui| {
                                 let shortcuts = vec![
                                     ("↵ Enter", "Execute selected action"),
                                     ("↓ Down", "Navigate to next result"),
                                     ("↑ Up", "Navigate to previous result"),
+                                    ("PgUp / PgDn", "Move one page through the results"),
+                                    ("Home / End", "Jump to the first or last result"),
+                                    ("Ctrl + 1-9", "Run the n-th result"),
+                                    ("⇥ Tab", "Complete a typed path or enter the selected folder"),
+                                    ("→ Right", "Enter the selected folder while typing a path"),
+                                    ("Ctrl + P", "Pin or unpin the selected clipboard entry"),
+                                    ("Shift + Del", "Remove the selected clipboard entry"),
                                     ("Esc", "Close window"),
                                     ("@ + word", "Access special commands"),
+                                    ("= / > ? ! cb", "Search only the calculator, files, commands, web, engines or clipboard"),
                                 ];
 
                                 for (key, action) in shortcuts {
                                     ui.horizontal(|ui| {
                                         ui.add_space(8.0);
-                                        Frame {
-                                            fill: Color32::from_rgb(40, 45, 65),
-                                            corner_radius: CornerRadius::same(4),
-                                            inner_margin: Margin::symmetric(8, 4),
-                                            ..Default::default()
-                                        }
-                                        .show(ui, |ui| {
+                                        theme.chip_frame().show(ui, |ui| {
                                             ui.label(RichText::new(key)
-                                                .size(11.5)
+                                                .size(theme.text.small)
                                                 .monospace()
-                                                .color(BLUE_HIGHLIGHT));
+                                                .color(colors.accent));
                                         });
                                         ui.add_space(12.0);
                                         ui.label(RichText::new(action)
-                                            .size(12.0)
-                                            .color(Color32::from_rgb(180, 180, 200)));
+                                            .size(theme.text.body)
+                                            .color(colors.text_secondary));
                                     });
                                     ui.add_space(6.0);
                                 }
@@ -157,7 +140,7 @@ impl eframe::App for InfoApp {
                             ui.add_space(20.0);
 
                             // Special commands
-                            self.render_section_centered(ui, "Special Commands", 520.0, |ui| {
+                            self.render_section_centered(ui, &theme, "Special Commands", 520.0, |ui| {
                                 let commands = vec![
                                     ("@history", "View search history"),
                                     ("@settings", "Open settings panel"),
@@ -167,22 +150,16 @@ impl eframe::App for InfoApp {
                                 for (cmd, desc) in commands {
                                     ui.horizontal(|ui| {
                                         ui.add_space(8.0);
-                                        Frame {
-                                            fill: Color32::from_rgb(40, 45, 65),
-                                            corner_radius: CornerRadius::same(4),
-                                            inner_margin: Margin::symmetric(8, 4),
-                                            ..Default::default()
-                                        }
-                                        .show(ui, |ui| {
+                                        theme.chip_frame().show(ui, |ui| {
                                             ui.label(RichText::new(cmd)
-                                                .size(11.5)
+                                                .size(theme.text.small)
                                                 .monospace()
-                                                .color(BLUE_HIGHLIGHT));
+                                                .color(colors.accent));
                                         });
                                         ui.add_space(12.0);
                                         ui.label(RichText::new(desc)
-                                            .size(12.0)
-                                            .color(Color32::from_rgb(180, 180, 200)));
+                                            .size(theme.text.body)
+                                            .color(colors.text_secondary));
                                     });
                                     ui.add_space(6.0);
                                 }
@@ -191,14 +168,14 @@ impl eframe::App for InfoApp {
                             ui.add_space(20.0);
 
                             // System info
-                            self.render_section_centered(ui, "System Information", 520.0, |ui| {
+                            self.render_section_centered(ui, &theme, "System Information", 520.0, |ui| {
                                 ui.horizontal(|ui| {
                                     ui.add_space(8.0);
-                                    self.render_sys_info_item(ui, "Platform", std::env::consts::OS);
+                                    self.render_sys_info_item(ui, &theme, "Platform", std::env::consts::OS);
                                     ui.add_space(20.0);
-                                    self.render_sys_info_item(ui, "Architecture", std::env::consts::ARCH);
+                                    self.render_sys_info_item(ui, &theme, "Architecture", std::env::consts::ARCH);
                                     ui.add_space(20.0);
-                                    self.render_sys_info_item(ui, "Framework", "egui/eframe");
+                                    self.render_sys_info_item(ui, &theme, "Framework", "egui/eframe");
                                 });
                             });
 
@@ -206,8 +183,8 @@ impl eframe::App for InfoApp {
 
                             // Footer
                             ui.label(RichText::new("🦀 Built with Rust")
-                                .size(11.5)
-                                .color(Color32::from_rgb(130, 130, 145)));
+                                .size(theme.text.small)
+                                .color(colors.text_faint));
                             
                             ui.add_space(12.0); // Extra space at bottom
                         });
@@ -226,38 +203,27 @@ impl InfoApp {
     fn render_section_centered<R>(
         &self,
         ui: &mut egui::Ui,
+        theme: &Theme,
         title: &str,
         max_width: f32,
         content: impl FnOnce(&mut egui::Ui) -> R,
     ) -> R {
-        Frame {
-            fill: SECTION_BG,
-            corner_radius: CornerRadius::same(8),
-            inner_margin: Margin::same(16),
-            stroke: egui::Stroke::new(1.0, SECTION_BORDER),
-            ..Default::default()
-        }
-        .show(ui, |ui| {
+        theme.section_frame().show(ui, |ui| {
             ui.set_max_width(max_width);
-            ui.label(RichText::new(title)
-                .size(15.0)
-                .strong()
-                .color(BLUE_HIGHLIGHT));
+            ui.label(theme.section_title(title));
             ui.add_space(12.0);
             content(ui)
         })
         .inner
     }
 
-    fn render_sys_info_item(&self, ui: &mut egui::Ui, label: &str, value: &str) {
+    fn render_sys_info_item(&self, ui: &mut egui::Ui, theme: &Theme, label: &str, value: &str) {
         ui.vertical(|ui| {
-            ui.label(RichText::new(label)
-                .size(11.0)
-                .color(Color32::from_rgb(140, 140, 155)));
+            ui.label(theme.note(label));
             ui.label(RichText::new(value)
-                .size(12.5)
+                .size(theme.text.body)
                 .strong()
-                .color(Color32::from_rgb(200, 200, 215)));
+                .color(theme.colors.text_secondary));
         });
     }
 }
\ No newline at end of file
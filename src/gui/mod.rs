@@ -1,4 +1,6 @@
 pub mod info;
 pub mod history;
+pub mod icons;
 pub mod search_bar;
-pub mod settings;
\ No newline at end of file
+pub mod settings;
+pub mod theme;
\ No newline at end of file
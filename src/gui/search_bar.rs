@@ -1,211 +1,631 @@
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use eframe::egui;
-use egui::{Frame, CornerRadius, Color32, Margin};
-use crate::utils::{execute_action::execute_action, search::perform_search, utils::SearchResult, window_manger::WindowManagerIntegration};
+use egui::{Frame, Margin, ScrollArea};
+use crate::gui::{icons::IconCache, theme::{self, Theme}};
+use crate::utils::{clipboard_history, daemon::{DaemonCommand, DaemonServer}, execute_action::execute_action, path_completion, paths, search::{ProviderRegistry, SearchUpdate, SearchWorker}, live_settings, settings_manager::{Settings, DEFAULT_FONT_SIZE}, utils::{ActionType, SearchResult}, window_manger::WindowManagerIntegration};
+
+// Rows grow beyond this when the theme's text needs more room
+const MIN_ROW_HEIGHT: f32 = 48.0;
+const ROW_SPACING: f32 = 4.0;
+const ICON_SIZE: f32 = 28.0;
+// Time for the WM to map the window again before it is floated and moved
+const SHOW_SETUP_DELAY: Duration = Duration::from_millis(40);
+
+const QUICK_SELECT_KEYS: [egui::Key; 9] = [
+    egui::Key::Num1, egui::Key::Num2, egui::Key::Num3,
+    egui::Key::Num4, egui::Key::Num5, egui::Key::Num6,
+    egui::Key::Num7, egui::Key::Num8, egui::Key::Num9,
+];
 
 pub struct QuickSearchApp {
     search_query: String,
     results: Vec<SearchResult>,
+    worker: SearchWorker,
+    // Generation of the search `results` came from
+    results_generation: u64,
+    results_complete: bool,
+    // Enter was pressed before the search finished, run the top result once it has
+    execute_when_complete: bool,
+    icons: IconCache,
     selected_index: usize,
+    // Id of the query field, to move its cursor after completing a path
+    input_id: Option<egui::Id>,
+    // Set when the selection moved by keyboard, so the list scrolls to it
+    scroll_to_selected: bool,
+    max_visible_rows: usize,
+    // Taken from the live settings every frame
+    settings: Arc<Settings>,
+    window_size: egui::Vec2,
+    // Bottom edge of the query field in the last frame
+    search_bar_bottom: f32,
     first_frame: bool,
     wm_integration: Option<WindowManagerIntegration>,
-    initial_setup_done: bool
+    initial_setup_done: bool,
+    // Only set in daemon mode, where closing hides the window instead
+    daemon: Option<Receiver<DaemonCommand>>,
+    visible: bool,
+    shown_at: Option<Instant>,
 }
 
 impl QuickSearchApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let settings = live_settings::current();
+        let ctx = cc.egui_ctx.clone();
+        // Repaint when the settings change on disk, so they apply without reopening the bar
+        let repaint = cc.egui_ctx.clone();
+        live_settings::subscribe(move || repaint.request_repaint());
+        // The UI scale comes from the settings only
+        cc.egui_ctx.options_mut(|options| options.zoom_with_keyboard = false);
+
         Self {
             search_query: String::new(),
             results: Vec::new(),
+            worker: SearchWorker::new(ProviderRegistry::new(), move || ctx.request_repaint()),
+            results_generation: 0,
+            results_complete: true,
+            execute_when_complete: false,
+            icons: IconCache::new(),
             selected_index: 0,
+            input_id: None,
+            scroll_to_selected: false,
+            max_visible_rows: settings.window.max_visible_rows.max(1),
+            settings,
+            window_size: egui::Vec2::ZERO,
+            search_bar_bottom: 0.0,
             first_frame: true,
             wm_integration: Some(WindowManagerIntegration::new()),
-            initial_setup_done: false
+            initial_setup_done: false,
+            daemon: None,
+            visible: true,
+            shown_at: None,
+        }
+    }
+
+    /// Starts hidden and waits for show/hide/toggle commands from `server`
+    pub fn with_daemon(cc: &eframe::CreationContext<'_>, server: DaemonServer) -> Self {
+        let ctx = cc.egui_ctx.clone();
+        let receiver = server.listen(move || ctx.request_repaint());
+        // Only a daemon runs long enough to see what gets copied
+        clipboard_history::start_watcher();
+
+        Self {
+            daemon: Some(receiver),
+            visible: false,
+            // Positioning happens on every show instead
+            initial_setup_done: true,
+            ..Self::new(cc)
         }
     }
-    
+
+    fn handle_daemon_commands(&mut self, ctx: &egui::Context) {
+        let Some(receiver) = &self.daemon else {
+            return;
+        };
+        let commands: Vec<DaemonCommand> = receiver.try_iter().collect();
+
+        for command in commands {
+            match command {
+                DaemonCommand::Show => self.show(ctx),
+                DaemonCommand::Hide => self.close(ctx),
+                DaemonCommand::Toggle if self.visible => self.close(ctx),
+                DaemonCommand::Toggle => self.show(ctx),
+            }
+        }
+    }
+
+    fn show(&mut self, ctx: &egui::Context) {
+        if !self.visible {
+            self.window_size = egui::Vec2::ZERO;
+            self.visible = true;
+            self.shown_at = Some(Instant::now());
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+        }
+
+        self.first_frame = true;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+    }
+
+    // Hides the window when running as a daemon, exits otherwise
+    fn close(&mut self, ctx: &egui::Context) {
+        self.worker.cancel();
+        self.search_query.clear();
+        self.results.clear();
+        self.results_complete = true;
+        self.execute_when_complete = false;
+        self.selected_index = 0;
+
+        if self.daemon.is_some() {
+            self.visible = false;
+            self.shown_at = None;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        } else {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+    }
+
+    // The previous results stay on screen until the first update for the new query arrives
     fn search(&mut self) {
+        self.execute_when_complete = false;
+
         if self.search_query.trim().is_empty() {
+            self.worker.cancel();
             self.results.clear();
+            self.results_complete = true;
+            self.selected_index = 0;
             return;
         }
-        
-        self.results = perform_search(&self.search_query);
-        self.selected_index = 0;
+
+        self.worker.search(&self.search_query);
+        self.results_complete = false;
+    }
+
+    fn apply_search_update(&mut self, ctx: &egui::Context, update: SearchUpdate) {
+        // A new query starts at the top, later updates keep the selected result selected
+        let selected = if update.generation != self.results_generation {
+            None
+        } else {
+            self.results.get(self.selected_index).map(|result| (result.title.clone(), result.subtitle.clone()))
+        };
+
+        self.results = update.results;
+        self.results_generation = update.generation;
+        self.results_complete = update.complete;
+        self.selected_index = selected
+            .and_then(|(title, subtitle)| {
+                self.results.iter().position(|result| result.title == title && result.subtitle == subtitle)
+            })
+            .unwrap_or(0);
+        self.scroll_to_selected = true;
+
+        if update.complete && std::mem::take(&mut self.execute_when_complete) {
+            self.execute_selected(ctx);
+        }
     }
-    
+
     fn execute_selected(&mut self, ctx: &egui::Context) {
         if self.selected_index < self.results.len() {
             let result = &self.results[self.selected_index];
-            
+
             execute_action(result, &self.search_query);
-            
-            self.search_query.clear();
-            self.results.clear();
-            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+
+            self.close(ctx);
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected_index = index;
+        self.scroll_to_selected = true;
+    }
+
+    fn set_query(&mut self, ctx: &egui::Context, query: String) {
+        self.search_query = query;
+        self.search();
+
+        if let Some(id) = self.input_id {
+            if let Some(mut state) = egui::TextEdit::load_state(ctx, id) {
+                let end = egui::text::CCursor::new(self.search_query.chars().count());
+                state.cursor.set_char_range(Some(egui::text::CCursorRange::one(end)));
+                state.store(ctx, id);
+            }
+        }
+    }
+
+    fn cursor_at_end(&self, ctx: &egui::Context) -> bool {
+        let Some(state) = self.input_id.and_then(|id| egui::TextEdit::load_state(ctx, id)) else {
+            return true;
+        };
+        state
+            .cursor
+            .char_range()
+            .is_none_or(|range| range.is_empty() && range.primary.index == self.search_query.chars().count())
+    }
+
+    // Path of the selected result if it is a folder the query can descend into
+    fn selected_dir(&self) -> Option<std::path::PathBuf> {
+        match &self.results.get(self.selected_index)?.action {
+            ActionType::OpenPath(path) if path.is_dir() => Some(path.clone()),
+            _ => None,
+        }
+    }
+
+    // Ctrl+P pins or unpins the selected clipboard entry, Shift+Delete removes it
+    fn handle_clipboard_keys(&mut self, ui: &mut egui::Ui) {
+        let Some(ActionType::CopyClip(id)) = self.results.get(self.selected_index).map(|result| &result.action) else {
+            return;
+        };
+
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::P)) {
+            clipboard_history::toggle_pin(id);
+            self.search();
+        } else if ui.input_mut(|i| i.consume_key(egui::Modifiers::SHIFT, egui::Key::Delete)) {
+            clipboard_history::remove(id);
+            self.search();
         }
     }
+
+    // Tab completes the typed path as far as it is unambiguous, or enters the selected folder.
+    // Right arrow at the end of the query enters the selected folder.
+    fn handle_path_completion(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        if !paths::looks_like_path(&self.search_query) {
+            return;
+        }
+
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab)) {
+            if let Some(completed) = path_completion::complete(&self.search_query) {
+                self.set_query(ctx, completed);
+            } else if let Some(dir) = self.selected_dir() {
+                self.set_query(ctx, path_completion::descend(&self.search_query, &dir));
+            }
+            return;
+        }
+
+        if self.cursor_at_end(ctx) {
+            if let Some(dir) = self.selected_dir() {
+                if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowRight)) {
+                    self.set_query(ctx, path_completion::descend(&self.search_query, &dir));
+                }
+            }
+        }
+    }
+
+    fn handle_keyboard(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.close(ctx);
+        }
+
+        if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            if self.results_complete {
+                self.execute_selected(ctx);
+            } else {
+                self.execute_when_complete = true;
+            }
+        }
+
+        self.handle_path_completion(ui, ctx);
+        self.handle_clipboard_keys(ui);
+
+        if self.results.is_empty() {
+            return;
+        }
+
+        let last = self.results.len() - 1;
+        let page = self.max_visible_rows;
+
+        // Consume navigation keys so the text field does not move its cursor as well
+        let pressed = |ui: &mut egui::Ui, key: egui::Key| {
+            ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, key))
+        };
+
+        if pressed(ui, egui::Key::ArrowDown) {
+            self.select(if self.selected_index < last { self.selected_index + 1 } else { 0 });
+        }
+
+        if pressed(ui, egui::Key::ArrowUp) {
+            self.select(if self.selected_index > 0 { self.selected_index - 1 } else { last });
+        }
+
+        if pressed(ui, egui::Key::PageDown) {
+            self.select((self.selected_index + page).min(last));
+        }
+
+        if pressed(ui, egui::Key::PageUp) {
+            self.select(self.selected_index.saturating_sub(page));
+        }
+
+        if pressed(ui, egui::Key::Home) {
+            self.select(0);
+        }
+
+        if pressed(ui, egui::Key::End) {
+            self.select(last);
+        }
+
+        // Ctrl+1..9 runs the n-th result directly
+        for (index, key) in QUICK_SELECT_KEYS.iter().enumerate() {
+            if index <= last && ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, *key)) {
+                self.selected_index = index;
+                self.execute_selected(ctx);
+                return;
+            }
+        }
+    }
+
+    // Picks up settings saved since the last frame and searches again with them
+    fn apply_settings(&mut self) {
+        let settings = live_settings::current();
+        if Arc::ptr_eq(&settings, &self.settings) {
+            return;
+        }
+
+        self.max_visible_rows = settings.window.max_visible_rows.max(1);
+        self.settings = settings;
+        if !self.search_query.trim().is_empty() {
+            self.search();
+        }
+    }
+
+    // Grows or shrinks the window so it fits the search bar plus the visible rows
+    fn fit_window_to_results(&mut self, ctx: &egui::Context, theme: &Theme) {
+        let visible_rows = self.results.len().min(self.max_visible_rows);
+        let height = self.search_bar_bottom + list_height(ctx, theme, visible_rows) + theme.padding.panel as f32;
+
+        let size = egui::vec2(self.settings.window.clamped_width(), height).round();
+        if size != self.window_size {
+            self.window_size = size;
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+        }
+    }
+
+    // Size of the window in screen pixels once all visible rows are filled, so a centered window
+    // doesn't have to move while results come and go
+    fn placement_size(&self, ctx: &egui::Context, theme: &Theme) -> (i32, i32) {
+        let height = self.search_bar_bottom + list_height(ctx, theme, self.max_visible_rows) + theme.padding.panel as f32;
+        // In physical pixels, the window manager divides by the scale of the monitor it picks
+        let pixels_per_point = ctx.pixels_per_point();
+        (
+            (self.settings.window.clamped_width() * pixels_per_point).round() as i32,
+            (height * pixels_per_point).round() as i32,
+        )
+    }
+}
+
+// Applies the window settings that live in egui itself, does nothing while they stay the same
+fn apply_window_settings(ctx: &egui::Context, settings: &Settings) {
+    let ui_scale = settings.window.clamped_scale();
+    if ctx.zoom_factor() != ui_scale {
+        ctx.set_zoom_factor(ui_scale);
+    }
+    theme::apply_font(ctx, &settings.window.font_family);
+}
+
+// Height of the result list below the search bar
+fn list_height(ctx: &egui::Context, theme: &Theme, rows: usize) -> f32 {
+    if rows == 0 {
+        return 0.0;
+    }
+    // Gap, separator and spacing between the search bar and the list
+    12.0 + rows as f32 * (row_height(ctx, theme) + ROW_SPACING)
+}
+
+// Height of a result row, with room for its icon or its title and subtitle
+fn row_height(ctx: &egui::Context, theme: &Theme) -> f32 {
+    // Title and subtitle are 2.0 apart
+    let text = ctx.fonts_mut(|fonts| {
+        fonts.row_height(&egui::FontId::proportional(theme.text.title))
+            + fonts.row_height(&egui::FontId::proportional(theme.text.small))
+    }) + 2.0;
+    let padding = 2.0 * theme.padding.row[1] as f32;
+    (text.max(ICON_SIZE) + padding).max(MIN_ROW_HEIGHT)
 }
 
 impl eframe::App for QuickSearchApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        const BLUE_HIGHLIGHT: Color32 = Color32::from_rgb(50, 140, 255);
+        self.handle_daemon_commands(ctx);
+        self.apply_settings();
+        apply_window_settings(ctx, &self.settings);
+        let font_scale = self.settings.window.font_size.max(1.0) / DEFAULT_FONT_SIZE;
+        let theme = theme::apply(ctx, &self.settings.theme).with_text_scale(font_scale);
+        let colors = &theme.colors;
 
-        if !self.initial_setup_done {
-            if let Some(ref mut wm) = self.wm_integration {
-                let _ = wm.setup_launcher_window("Quick Search", 500);
-                self.initial_setup_done = true;
+        if let Some(update) = self.worker.poll() {
+            self.apply_search_update(ctx, update);
+        }
+
+        // Closing the daemon's window from the WM only hides it
+        if self.daemon.is_some() && ctx.input(|i| i.viewport().close_requested()) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.close(ctx);
+        }
+
+        if !self.visible {
+            // eframe unhides the window once its first frame is painted, so hide it again
+            if ctx.cumulative_frame_nr() < 2 {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+                ctx.request_repaint();
             }
+            return;
         }
 
         egui::CentralPanel::default()
             .frame(Frame {
-                fill: Color32::from_rgba_premultiplied(20, 20, 24, 250),
-                inner_margin: Margin::same(8),
+                fill: colors.panel,
+                inner_margin: Margin::same(theme.padding.panel),
                 outer_margin: Margin::same(0),
                 shadow: egui::epaint::Shadow {
                     offset: [0, 4],
                     blur: 16,
                     spread: 0,
-                    color: Color32::from_rgba_premultiplied(0, 0, 0, 100),
+                    color: colors.shadow,
                 },
-                stroke: egui::Stroke::new(1.0, Color32::from_rgba_premultiplied(60, 60, 70, 180)),
+                stroke: egui::Stroke::new(1.0, colors.border),
                 ..Frame::default()
             })
             .show(ctx, |ui| {
                 ui.style_mut().spacing.item_spacing = egui::vec2(0.0, 4.0);
-                
-                let search_frame = Frame {
-                    fill: Color32::from_rgba_premultiplied(25, 25, 30, 255), 
-                    corner_radius: CornerRadius::same(0),
-                    inner_margin: Margin::symmetric(12, 8),
-                    stroke: egui::Stroke::new(2.0, BLUE_HIGHLIGHT),
-                    ..Frame::default()
-                };
-                
-                let original_style = ui.style().clone(); 
-                let mut custom_visuals = ui.style().visuals.clone();
-                custom_visuals.selection.bg_fill = Color32::from_rgba_premultiplied(50, 140, 255, 100);
-                custom_visuals.widgets.active.fg_stroke.color = BLUE_HIGHLIGHT;
-                ui.style_mut().visuals = custom_visuals;
 
-                // Keyboard shortcuts
-                if ui.input(|i| i.key_pressed(egui::Key::Escape)) { 
-                    self.search_query.clear();
-                    self.results.clear();
-                    ctx.send_viewport_cmd(egui::ViewportCommand::Close); 
-                }
-                
-                if ui.input(|i| i.key_pressed(egui::Key::Enter)) { 
-                    self.execute_selected(ctx); 
-                }
-                
-                if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
-                    ui.input_mut(|i| {
-                        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown)
-                    });
+                let search_frame = theme.input_frame();
 
-                    if self.selected_index < self.results.len().saturating_sub(1) { 
-                        self.selected_index += 1; 
-                    } else if !self.results.is_empty() { 
-                        self.selected_index = 0; 
-                    }
-                }
-                
-                if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
-                    ui.input_mut(|i| {
-                        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp)
-                    });
+                // Keyboard shortcuts
+                self.handle_keyboard(ui, ctx);
 
-                    if self.selected_index > 0 { 
-                        self.selected_index = self.selected_index.saturating_sub(1); 
-                    } else if !self.results.is_empty() { 
-                        self.selected_index = self.results.len().saturating_sub(1); 
-                    }
-                }
+                // A query mode prefix gets a chip naming its provider, and the provider's hint until more is typed
+                let registry = self.worker.registry();
+                let mode = registry.mode_for(&self.search_query, &self.settings).map(|(index, rest)| {
+                    let provider = &registry.providers()[index];
+                    (provider.icon(), provider.name(), rest.is_empty().then(|| provider.hint()))
+                });
 
-                let search_response = search_frame.show(ui, |ui| {
+                let search_frame_response = search_frame.show(ui, |ui| {
                     ui.set_width(ui.available_width());
-                    let response = ui.add(
-                        egui::TextEdit::singleline(&mut self.search_query)
+                    ui.horizontal(|ui| {
+                        if let Some((icon, name, _)) = mode {
+                            theme.chip_frame().show(ui, |ui| {
+                                ui.label(egui::RichText::new(format!("{} {}", icon, name))
+                                    .size(theme.text.body)
+                                    .color(colors.accent));
+                            });
+                            ui.add_space(8.0);
+                        }
+
+                        let output = egui::TextEdit::singleline(&mut self.search_query)
                             .id_source("quick_search_input")
-                            .font(egui::TextStyle::Heading)
+                            .font(egui::FontId::proportional(theme.text.query))
                             .hint_text("🔎 Search or type @info for help")
                             .desired_width(f32::INFINITY)
-                            .frame(false) 
+                            .frame(false)
                             .lock_focus(true)
                             .cursor_at_end(true)
-                    );
-                    response
-                }).inner;
-                
-                ui.set_style(original_style); 
-                
-                if self.first_frame { 
+                            .show(ui);
+
+                        if let Some((_, _, Some(hint))) = mode {
+                            let typed = output.galley.size();
+                            ui.painter_at(output.response.rect).text(
+                                output.galley_pos + egui::vec2(typed.x + 8.0, typed.y / 2.0),
+                                egui::Align2::LEFT_CENTER,
+                                hint,
+                                egui::FontId::proportional(theme.text.title),
+                                colors.text_faint,
+                            );
+                        }
+
+                        output.response
+                    })
+                    .inner
+                });
+                let search_response = search_frame_response.inner;
+                self.input_id = Some(search_response.id);
+                self.search_bar_bottom = search_frame_response.response.rect.bottom();
+
+                if self.first_frame {
                     search_response.request_focus();
-                    self.first_frame = false; 
+                    self.first_frame = false;
                 }
-                
-                if search_response.changed() { 
-                    self.search(); 
+
+                if search_response.changed() {
+                    self.search();
                 }
-                
-                ui.add_space(4.0);
-                
-                Frame {
-                    fill: Color32::TRANSPARENT,
-                    ..Frame::default()
+
+                self.fit_window_to_results(ctx, &theme);
+
+                if self.results.is_empty() {
+                    return;
                 }
-                .show(ui, |ui| {
-                    ui.set_height(70.0); // Fixed height for result area
-                    
-                    if !self.results.is_empty() {
-                        ui.separator();
-                        ui.add_space(4.0);
-
-                        if let Some(result) = self.results.get(self.selected_index) {
-                            let frame = Frame {
-                                fill: Color32::from_rgba_premultiplied(80, 85, 110, 240),
-                                corner_radius: CornerRadius::same(6),
-                                inner_margin: Margin::symmetric(10, 8),
-                                ..Frame::default()
-                            };
+
+                ui.add_space(4.0);
+                ui.separator();
+                ui.add_space(4.0);
+
+                let visible_rows = self.results.len().min(self.max_visible_rows);
+                let row_height = row_height(ctx, &theme);
+                let pointer_moved = ui.input(|i| i.pointer.delta() != egui::Vec2::ZERO);
+                let mut clicked = None;
+                let mut hovered = None;
+                let icons = &mut self.icons;
+
+                ScrollArea::vertical()
+                    .max_height(visible_rows as f32 * (row_height + ROW_SPACING))
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        ui.spacing_mut().item_spacing.y = ROW_SPACING;
+
+                        for (idx, result) in self.results.iter().enumerate() {
+                            let is_selected = idx == self.selected_index;
+
+                            let frame = theme.row_frame(is_selected);
 
                             let response = frame.show(ui, |ui| {
                                 ui.set_width(ui.available_width());
+                                ui.set_height(row_height - frame.inner_margin.sum().y);
                                 ui.horizontal(|ui| {
-
-                                    ui.label(egui::RichText::new(&result.icon)
-                                        .size(14.0)
-                                        .color(BLUE_HIGHLIGHT)
-                                        .monospace());
+                                    match icons.get(ctx, &result.icon_names, ICON_SIZE) {
+                                        Some(texture) => {
+                                            ui.add(egui::Image::new(&texture)
+                                                .fit_to_exact_size(egui::vec2(ICON_SIZE, ICON_SIZE)));
+                                        }
+                                        None => {
+                                            ui.label(egui::RichText::new(&result.icon)
+                                                .size(theme.text.title)
+                                                .color(colors.accent)
+                                                .monospace());
+                                        }
+                                    }
                                     ui.add_space(8.0);
                                     ui.vertical(|ui| {
                                         ui.spacing_mut().item_spacing.y = 2.0;
                                         ui.label(egui::RichText::new(&result.title)
-                                            .size(14.0)
-                                            .color(Color32::from_rgb(240, 240, 245)));
+                                            .size(theme.text.title)
+                                            .color(colors.text));
                                         ui.label(egui::RichText::new(&result.subtitle)
-                                            .size(11.0)
-                                            .color(Color32::from_rgb(150, 150, 160)));
+                                            .size(theme.text.small)
+                                            .color(colors.text_muted));
                                     });
+
+                                    if idx < QUICK_SELECT_KEYS.len() {
+                                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                            ui.label(egui::RichText::new(format!("Ctrl+{}", idx + 1))
+                                                .size(theme.text.tiny)
+                                                .color(colors.text_faint));
+                                        });
+                                    }
                                 });
                             });
 
                             let rect = response.response.rect;
-                            let hover_response = ui.interact(
+                            let row_response = ui.interact(
                                 rect,
-                                egui::Id::new("single_result_interaction"),
+                                egui::Id::new(("result_row", idx)),
                                 egui::Sense::click(),
                             );
 
-                            if hover_response.clicked() {
-                                self.execute_selected(ctx);
+                            // Only follow the mouse when it actually moves, not when the list scrolls under it
+                            if row_response.hovered() && pointer_moved {
+                                hovered = Some(idx);
+                            }
+
+                            if row_response.clicked() {
+                                clicked = Some(idx);
+                            }
+
+                            if is_selected && self.scroll_to_selected {
+                                row_response.scroll_to_me(None);
                             }
                         }
-                    }
-                });
+                    });
+
+                self.scroll_to_selected = false;
+
+                if let Some(idx) = hovered {
+                    self.selected_index = idx;
+                }
+
+                if let Some(idx) = clicked {
+                    self.selected_index = idx;
+                    self.execute_selected(ctx);
+                }
             });
-        
-        ctx.request_repaint();
+
+        // Placed after the first frame is laid out, when the height of the search bar is known
+        if !self.initial_setup_done {
+            let size = self.placement_size(ctx, &theme);
+            if let Some(ref mut wm) = self.wm_integration {
+                let _ = wm.setup_launcher_window("Quick Search", &self.settings.window, size);
+                self.initial_setup_done = true;
+            }
+        }
+
+        if self.shown_at.is_some_and(|shown_at| shown_at.elapsed() >= SHOW_SETUP_DELAY) {
+            self.shown_at = None;
+            let size = self.placement_size(ctx, &theme);
+            if let Some(ref mut wm) = self.wm_integration {
+                let _ = wm.show_launcher_window("Quick Search", &self.settings.window, size);
+            }
+        }
+
+        if self.visible {
+            ctx.request_repaint();
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,27 +1,81 @@
 use eframe::egui;
-use egui::{Align, Color32, CornerRadius, Frame, Margin, ScrollArea, TextEdit};
-use crate::utils::{helpers::helpers::is_command_available, settings_manager::{Settings, SettingsManager}};
-
-const BLUE_HIGHLIGHT: Color32 = Color32::from_rgb(50, 140, 255);
-const BORDER_NORMAL: Color32 = Color32::from_rgb(60, 60, 70);
-const BORDER_INVALID: Color32 = Color32::from_rgb(200, 60, 60);
+use egui::{Align, Frame, Margin, RichText, ScrollArea, TextEdit};
+use crate::gui::theme::{self, Theme, SYSTEM_THEME};
+use crate::utils::{helpers::helpers::is_command_available, search::ProviderRegistry, settings_manager::{QueryMode, SearchEngine, Settings, SettingsManager, SettingsProblems, WindowAnchor, WindowSettings}};
 
 pub struct SettingsApp {
     settings: Settings,
     settings_manager: SettingsManager,
+    registry: ProviderRegistry,
+    // Comma separated form of `settings.file_index.roots` while it is being edited
+    file_index_roots: String,
+    // Same for `settings.clipboard.excluded_types`
+    clipboard_excluded: String,
+    // What could not be read from the settings file, shown until dismissed
+    problems: SettingsProblems,
+    // Theme under the pointer in the theme picker, shown instead of the saved one while hovered
+    previewed_theme: Option<String>,
 }
 
 impl SettingsApp {
     pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
         let settings_manager = SettingsManager::new();
-        let settings = settings_manager.load_settings();
+        let (settings, problems) = settings_manager.load_settings_checked();
         
         Self {
+            file_index_roots: settings.file_index.roots.join(", "),
+            clipboard_excluded: settings.clipboard.excluded_types.join(", "),
             settings,
             settings_manager,
+            registry: ProviderRegistry::new(),
+            problems,
+            previewed_theme: None,
         }
     }
     
+    // Lists the problems of the settings file with where it was backed up
+    fn render_problems(&mut self, ui: &mut egui::Ui, theme: &Theme) {
+        if self.problems.is_empty() {
+            return;
+        }
+
+        Frame {
+            fill: theme.colors.danger_background,
+            stroke: egui::Stroke::new(1.5, theme.colors.danger),
+            ..theme.section_frame()
+        }
+        .show(ui, |ui| {
+            ui.set_max_width(520.0);
+
+            ui.horizontal(|ui| {
+                ui.label(theme.section_title("⚠  Settings file has problems").color(theme.colors.danger));
+
+                ui.with_layout(egui::Layout::right_to_left(Align::Center), |ui| {
+                    if ui.small_button("✕").on_hover_text("Dismiss").clicked() {
+                        self.problems = SettingsProblems::default();
+                    }
+                });
+            });
+            ui.add_space(6.0);
+
+            for error in &self.problems.errors {
+                ui.label(RichText::new(error)
+                    .size(theme.text.small)
+                    .monospace()
+                    .color(theme.colors.text_secondary));
+            }
+
+            ui.add_space(6.0);
+            let note = match &self.problems.backup {
+                Some(backup) => format!("Everything else was loaded. The original file is kept at {}, changing a setting here saves over it.", backup.display()),
+                None => "Everything else was loaded. Changing a setting here saves over the file.".to_string(),
+            };
+            ui.label(theme.note(&note));
+        });
+
+        ui.add_space(20.0);
+    }
+
     fn save_settings(&mut self) {
         if let Err(e) = self.settings_manager.save_settings(&self.settings) {
             eprintln!("Failed to save settings: {}", e);
@@ -31,26 +85,21 @@ impl SettingsApp {
 
 impl eframe::App for SettingsApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Picking a theme restyles this window right away, hovering one in the picker previews it
+        let theme_name = self.previewed_theme.take().unwrap_or_else(|| self.settings.theme.clone());
+        let theme = theme::apply(ctx, &theme_name);
+
         egui::CentralPanel::default()
-            .frame(Frame {
-                fill: Color32::from_rgb(18, 18, 22),
-                corner_radius: CornerRadius::same(12),
-                inner_margin: Margin::same(20),
-                stroke: egui::Stroke::new(1.5, Color32::from_rgb(60, 60, 70)),
-                ..Frame::default()
-            })
+            .frame(theme.window_frame())
             .show(ctx, |ui| {
                 // Header (No change)
                 ui.horizontal(|ui| {
                     ui.add_space(4.0);
-                    ui.heading(egui::RichText::new("⚙️  Settings")
-                        .size(24.0)
-                        .color(BLUE_HIGHLIGHT)
-                        .strong());
+                    ui.heading(theme.heading("⚙️  Settings"));
                     
                     ui.with_layout(egui::Layout::right_to_left(Align::Center), |ui| {
-                        if ui.add(egui::Button::new(egui::RichText::new("✕").size(18.0))
-                            .fill(Color32::from_rgb(60, 30, 30))
+                        if ui.add(egui::Button::new(RichText::new("✕").size(18.0))
+                            .fill(theme.colors.danger_background)
                             .stroke(egui::Stroke::NONE))
                             .on_hover_text("Close")
                             .clicked() 
@@ -70,58 +119,32 @@ impl eframe::App for SettingsApp {
                     .show(ui, |ui| {
                         ui.vertical_centered(|ui| {
                             let mut settings_changed = false;
-                            Frame {
-                                fill: Color32::from_rgb(28, 28, 32),
-                                corner_radius: CornerRadius::same(8),
-                                inner_margin: Margin::same(16),
-                                stroke: egui::Stroke::new(1.0, Color32::from_rgb(60, 60, 70)),
-                                ..Frame::default()
-                            }
-                            .show(ui, |ui| {
+                            self.render_problems(ui, &theme);
+
+                            theme.section_frame().show(ui, |ui| {
                                 ui.set_max_width(520.0);
                                 
-                                ui.label(egui::RichText::new("Search Features")
-                                    .size(15.0)
-                                    .strong()
-                                    .color(BLUE_HIGHLIGHT));
+                                ui.label(theme.section_title("Search Features"));
                                 ui.add_space(12.0);
 
-                                // Application Search
-                                Self::render_setting_item(ui, &mut settings_changed, 
-                                    &mut self.settings.enable_app_search,
-                                    "🔍  Application Search",
-                                    "Search for installed applications on your system"
-                                );
-
-                                // Web Search
-                                Self::render_setting_item(ui, &mut settings_changed,
-                                    &mut self.settings.enable_web_search,
-                                    "🌐  Web Search",
-                                    "Fallback to web search when no local results found"
-                                );
-
-                                // Math Evaluation
-                                Self::render_setting_item(ui, &mut settings_changed,
-                                    &mut self.settings.enable_math_eval,
-                                    "🔢  Math Evaluation",
-                                    "Evaluate mathematical expressions directly"
-                                );
+                                for provider in self.registry.providers() {
+                                    let mut enabled = provider.is_enabled(&self.settings);
+                                    let mut changed = false;
 
-                                // File Search
-                                Self::render_setting_item(ui, &mut settings_changed,
-                                    &mut self.settings.enable_file_search,
-                                    "📁  File & Folder Search",
-                                    "Search for files and folders by path"
-                                );
+                                    Self::render_setting_item(ui, &theme, &mut changed,
+                                        &mut enabled,
+                                        &format!("{}  {}", provider.icon(), provider.name()),
+                                        provider.description()
+                                    );
 
-                                // Commands
-                                Self::render_setting_item(ui, &mut settings_changed,
-                                    &mut self.settings.enable_run_commands,
-                                    " Run Command",
-                                    "Run commands on a new terminal");
+                                    if changed {
+                                        self.settings.providers.insert(provider.id().to_string(), enabled);
+                                        settings_changed = true;
+                                    }
+                                }
 
                                 // History
-                                Self::render_setting_item(ui, &mut settings_changed,
+                                Self::render_setting_item(ui, &theme, &mut settings_changed,
                                     &mut self.settings.enable_history,
                                     "📜  Search History",
                                     "Save and access your search history"
@@ -130,20 +153,10 @@ impl eframe::App for SettingsApp {
 
                             ui.add_space(20.0);
 
-                            Frame {
-                                fill: Color32::from_rgb(28, 28, 32),
-                                corner_radius: CornerRadius::same(8),
-                                inner_margin: Margin::same(16),
-                                stroke: egui::Stroke::new(1.0, Color32::from_rgb(60, 60, 70)),
-                                ..Frame::default()
-                            }
-                            .show(ui, |ui| {
+                            theme.section_frame().show(ui, |ui| {
                                 ui.set_max_width(520.0);
                                 
-                                ui.label(egui::RichText::new("Editor & Terminal")
-                                    .size(15.0)
-                                    .strong()
-                                    .color(BLUE_HIGHLIGHT));
+                                ui.label(theme.section_title("Editor & Terminal"));
                                 ui.add_space(12.0);
                                 
                                  let terminal_valid = self.settings.terminal_command.is_empty()
@@ -152,14 +165,14 @@ impl eframe::App for SettingsApp {
                                 let editor_valid = self.settings.text_editor_command.is_empty()
                                     || is_command_available(&self.settings.text_editor_command);
 
-                                Self::render_input_setting(ui, &mut settings_changed,
+                                Self::render_input_setting(ui, &theme, &mut settings_changed,
                                     &mut self.settings.terminal_command,
                                     "💻  Terminal Command",
                                     "Command to launch your preferred terminal emulator (e.g., 'alacritty', 'kitty', 'gnome-terminal')",
                                     terminal_valid
                                 );
                                 
-                                Self::render_input_setting(ui, &mut settings_changed,
+                                Self::render_input_setting(ui, &theme, &mut settings_changed,
                                     &mut self.settings.text_editor_command,
                                     "✏️  Default Text Editor",
                                     "Command to launch your preferred text editor (e.g., 'xed', 'nvim', 'subl'). Takes priority over system detection.",
@@ -167,6 +180,213 @@ impl eframe::App for SettingsApp {
                                 );
                             });
 
+                            ui.add_space(20.0);
+
+                            theme.section_frame().show(ui, |ui| {
+                                ui.set_max_width(520.0);
+
+                                ui.label(theme.section_title("Search Engines"));
+                                ui.add_space(4.0);
+                                ui.label(theme.note("Type a keyword before your query to search that site. The selected engine handles plain web searches. Use {query} in the URL for the search terms."));
+                                ui.add_space(12.0);
+
+                                settings_changed |= self.render_search_engines(ui, &theme);
+                            });
+
+                            ui.add_space(20.0);
+
+                            theme.section_frame().show(ui, |ui| {
+                                ui.set_max_width(520.0);
+
+                                ui.label(theme.section_title("Query Modes"));
+                                ui.add_space(4.0);
+                                ui.label(theme.note("Start a query with a prefix to search only one source, e.g. '= 2^10' or '/ report'. Word prefixes need a space after them. Modes of disabled sources are ignored."));
+                                ui.add_space(12.0);
+
+                                settings_changed |= self.render_query_modes(ui, &theme);
+                            });
+
+                            ui.add_space(20.0);
+
+                            theme.section_frame().show(ui, |ui| {
+                                ui.set_max_width(520.0);
+
+                                ui.label(theme.section_title("Interface"));
+                                ui.add_space(12.0);
+
+                                settings_changed |= self.render_theme_picker(ui, &theme);
+                            });
+
+                            ui.add_space(20.0);
+
+                            theme.section_frame().show(ui, |ui| {
+                                ui.set_max_width(520.0);
+
+                                ui.label(theme.section_title("Search Bar"));
+                                ui.add_space(4.0);
+                                ui.label(theme.note("Size and font apply right away, the position the next time the search bar opens"));
+                                ui.add_space(12.0);
+
+                                let window = &mut self.settings.window;
+
+                                Self::render_slider_setting(ui, &theme, &mut settings_changed,
+                                    &mut window.width,
+                                    300.0..=1200.0,
+                                    "↔  Width",
+                                    "Width of the search bar in pixels, before scaling"
+                                );
+
+                                Self::render_slider_setting(ui, &theme, &mut settings_changed,
+                                    &mut window.max_visible_rows,
+                                    1..=12,
+                                    "📋  Visible Results",
+                                    "Number of result rows shown below the search bar before scrolling"
+                                );
+
+                                Self::render_text_setting(ui, &theme, &mut settings_changed,
+                                    &mut window.font_family,
+                                    "🔤  Font",
+                                    "A font name like 'Inter' or the path of a font file. Leave empty for the built-in font",
+                                    "Built-in font"
+                                );
+
+                                Self::render_slider_setting(ui, &theme, &mut settings_changed,
+                                    &mut window.font_size,
+                                    8.0..=32.0,
+                                    "🔠  Font Size",
+                                    "Size of result titles, the rest of the text follows. 14 keeps the theme's sizes"
+                                );
+
+                                Self::render_slider_setting(ui, &theme, &mut settings_changed,
+                                    &mut window.ui_scale,
+                                    0.5..=3.0,
+                                    "🔍  UI Scale",
+                                    "Scales the whole search bar, for HiDPI screens your desktop doesn't scale"
+                                );
+
+                                settings_changed |= Self::render_placement(ui, &theme, window);
+                            });
+
+                            ui.add_space(20.0);
+
+                            theme.section_frame().show(ui, |ui| {
+                                ui.set_max_width(520.0);
+
+                                ui.label(theme.section_title("File Index"));
+                                ui.add_space(4.0);
+                                ui.label(theme.note("Changes apply the next time Quick Search starts"));
+                                ui.add_space(12.0);
+
+                                let mut roots_changed = false;
+                                Self::render_text_setting(ui, &theme, &mut roots_changed,
+                                    &mut self.file_index_roots,
+                                    "📂  Indexed Folders",
+                                    "Comma separated folders to search for files, e.g. '~, /mnt/data'",
+                                    "~"
+                                );
+                                if roots_changed {
+                                    self.settings.file_index.roots = self.file_index_roots
+                                        .split(',')
+                                        .map(str::trim)
+                                        .filter(|root| !root.is_empty())
+                                        .map(str::to_string)
+                                        .collect();
+                                    settings_changed = true;
+                                }
+
+                                Self::render_slider_setting(ui, &theme, &mut settings_changed,
+                                    &mut self.settings.file_index.max_depth,
+                                    1..=20,
+                                    "🗂  Folder Depth",
+                                    "How many folder levels below each indexed folder are searched"
+                                );
+
+                                Self::render_slider_setting(ui, &theme, &mut settings_changed,
+                                    &mut self.settings.file_index.max_entries,
+                                    10_000..=1_000_000,
+                                    "📏  Index Size",
+                                    "How many files and folders the index holds at most, whatever their size on disk"
+                                );
+
+                                Self::render_setting_item(ui, &theme, &mut settings_changed,
+                                    &mut self.settings.file_index.include_hidden,
+                                    "👁  Hidden Files",
+                                    "Also index files and folders whose name starts with a dot"
+                                );
+                            });
+
+                            ui.add_space(20.0);
+
+                            theme.section_frame().show(ui, |ui| {
+                                ui.set_max_width(520.0);
+
+                                ui.label(theme.section_title("Currency Rates"));
+                                ui.add_space(4.0);
+                                ui.label(theme.note("Conversions like '100 usd to eur' use the last downloaded rates, also offline. Nothing is downloaded until your first conversion, run 'quick_search --refresh-rates' to update them now"));
+                                ui.add_space(12.0);
+
+                                Self::render_text_setting(ui, &theme, &mut settings_changed,
+                                    &mut self.settings.currency.source,
+                                    "💱  Rates Source",
+                                    "A JSON endpoint or a local file with rates, e.g. 'https://api.frankfurter.app/latest' or '~/rates.json'",
+                                    "https://open.er-api.com/v6/latest/USD"
+                                );
+
+                                Self::render_slider_setting(ui, &theme, &mut settings_changed,
+                                    &mut self.settings.currency.refresh_hours,
+                                    0..=168,
+                                    "⏱  Refresh Interval (hours)",
+                                    "How old the rates may get before they are downloaded again, 0 to only refresh manually"
+                                );
+                            });
+
+                            ui.add_space(20.0);
+
+                            theme.section_frame().show(ui, |ui| {
+                                ui.set_max_width(520.0);
+
+                                ui.label(theme.section_title("Clipboard History"));
+                                ui.add_space(4.0);
+                                ui.label(theme.note("Recorded while Quick Search runs with --daemon. Type 'cb' to search it, Ctrl+P pins the selected entry and Shift+Del removes it"));
+                                ui.add_space(12.0);
+
+                                Self::render_setting_item(ui, &theme, &mut settings_changed,
+                                    &mut self.settings.clipboard.enabled,
+                                    "📋  Record Clipboard",
+                                    "Keep a history of copied text"
+                                );
+
+                                Self::render_setting_item(ui, &theme, &mut settings_changed,
+                                    &mut self.settings.clipboard.record_images,
+                                    "🖼  Record Images",
+                                    "Also keep copied images, stored as PNG files in the config folder"
+                                );
+
+                                Self::render_slider_setting(ui, &theme, &mut settings_changed,
+                                    &mut self.settings.clipboard.max_entries,
+                                    10..=1000,
+                                    "🗃  History Size",
+                                    "How many entries are kept, pinned ones are never dropped"
+                                );
+
+                                let mut excluded_changed = false;
+                                Self::render_text_setting(ui, &theme, &mut excluded_changed,
+                                    &mut self.clipboard_excluded,
+                                    "🔒  Excluded Types",
+                                    "Comma separated MIME types that keep a copy out of the history. Password managers like KeePassXC mark secrets with 'x-kde-passwordManagerHint'",
+                                    "x-kde-passwordManagerHint"
+                                );
+                                if excluded_changed {
+                                    self.settings.clipboard.excluded_types = self.clipboard_excluded
+                                        .split(',')
+                                        .map(str::trim)
+                                        .filter(|excluded| !excluded.is_empty())
+                                        .map(str::to_string)
+                                        .collect();
+                                    settings_changed = true;
+                                }
+                            });
+
                             if settings_changed {
                                 self.save_settings();
                             }
@@ -175,19 +395,17 @@ impl eframe::App for SettingsApp {
 
                             // Info footer (No change)
                             Frame {
-                                fill: Color32::from_rgba_unmultiplied(40, 50, 70, 100),
-                                corner_radius: CornerRadius::same(6),
-                                inner_margin: Margin::symmetric(16, 10),
-                                ..Frame::default()
+                                fill: theme.colors.notice,
+                                ..theme.item_frame()
                             }
                             .show(ui, |ui| {
                                 ui.set_max_width(520.0);
                                 ui.horizontal(|ui| {
-                                    ui.label(egui::RichText::new("💾").size(14.0));
+                                    ui.label(RichText::new("💾").size(14.0));
                                     ui.add_space(8.0);
-                                    ui.label(egui::RichText::new("Settings are saved automatically")
-                                        .size(11.5)
-                                        .color(Color32::from_rgb(160, 170, 190)));
+                                    ui.label(RichText::new("Settings are saved automatically and apply to a running search bar right away")
+                                        .size(theme.text.small)
+                                        .color(theme.colors.text_secondary));
                                 });
                             });
 
@@ -206,31 +424,24 @@ impl eframe::App for SettingsApp {
 impl SettingsApp {
     fn render_setting_item(
         ui: &mut egui::Ui,
+        theme: &Theme,
         settings_changed: &mut bool,
         setting: &mut bool,
         title: &str,
         description: &str,
     ) {
-        Frame {
-            fill: Color32::from_rgba_unmultiplied(35, 35, 42, 200),
-            corner_radius: CornerRadius::same(6),
-            inner_margin: Margin::symmetric(14, 12),
-            ..Frame::default()
-        }
-        .show(ui, |ui| {
+        theme.item_frame().show(ui, |ui| {
             ui.horizontal(|ui| {
                 let checkbox_response = ui.checkbox(setting, "");
                 *settings_changed |= checkbox_response.changed();
                 
                 ui.vertical(|ui| {
                     ui.spacing_mut().item_spacing.y = 2.0;
-                    ui.label(egui::RichText::new(title)
-                        .size(13.5)
+                    ui.label(RichText::new(title)
+                        .size(theme.text.title)
                         .strong()
-                        .color(Color32::from_rgb(220, 220, 235)));
-                    ui.label(egui::RichText::new(description)
-                        .size(11.5)
-                        .color(Color32::from_rgb(150, 150, 165)));
+                        .color(theme.colors.text));
+                    ui.label(theme.note(description));
                 });
             });
         });
@@ -239,6 +450,7 @@ impl SettingsApp {
 
     fn render_input_setting(
         ui: &mut egui::Ui,
+        theme: &Theme,
         settings_changed: &mut bool,
         value: &mut String,
         title: &str,
@@ -246,25 +458,18 @@ impl SettingsApp {
         is_valid: bool,
     ) {
         let border = if is_valid || value.is_empty() {
-            BORDER_NORMAL
+            theme.colors.border
         } else {
-            BORDER_INVALID
+            theme.colors.danger
         };
 
         Frame {
-            fill: Color32::from_rgba_unmultiplied(35, 35, 42, 200),
-            corner_radius: CornerRadius::same(6),
-            inner_margin: Margin::symmetric(14, 12),
             stroke: egui::Stroke::new(1.5, border),
-            ..Frame::default()
+            ..theme.item_frame()
         }
         .show(ui, |ui| {
             ui.label(title);
-            ui.label(
-                egui::RichText::new(description)
-                    .size(11.5)
-                    .color(Color32::from_rgb(150, 150, 165)),
-            );
+            ui.label(theme.note(description));
 
             ui.add_space(6.0);
 
@@ -279,13 +484,332 @@ impl SettingsApp {
             if !is_valid && !value.is_empty() {
                 ui.add_space(4.0);
                 ui.label(
-                    egui::RichText::new("Command not found in PATH")
-                        .size(11.0)
-                        .color(BORDER_INVALID),
+                    RichText::new("Command not found in PATH")
+                        .size(theme.text.small)
+                        .color(theme.colors.danger),
                 );
             }
         });
 
         ui.add_space(8.0);
     }
-}
\ No newline at end of file
+
+    // One editable row per engine, returns whether anything changed
+    fn render_search_engines(&mut self, ui: &mut egui::Ui, theme: &Theme) -> bool {
+        let mut changed = false;
+        let mut removed = None;
+        let default_keyword = &mut self.settings.default_search_engine;
+
+        for (index, engine) in self.settings.search_engines.iter_mut().enumerate() {
+            let url_valid = engine.url.contains("{query}");
+
+            Frame {
+                inner_margin: Margin::symmetric(theme.padding.row[0], theme.padding.row[1]),
+                stroke: egui::Stroke::new(1.5, if url_valid { theme.colors.border } else { theme.colors.danger }),
+                ..theme.item_frame()
+            }
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let is_default = *default_keyword == engine.keyword;
+                    if ui.radio(is_default, "").on_hover_text("Use for plain web searches").clicked() {
+                        *default_keyword = engine.keyword.clone();
+                        changed = true;
+                    }
+
+                    changed |= ui.add(TextEdit::singleline(&mut engine.name)
+                        .desired_width(90.0)
+                        .hint_text("Name")).changed();
+
+                    let keyword_response = ui.add(TextEdit::singleline(&mut engine.keyword)
+                        .desired_width(50.0)
+                        .hint_text("Keyword"));
+                    if keyword_response.changed() {
+                        // Keep the default pointing at the same engine
+                        if is_default {
+                            *default_keyword = engine.keyword.clone();
+                        }
+                        changed = true;
+                    }
+
+                    changed |= ui.add(TextEdit::singleline(&mut engine.url)
+                        .desired_width(ui.available_width() - 36.0)
+                        .hint_text("https://example.com/search?q={query}")).changed();
+
+                    if ui.add(egui::Button::new("🗑").fill(theme.colors.danger_background))
+                        .on_hover_text("Remove")
+                        .clicked()
+                    {
+                        removed = Some(index);
+                    }
+                });
+            });
+            ui.add_space(6.0);
+        }
+
+        if let Some(index) = removed {
+            self.settings.search_engines.remove(index);
+            changed = true;
+        }
+
+        if ui.button("➕  Add search engine").clicked() {
+            self.settings.search_engines.push(SearchEngine {
+                name: String::new(),
+                keyword: String::new(),
+                url: String::new(),
+            });
+            changed = true;
+        }
+
+        changed
+    }
+
+    // One row per mode with its prefix and provider, returns whether anything changed
+    fn render_query_modes(&mut self, ui: &mut egui::Ui, theme: &Theme) -> bool {
+        let mut changed = false;
+        let mut removed = None;
+        let providers = self.registry.providers();
+
+        for (index, mode) in self.settings.query_modes.iter_mut().enumerate() {
+            let provider = providers.iter().find(|provider| provider.id() == mode.provider);
+            let valid = !mode.prefix.trim().is_empty() && provider.is_some();
+
+            Frame {
+                inner_margin: Margin::symmetric(theme.padding.row[0], theme.padding.row[1]),
+                stroke: egui::Stroke::new(1.5, if valid { theme.colors.border } else { theme.colors.danger }),
+                ..theme.item_frame()
+            }
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    changed |= ui.add(TextEdit::singleline(&mut mode.prefix)
+                        .desired_width(50.0)
+                        .hint_text("Prefix")).changed();
+
+                    let selected = provider.map_or("Choose a source", |provider| provider.name());
+                    egui::ComboBox::from_id_salt(("query_mode", index))
+                        .selected_text(selected)
+                        .width(ui.available_width() - 36.0)
+                        .show_ui(ui, |ui| {
+                            for provider in providers {
+                                let label = format!("{}  {}", provider.icon(), provider.name());
+                                if ui.selectable_label(mode.provider == provider.id(), label).clicked() {
+                                    mode.provider = provider.id().to_string();
+                                    changed = true;
+                                }
+                            }
+                        });
+
+                    if ui.add(egui::Button::new("🗑").fill(theme.colors.danger_background))
+                        .on_hover_text("Remove")
+                        .clicked()
+                    {
+                        removed = Some(index);
+                    }
+                });
+            });
+            ui.add_space(6.0);
+        }
+
+        if let Some(index) = removed {
+            self.settings.query_modes.remove(index);
+            changed = true;
+        }
+
+        if ui.button("➕  Add query mode").clicked() {
+            self.settings.query_modes.push(QueryMode {
+                prefix: String::new(),
+                provider: String::new(),
+            });
+            changed = true;
+        }
+
+        changed
+    }
+
+    // Anchor and offset of the search bar, returns whether either changed
+    fn render_placement(ui: &mut egui::Ui, theme: &Theme, window: &mut WindowSettings) -> bool {
+        let mut changed = false;
+
+        theme.item_frame().show(ui, |ui| {
+            ui.label(RichText::new("📍  Position")
+                .size(theme.text.title)
+                .strong()
+                .color(theme.colors.text));
+            ui.label(theme.note("Where the search bar opens. The offset moves it from there in pixels, or is the position itself for a custom one"));
+
+            ui.add_space(6.0);
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("window_anchor")
+                    .selected_text(anchor_label(window.anchor))
+                    .show_ui(ui, |ui| {
+                        for anchor in [WindowAnchor::TopCenter, WindowAnchor::Center, WindowAnchor::UnderCursor, WindowAnchor::Custom] {
+                            changed |= ui.selectable_value(&mut window.anchor, anchor, anchor_label(anchor)).changed();
+                        }
+                    });
+
+                ui.add_space(12.0);
+                ui.label("x");
+                changed |= ui.add(egui::DragValue::new(&mut window.offset[0])).changed();
+                ui.label("y");
+                changed |= ui.add(egui::DragValue::new(&mut window.offset[1])).changed();
+            });
+        });
+        ui.add_space(8.0);
+
+        changed
+    }
+
+    // Theme list with a search bar drawn in the shown theme, returns whether another one was picked
+    fn render_theme_picker(&mut self, ui: &mut egui::Ui, theme: &Theme) -> bool {
+        let mut changed = false;
+
+        theme.item_frame().show(ui, |ui| {
+            ui.label(RichText::new("🎨  Theme")
+                .size(theme.text.title)
+                .strong()
+                .color(theme.colors.text));
+            ui.label(theme.note(&format!(
+                "Hover a theme to preview it. Your own themes are JSON files in {}",
+                theme::themes_dir().display()
+            )));
+
+            ui.add_space(6.0);
+
+            egui::ComboBox::from_id_salt("theme")
+                .selected_text(theme_label(&self.settings.theme))
+                .width(ui.available_width())
+                .show_ui(ui, |ui| {
+                    for name in std::iter::once(SYSTEM_THEME.to_string()).chain(theme::available()) {
+                        let response = ui.selectable_label(self.settings.theme == name, theme_label(&name));
+                        if response.clicked() {
+                            self.settings.theme = name;
+                            changed = true;
+                        } else if response.hovered() {
+                            self.previewed_theme = Some(name);
+                        }
+                    }
+                });
+
+            ui.add_space(8.0);
+            Self::render_theme_preview(ui, theme);
+        });
+        ui.add_space(8.0);
+
+        changed
+    }
+
+    // A query with two results, framed like the real search bar
+    fn render_theme_preview(ui: &mut egui::Ui, theme: &Theme) {
+        let colors = &theme.colors;
+
+        Frame {
+            fill: colors.panel,
+            inner_margin: Margin::same(theme.padding.panel),
+            stroke: egui::Stroke::new(1.0, colors.border),
+            ..Frame::default()
+        }
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.spacing_mut().item_spacing.y = 4.0;
+
+            theme.input_frame().show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.label(RichText::new("firefox").size(theme.text.query).color(colors.text));
+            });
+
+            let results = [
+                (true, "🌐", "Firefox", "Web Browser"),
+                (false, "🔍", "Search the web for 'firefox'", "Google"),
+            ];
+            for (selected, icon, title, subtitle) in results {
+                theme.row_frame(selected).show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(icon).size(theme.text.title).color(colors.accent));
+                        ui.add_space(8.0);
+                        ui.vertical(|ui| {
+                            ui.spacing_mut().item_spacing.y = 2.0;
+                            ui.label(RichText::new(title).size(theme.text.title).color(colors.text));
+                            ui.label(RichText::new(subtitle).size(theme.text.small).color(colors.text_muted));
+                        });
+                    });
+                });
+            }
+        });
+    }
+
+    fn render_text_setting(
+        ui: &mut egui::Ui,
+        theme: &Theme,
+        settings_changed: &mut bool,
+        value: &mut String,
+        title: &str,
+        description: &str,
+        hint: &str,
+    ) {
+        Frame {
+            stroke: egui::Stroke::new(1.5, theme.colors.border),
+            ..theme.item_frame()
+        }
+        .show(ui, |ui| {
+            ui.label(title);
+            ui.label(theme.note(description));
+
+            ui.add_space(6.0);
+
+            let response = ui.add(
+                TextEdit::singleline(value)
+                    .desired_width(f32::INFINITY)
+                    .hint_text(hint),
+            );
+
+            *settings_changed |= response.changed();
+        });
+
+        ui.add_space(8.0);
+    }
+
+    fn render_slider_setting<N: egui::emath::Numeric>(
+        ui: &mut egui::Ui,
+        theme: &Theme,
+        settings_changed: &mut bool,
+        value: &mut N,
+        range: std::ops::RangeInclusive<N>,
+        title: &str,
+        description: &str,
+    ) {
+        theme.item_frame().show(ui, |ui| {
+            ui.label(RichText::new(title)
+                .size(theme.text.title)
+                .strong()
+                .color(theme.colors.text));
+            ui.label(theme.note(description));
+
+            ui.add_space(6.0);
+
+            let response = ui.add(egui::Slider::new(value, range));
+            *settings_changed |= response.changed();
+        });
+
+        ui.add_space(8.0);
+    }
+}
+
+fn anchor_label(anchor: WindowAnchor) -> &'static str {
+    match anchor {
+        WindowAnchor::TopCenter => "Top center",
+        WindowAnchor::Center => "Center",
+        WindowAnchor::UnderCursor => "Under the cursor",
+        WindowAnchor::Custom => "Custom position",
+    }
+}
+
+fn theme_label(name: &str) -> String {
+    match name {
+        SYSTEM_THEME => "Follow system (dark or light)".to_string(),
+        "dark" => "Dark".to_string(),
+        "light" => "Light".to_string(),
+        "high-contrast" => "High contrast".to_string(),
+        _ => name.to_string(),
+    }
+}
@@ -0,0 +1,614 @@
+// Colors, corner radii, paddings and text sizes of every window. The built-in themes live here,
+// user themes are JSON files in the config dir that change parts of a built-in one
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+
+use eframe::egui;
+use egui::{Color32, CornerRadius, Frame, Margin, RichText, Stroke};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::utils::{desktop_entry::parse_groups, paths::expand_user_path};
+
+/// Theme name that picks the dark or light theme to match the desktop
+pub const SYSTEM_THEME: &str = "system";
+pub const DEFAULT_THEME: &str = "dark";
+const BUILT_IN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    // Whether egui's own widgets (buttons, sliders, ...) start from its dark or light look
+    pub dark: bool,
+    pub colors: ThemeColors,
+    pub radius: ThemeRadius,
+    pub padding: ThemePadding,
+    pub text: ThemeText,
+}
+
+/// Colors are written as "#rrggbb" or "#rrggbbaa" in theme files
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThemeColors {
+    // Background of the settings and info windows
+    #[serde(with = "hex")]
+    pub window: Color32,
+    // Background of the search bar and history windows
+    #[serde(with = "hex")]
+    pub panel: Color32,
+    #[serde(with = "hex")]
+    pub input: Color32,
+    #[serde(with = "hex")]
+    pub section: Color32,
+    // Rows inside a section
+    #[serde(with = "hex")]
+    pub item: Color32,
+    #[serde(with = "hex")]
+    pub selected: Color32,
+    #[serde(with = "hex")]
+    pub border: Color32,
+    // Headings, icons, the search bar outline and focused widgets
+    #[serde(with = "hex")]
+    pub accent: Color32,
+    // Selected text
+    #[serde(with = "hex")]
+    pub selection: Color32,
+    // Key caps and the query mode label
+    #[serde(with = "hex")]
+    pub chip: Color32,
+    // Buttons, checkboxes and sliders
+    #[serde(with = "hex")]
+    pub control: Color32,
+    #[serde(with = "hex")]
+    pub control_border: Color32,
+    #[serde(with = "hex")]
+    pub text: Color32,
+    #[serde(with = "hex")]
+    pub text_secondary: Color32,
+    #[serde(with = "hex")]
+    pub text_muted: Color32,
+    #[serde(with = "hex")]
+    pub text_faint: Color32,
+    // Invalid input, delete buttons and errors
+    #[serde(with = "hex")]
+    pub danger: Color32,
+    #[serde(with = "hex")]
+    pub danger_background: Color32,
+    #[serde(with = "hex")]
+    pub notice: Color32,
+    #[serde(with = "hex")]
+    pub shadow: Color32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThemeRadius {
+    pub window: u8,
+    pub section: u8,
+    pub item: u8,
+    pub chip: u8,
+    pub input: u8,
+}
+
+/// Inner margins, single numbers apply to all sides and pairs are [horizontal, vertical]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThemePadding {
+    pub window: i8,
+    // Around the search bar and its results
+    pub panel: i8,
+    pub section: i8,
+    pub item: [i8; 2],
+    // One search result or history entry
+    pub row: [i8; 2],
+    pub chip: [i8; 2],
+    pub input: [i8; 2],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThemeText {
+    pub heading: f32,
+    pub section: f32,
+    // What is typed into the search bar
+    pub query: f32,
+    pub title: f32,
+    pub body: f32,
+    pub small: f32,
+    pub tiny: f32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            dark: true,
+            colors: ThemeColors {
+                window: Color32::from_rgb(18, 18, 22),
+                panel: Color32::from_rgba_unmultiplied(20, 20, 24, 250),
+                input: Color32::from_rgb(25, 25, 30),
+                section: Color32::from_rgb(28, 28, 32),
+                item: Color32::from_rgba_unmultiplied(35, 35, 42, 200),
+                selected: Color32::from_rgba_unmultiplied(80, 85, 110, 240),
+                border: Color32::from_rgb(60, 60, 70),
+                accent: Color32::from_rgb(50, 140, 255),
+                selection: Color32::from_rgba_unmultiplied(50, 140, 255, 100),
+                chip: Color32::from_rgb(40, 45, 65),
+                control: Color32::from_rgb(55, 55, 64),
+                control_border: Color32::TRANSPARENT,
+                text: Color32::from_rgb(240, 240, 245),
+                text_secondary: Color32::from_rgb(180, 180, 195),
+                text_muted: Color32::from_rgb(150, 150, 165),
+                text_faint: Color32::from_rgb(115, 115, 125),
+                danger: Color32::from_rgb(220, 80, 80),
+                danger_background: Color32::from_rgb(60, 30, 30),
+                notice: Color32::from_rgba_unmultiplied(40, 50, 70, 100),
+                shadow: Color32::from_rgba_unmultiplied(0, 0, 0, 100),
+            },
+            radius: ThemeRadius {
+                window: 12,
+                section: 8,
+                item: 6,
+                chip: 4,
+                input: 0,
+            },
+            padding: ThemePadding {
+                window: 20,
+                panel: 8,
+                section: 16,
+                item: [14, 12],
+                row: [10, 8],
+                chip: [8, 4],
+                input: [12, 8],
+            },
+            text: ThemeText {
+                heading: 24.0,
+                section: 15.0,
+                query: 18.0,
+                title: 14.0,
+                body: 12.0,
+                small: 11.5,
+                tiny: 10.0,
+            },
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            dark: false,
+            colors: ThemeColors {
+                window: Color32::from_rgb(244, 244, 247),
+                panel: Color32::from_rgba_unmultiplied(250, 250, 252, 250),
+                input: Color32::from_rgb(255, 255, 255),
+                section: Color32::from_rgb(236, 236, 241),
+                item: Color32::from_rgba_unmultiplied(225, 225, 232, 220),
+                selected: Color32::from_rgba_unmultiplied(205, 220, 250, 240),
+                border: Color32::from_rgb(200, 200, 210),
+                accent: Color32::from_rgb(20, 105, 220),
+                selection: Color32::from_rgba_unmultiplied(20, 105, 220, 80),
+                chip: Color32::from_rgb(218, 228, 246),
+                control: Color32::from_rgb(214, 214, 222),
+                control_border: Color32::from_rgb(190, 190, 200),
+                text: Color32::from_rgb(25, 25, 32),
+                text_secondary: Color32::from_rgb(70, 70, 85),
+                text_muted: Color32::from_rgb(100, 100, 115),
+                text_faint: Color32::from_rgb(140, 140, 150),
+                danger: Color32::from_rgb(200, 50, 50),
+                danger_background: Color32::from_rgb(248, 220, 220),
+                notice: Color32::from_rgba_unmultiplied(200, 218, 245, 160),
+                shadow: Color32::from_rgba_unmultiplied(0, 0, 0, 50),
+            },
+            ..Self::dark()
+        }
+    }
+
+    // Pure black and white with a yellow accent and larger text
+    pub fn high_contrast() -> Self {
+        let dark = Self::dark();
+        Self {
+            dark: true,
+            colors: ThemeColors {
+                window: Color32::BLACK,
+                panel: Color32::BLACK,
+                input: Color32::BLACK,
+                section: Color32::BLACK,
+                item: Color32::from_rgb(20, 20, 20),
+                selected: Color32::from_rgb(0, 60, 150),
+                border: Color32::WHITE,
+                accent: Color32::from_rgb(255, 215, 0),
+                selection: Color32::from_rgba_unmultiplied(255, 215, 0, 120),
+                chip: Color32::from_rgb(50, 45, 0),
+                control: Color32::BLACK,
+                control_border: Color32::WHITE,
+                text: Color32::WHITE,
+                text_secondary: Color32::WHITE,
+                text_muted: Color32::from_rgb(225, 225, 225),
+                text_faint: Color32::from_rgb(200, 200, 200),
+                danger: Color32::from_rgb(255, 100, 100),
+                danger_background: Color32::from_rgb(100, 0, 0),
+                notice: Color32::from_rgb(0, 40, 100),
+                shadow: Color32::TRANSPARENT,
+            },
+            text: ThemeText {
+                heading: 26.0,
+                section: 16.5,
+                query: 20.0,
+                title: 15.0,
+                body: 13.5,
+                small: 13.0,
+                tiny: 12.0,
+            },
+            ..dark
+        }
+    }
+
+    fn built_in(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// The built-in theme or user theme file called `name`
+    pub fn load(name: &str) -> Result<Self, String> {
+        if let Some(theme) = Self::built_in(name) {
+            return Ok(theme);
+        }
+
+        let path = themes_dir().join(format!("{}.json", name));
+        let content = fs::read_to_string(&path).map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+        let Value::Object(mut overrides) = serde_json::from_str(&content)
+            .map_err(|e| format!("{} is not valid JSON: {}", path.display(), e))?
+        else {
+            return Err(format!("{} must hold a JSON object", path.display()));
+        };
+
+        // Anything the file leaves out comes from the theme it extends
+        let base_name = match overrides.remove("extends") {
+            Some(Value::String(base)) => base,
+            _ => DEFAULT_THEME.to_string(),
+        };
+        let base = Self::built_in(&base_name)
+            .ok_or_else(|| format!("{} extends '{}', which is not a built-in theme", path.display(), base_name))?;
+
+        let mut merged = serde_json::to_value(base).map_err(|e| e.to_string())?;
+        merge(&mut merged, overrides);
+        serde_json::from_value(merged).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// The same theme with every text size multiplied by `factor`
+    pub fn with_text_scale(&self, factor: f32) -> Self {
+        let text = &self.text;
+        Self {
+            text: ThemeText {
+                heading: text.heading * factor,
+                section: text.section * factor,
+                query: text.query * factor,
+                title: text.title * factor,
+                body: text.body * factor,
+                small: text.small * factor,
+                tiny: text.tiny * factor,
+            },
+            ..self.clone()
+        }
+    }
+
+    /// egui's own widgets in the theme's colors
+    pub fn visuals(&self) -> egui::Visuals {
+        let colors = &self.colors;
+        let mut visuals = if self.dark { egui::Visuals::dark() } else { egui::Visuals::light() };
+
+        visuals.panel_fill = colors.window;
+        visuals.window_fill = colors.window;
+        visuals.window_stroke = Stroke::new(1.0, colors.border);
+        visuals.extreme_bg_color = colors.input;
+        visuals.faint_bg_color = colors.item;
+        visuals.selection.bg_fill = colors.selection;
+        visuals.selection.stroke = Stroke::new(1.0, colors.accent);
+        visuals.hyperlink_color = colors.accent;
+        visuals.error_fg_color = colors.danger;
+        visuals.text_cursor.stroke.color = colors.accent;
+
+        let widgets = &mut visuals.widgets;
+        widgets.noninteractive.bg_stroke = Stroke::new(1.0, colors.border);
+        widgets.noninteractive.fg_stroke.color = colors.text;
+        for state in [&mut widgets.inactive, &mut widgets.hovered, &mut widgets.active, &mut widgets.open] {
+            state.bg_fill = colors.control;
+            state.weak_bg_fill = colors.control;
+            state.fg_stroke.color = colors.text;
+        }
+        widgets.inactive.bg_stroke = Stroke::new(1.0, colors.control_border);
+        widgets.hovered.bg_stroke = Stroke::new(1.0, colors.accent);
+        widgets.active.bg_stroke = Stroke::new(1.0, colors.accent);
+        widgets.active.fg_stroke.color = colors.accent;
+        widgets.open.bg_stroke = Stroke::new(1.0, colors.accent);
+
+        visuals
+    }
+
+    /// Outermost frame of the settings and info windows
+    pub fn window_frame(&self) -> Frame {
+        Frame {
+            fill: self.colors.window,
+            corner_radius: CornerRadius::same(self.radius.window),
+            inner_margin: Margin::same(self.padding.window),
+            stroke: Stroke::new(1.5, self.colors.border),
+            ..Frame::default()
+        }
+    }
+
+    pub fn section_frame(&self) -> Frame {
+        Frame {
+            fill: self.colors.section,
+            corner_radius: CornerRadius::same(self.radius.section),
+            inner_margin: Margin::same(self.padding.section),
+            stroke: Stroke::new(1.0, self.colors.border),
+            ..Frame::default()
+        }
+    }
+
+    pub fn item_frame(&self) -> Frame {
+        Frame {
+            fill: self.colors.item,
+            corner_radius: CornerRadius::same(self.radius.item),
+            inner_margin: symmetric(self.padding.item),
+            ..Frame::default()
+        }
+    }
+
+    pub fn row_frame(&self, selected: bool) -> Frame {
+        Frame {
+            fill: if selected { self.colors.selected } else { Color32::TRANSPARENT },
+            corner_radius: CornerRadius::same(self.radius.item),
+            inner_margin: symmetric(self.padding.row),
+            ..Frame::default()
+        }
+    }
+
+    pub fn chip_frame(&self) -> Frame {
+        Frame {
+            fill: self.colors.chip,
+            corner_radius: CornerRadius::same(self.radius.chip),
+            inner_margin: symmetric(self.padding.chip),
+            ..Frame::default()
+        }
+    }
+
+    pub fn input_frame(&self) -> Frame {
+        Frame {
+            fill: self.colors.input,
+            corner_radius: CornerRadius::same(self.radius.input),
+            inner_margin: symmetric(self.padding.input),
+            stroke: Stroke::new(2.0, self.colors.accent),
+            ..Frame::default()
+        }
+    }
+
+    pub fn heading(&self, text: &str) -> RichText {
+        RichText::new(text).size(self.text.heading).color(self.colors.accent).strong()
+    }
+
+    pub fn section_title(&self, text: &str) -> RichText {
+        RichText::new(text).size(self.text.section).color(self.colors.accent).strong()
+    }
+
+    // Explanations below titles
+    pub fn note(&self, text: &str) -> RichText {
+        RichText::new(text).size(self.text.small).color(self.colors.text_muted)
+    }
+}
+
+fn symmetric([x, y]: [i8; 2]) -> Margin {
+    Margin::symmetric(x, y)
+}
+
+// Replaces the values of `base` that `overrides` has, keeping the rest of nested objects
+fn merge(base: &mut Value, overrides: Map<String, Value>) {
+    let Value::Object(base) = base else {
+        return;
+    };
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(existing @ Value::Object(_)), Value::Object(nested)) => merge(existing, nested),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+mod hex {
+    use eframe::egui::Color32;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color32, serializer: S) -> Result<S::Ok, S::Error> {
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        let text = if a == 255 {
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        };
+        serializer.serialize_str(&text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color32, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse(&text).ok_or_else(|| D::Error::custom(format!("'{}' is not a color like #rrggbb or #rrggbbaa", text)))
+    }
+
+    fn parse(text: &str) -> Option<Color32> {
+        let digits = text.strip_prefix('#')?;
+        if !digits.is_ascii() || !matches!(digits.len(), 6 | 8) {
+            return None;
+        }
+
+        let channel = |start: usize| u8::from_str_radix(&digits[start..start + 2], 16).ok();
+        let alpha = if digits.len() == 8 { channel(6)? } else { 255 };
+        Some(Color32::from_rgba_unmultiplied(channel(0)?, channel(2)?, channel(4)?, alpha))
+    }
+}
+
+/// Where user themes are read from, one `<name>.json` file per theme
+pub fn themes_dir() -> PathBuf {
+    dirs::config_dir()
+        .map(|config_dir| config_dir.join("quick_search"))
+        .unwrap_or_default()
+        .join("themes")
+}
+
+/// Names of the themes that can be picked: the built-in ones, then the user's
+pub fn available() -> Vec<String> {
+    let mut user_themes: Vec<String> = fs::read_dir(themes_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .filter(|name| !BUILT_IN_THEMES.contains(&name.as_str()))
+        .collect();
+    user_themes.sort();
+
+    BUILT_IN_THEMES.iter().map(|name| name.to_string()).chain(user_themes).collect()
+}
+
+/// The theme called `name`, applied to `ctx` whenever it differs from the last one. Cheap enough to
+/// call every frame, the theme is only loaded again once the name changes.
+pub fn apply(ctx: &egui::Context, name: &str) -> Arc<Theme> {
+    let name = if name == SYSTEM_THEME {
+        if system_prefers_dark(ctx) { "dark" } else { "light" }
+    } else {
+        name
+    };
+
+    let id = egui::Id::new("quick_search_theme");
+    if let Some((applied, theme)) = ctx.data(|data| data.get_temp::<(String, Arc<Theme>)>(id)) {
+        if applied == name {
+            return theme;
+        }
+    }
+
+    let theme = Arc::new(Theme::load(name).unwrap_or_else(|e| {
+        eprintln!("Using the {} theme instead: {}", DEFAULT_THEME, e);
+        Theme::default()
+    }));
+
+    let egui_theme = if theme.dark { egui::Theme::Dark } else { egui::Theme::Light };
+    ctx.set_theme(egui_theme);
+    ctx.set_visuals_of(egui_theme, theme.visuals());
+    ctx.data_mut(|data| data.insert_temp(id, (name.to_string(), Arc::clone(&theme))));
+
+    theme
+}
+
+/// Makes `family` the proportional font of `ctx`, or egui's built-in one when it's empty. `family`
+/// is a font name fontconfig knows or the path of a font file. Emoji still come from the built-in fonts.
+pub fn apply_font(ctx: &egui::Context, family: &str) {
+    let family = family.trim();
+    let id = egui::Id::new("quick_search_font");
+    let applied = ctx.data(|data| data.get_temp::<String>(id));
+    // The built-in font needs no work the first time
+    if applied.as_deref().unwrap_or_default() == family {
+        return;
+    }
+    ctx.data_mut(|data| data.insert_temp(id, family.to_string()));
+
+    let mut fonts = egui::FontDefinitions::default();
+    if !family.is_empty() {
+        match load_font(family) {
+            Ok(font) => {
+                fonts.font_data.insert(family.to_string(), Arc::new(egui::FontData::from_owned(font)));
+                fonts.families.entry(egui::FontFamily::Proportional).or_default().insert(0, family.to_string());
+            }
+            Err(e) => eprintln!("Using the built-in font instead: {}", e),
+        }
+    }
+    ctx.set_fonts(fonts);
+}
+
+fn load_font(family: &str) -> Result<Vec<u8>, String> {
+    let path = if family.contains('/') {
+        expand_user_path(family)
+    } else {
+        let output = Command::new("fc-match")
+            .args(["--format=%{family}\n%{file}", family])
+            .output()
+            .map_err(|e| format!("Can't run fc-match to find the font '{}': {}", family, e))?;
+        let matched = String::from_utf8_lossy(&output.stdout).to_string();
+        let (families, file) = matched.split_once('\n').unwrap_or_default();
+
+        // fc-match always answers, with some other font if nothing is called like that
+        let wanted = family.to_lowercase();
+        if !families.split(',').any(|name| name.to_lowercase().starts_with(&wanted)) {
+            return Err(format!("No installed font is called '{}'", family));
+        }
+        PathBuf::from(file.trim())
+    };
+
+    if path.as_os_str().is_empty() || !Path::new(&path).is_file() {
+        return Err(format!("No font file found for '{}'", family));
+    }
+    fs::read(&path).map_err(|e| format!("Can't read {}: {}", path.display(), e))
+}
+
+fn system_prefers_dark(ctx: &egui::Context) -> bool {
+    if let Some(theme) = ctx.system_theme() {
+        return theme == egui::Theme::Dark;
+    }
+
+    static DETECTED: OnceLock<bool> = OnceLock::new();
+    *DETECTED.get_or_init(detect_dark_preference)
+}
+
+// Not every platform tells winit, so ask the desktop settings like the icon theme lookup does
+fn detect_dark_preference() -> bool {
+    // GNOME and the desktops following the freedesktop color-scheme setting
+    if let Ok(output) = Command::new("gsettings").args(["get", "org.gnome.desktop.interface", "color-scheme"]).output() {
+        let scheme = String::from_utf8_lossy(&output.stdout);
+        if scheme.contains("prefer-dark") {
+            return true;
+        }
+        if scheme.contains("prefer-light") {
+            return false;
+        }
+    }
+
+    if let Ok(gtk_theme) = std::env::var("GTK_THEME") {
+        return gtk_theme.to_lowercase().contains("dark");
+    }
+
+    let config_dir = dirs::config_dir().unwrap_or_default();
+    for file in ["gtk-4.0/settings.ini", "gtk-3.0/settings.ini"] {
+        if let Ok(content) = fs::read_to_string(config_dir.join(file)) {
+            let groups = parse_groups(&content);
+            let Some(settings) = groups.get("Settings") else {
+                continue;
+            };
+            let prefer_dark = settings.get("gtk-application-prefer-dark-theme");
+            if prefer_dark.is_some_and(|value| matches!(value.trim(), "1" | "true")) {
+                return true;
+            }
+            if let Some(theme) = settings.get("gtk-theme-name") {
+                return theme.to_lowercase().contains("dark");
+            }
+            if prefer_dark.is_some() {
+                return false;
+            }
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(config_dir.join("kdeglobals")) {
+        let groups = parse_groups(&content);
+        if let Some(scheme) = groups.get("General").and_then(|group| group.get("ColorScheme")) {
+            return scheme.to_lowercase().contains("dark");
+        }
+    }
+
+    // Quick Search has always been dark
+    true
+}
@@ -3,6 +3,7 @@ mod utils;
 
 use eframe::egui;
 use named_lock::NamedLock;
+use utils::daemon::{self, DaemonCommand};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -26,7 +27,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             "--force" => {
                 return run_main_window(None);
             }
-            _ => {}
+            "--daemon" => {
+                return run_daemon();
+            }
+            "--refresh-rates" => {
+                let settings = utils::settings_manager::SettingsManager::new().load_settings();
+                let table = utils::currency::refresh_rates(&settings.currency)?;
+                println!("Saved {} exchange rates based on {}", table.rates.len(), table.base);
+                return Ok(());
+            }
+            flag => {
+                if let Some(command) = DaemonCommand::from_flag(flag) {
+                    // Without a daemon, showing falls back to a normal launch
+                    if daemon::send_command(command).is_ok() || command == DaemonCommand::Hide {
+                        return Ok(());
+                    }
+                }
+            }
         }
     }
 
@@ -34,6 +51,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let guard = match lock.try_lock() {
         Ok(guard) => guard,
         Err(_) => {
+            // The lock holder may be a daemon, which can just show its window
+            if daemon::send_command(DaemonCommand::Show).is_ok() {
+                return Ok(());
+            }
             eprintln!("Another instance is already running.");
             eprintln!("If you're sure no other instance is running, use: --force");
             return Ok(());
@@ -43,26 +64,53 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     run_main_window(Some(guard))
 }
 
-fn run_main_window(_guard: Option<named_lock::NamedLockGuard>) -> Result<(), Box<dyn std::error::Error>> {
-    let native_options = eframe::NativeOptions {
+fn main_window_options() -> eframe::NativeOptions {
+    let window = &utils::live_settings::current().window;
+    // The search bar fits the height to its content in the first frame
+    let size = [window.clamped_width(), 130.0].map(|length| length * window.clamped_scale());
+
+    eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([500.0, 130.0])
+            .with_inner_size(size)
             .with_decorations(false)
             .with_transparent(false)
             .with_resizable(false)
             .with_always_on_top(),
         ..Default::default()
-    };
+    }
+}
 
+fn run_main_window(_guard: Option<named_lock::NamedLockGuard>) -> Result<(), Box<dyn std::error::Error>> {
     eframe::run_native(
         "Quick Search",
-        native_options,
+        main_window_options(),
         Box::new(|cc| Ok(Box::new(gui::search_bar::QuickSearchApp::new(cc)))),
     )?;
 
     Ok(())
 }
 
+fn run_daemon() -> Result<(), Box<dyn std::error::Error>> {
+    let lock = NamedLock::create("quick_search_single_instance")?;
+    let _guard = match lock.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => {
+            eprintln!("Another instance is already running.");
+            return Ok(());
+        }
+    };
+
+    let server = daemon::DaemonServer::bind()?;
+
+    eframe::run_native(
+        "Quick Search",
+        main_window_options(),
+        Box::new(|cc| Ok(Box::new(gui::search_bar::QuickSearchApp::with_daemon(cc, server)))),
+    )?;
+
+    Ok(())
+}
+
 fn run_history_window() -> Result<(), Box<dyn std::error::Error>> {
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
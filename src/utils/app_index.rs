@@ -0,0 +1,253 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, UNIX_EPOCH};
+
+use inotify::{Inotify, WatchMask};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{desktop_entry::{application_dirs, current_desktops, DesktopEntry, Locale}, fuzzy::fuzzy_score, get_apps::scan_applications, utils::{ActionType, SearchResult}};
+
+// App scores land in APP_SCORE_BASE..=APP_SCORE_BASE + APP_SCORE_RANGE depending on match quality
+const APP_SCORE_BASE: f32 = 50.0;
+const APP_SCORE_RANGE: f32 = 40.0;
+const MAX_APP_RESULTS: usize = 5;
+const MAX_ACTION_RESULTS: usize = 2;
+// Actions rank just below their application unless the query targets them
+const ACTION_WEIGHT: f32 = 0.9;
+// Matches on secondary fields count for less than a match on the name
+const GENERIC_NAME_WEIGHT: f32 = 0.8;
+const KEYWORD_WEIGHT: f32 = 0.7;
+
+// Installing a package writes several files, the rescan waits for the rest of them
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+const WATCH_MASK: WatchMask = WatchMask::CLOSE_WRITE
+    .union(WatchMask::CREATE)
+    .union(WatchMask::DELETE)
+    .union(WatchMask::MOVED_FROM)
+    .union(WatchMask::MOVED_TO)
+    .union(WatchMask::ONLYDIR);
+
+// Directory -> mtime in nanoseconds, None if the directory did not exist
+type DirStamps = Vec<(PathBuf, Option<u128>)>;
+
+#[derive(Serialize, Deserialize)]
+struct IndexCache {
+    // Locale and desktop the entries were resolved for
+    environment: String,
+    dirs: DirStamps,
+    apps: Vec<DesktopEntry>,
+}
+
+/// Installed applications, kept in memory so searching never touches the disk
+pub struct AppIndex {
+    apps: Arc<RwLock<Vec<DesktopEntry>>>,
+}
+
+// Rebuilds the index on its background thread
+struct Scanner {
+    app_dirs: Vec<PathBuf>,
+    locale: Locale,
+    desktops: Vec<String>,
+    environment: String,
+    cache_file: PathBuf,
+    apps: Arc<RwLock<Vec<DesktopEntry>>>,
+}
+
+impl AppIndex {
+    /// Loads the on-disk cache and, if it is missing or outdated, rebuilds it on a background thread.
+    /// Until the rebuild finishes, searches use whatever the stale cache contained. The thread then
+    /// keeps watching the application dirs, so apps installed or removed later show up too.
+    pub fn load() -> Self {
+        let app_dirs = application_dirs();
+        let stamps = Self::dir_stamps(&app_dirs);
+        let cache_file = Self::get_cache_path();
+        let locale = Locale::from_env();
+        let desktops = current_desktops();
+        let environment = format!("{}|{}", locale.tag(), desktops.join(":"));
+
+        let cached = Self::read_cache(&cache_file);
+        let is_fresh = cached
+            .as_ref()
+            .is_some_and(|cache| cache.dirs == stamps && cache.environment == environment);
+
+        let apps = Arc::new(RwLock::new(cached.map(|cache| cache.apps).unwrap_or_default()));
+
+        let scanner = Scanner {
+            app_dirs,
+            locale,
+            desktops,
+            environment,
+            cache_file,
+            apps: Arc::clone(&apps),
+        };
+        std::thread::spawn(move || {
+            if !is_fresh {
+                scanner.rescan();
+            }
+            scanner.watch();
+        });
+
+        Self { apps }
+    }
+
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        let Ok(apps) = self.apps.read() else {
+            return Vec::new();
+        };
+
+        let mut results: Vec<SearchResult> = apps
+            .iter()
+            .filter_map(|app| {
+                let match_score = Self::match_score(query, app)?;
+                let launch_spec = app.launch_spec()?;
+                Some(SearchResult {
+                    title: app.name.clone(),
+                    subtitle: app.generic_name.clone().unwrap_or_else(|| "Application".to_string()),
+                    icon: "[APP]".to_string(),
+                    icon_names: app.icon.iter().cloned().collect(),
+                    action: ActionType::OpenApp(launch_spec),
+                    score: APP_SCORE_BASE + APP_SCORE_RANGE * match_score,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(MAX_APP_RESULTS);
+
+        let mut action_results = Self::search_actions(query, &apps);
+        action_results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        action_results.truncate(MAX_ACTION_RESULTS);
+
+        results.extend(action_results);
+        results
+    }
+
+    // Desktop actions match on "<app> <action>", so "firefox private" finds "New Private Window"
+    fn search_actions(query: &str, apps: &[DesktopEntry]) -> Vec<SearchResult> {
+        apps.iter()
+            .flat_map(|app| app.actions.iter().map(move |action| (app, action)))
+            .filter_map(|(app, action)| {
+                let match_score = fuzzy_score(query, &format!("{} {}", app.name, action.name))?;
+                let launch_spec = app.action_launch_spec(action)?;
+                Some(SearchResult {
+                    title: format!("{}: {}", app.name, action.name),
+                    subtitle: "Application action".to_string(),
+                    icon: "[APP]".to_string(),
+                    icon_names: action.icon.iter().chain(app.icon.iter()).cloned().collect(),
+                    action: ActionType::OpenAppAction(launch_spec),
+                    score: APP_SCORE_BASE + APP_SCORE_RANGE * match_score * ACTION_WEIGHT,
+                })
+            })
+            .collect()
+    }
+
+    fn match_score(query: &str, app: &DesktopEntry) -> Option<f32> {
+        let name = fuzzy_score(query, &app.name);
+        let generic_name = app
+            .generic_name
+            .as_deref()
+            .and_then(|generic_name| fuzzy_score(query, generic_name))
+            .map(|score| score * GENERIC_NAME_WEIGHT);
+        let keyword = app
+            .keywords
+            .iter()
+            .filter_map(|keyword| fuzzy_score(query, keyword))
+            .map(|score| score * KEYWORD_WEIGHT)
+            .reduce(f32::max);
+
+        [name, generic_name, keyword].into_iter().flatten().reduce(f32::max)
+    }
+
+    fn get_cache_path() -> PathBuf {
+        if let Some(cache_dir) = dirs::cache_dir() {
+            let app_dir = cache_dir.join("quick_search");
+            let _ = fs::create_dir_all(&app_dir);
+            return app_dir.join("app_index.json");
+        }
+
+        // Fallback
+        PathBuf::from("app_index.json")
+    }
+
+    fn dir_stamps(app_dirs: &[PathBuf]) -> DirStamps {
+        app_dirs
+            .iter()
+            .map(|dir| {
+                let mtime = fs::metadata(dir)
+                    .and_then(|meta| meta.modified())
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_nanos());
+                (dir.clone(), mtime)
+            })
+            .collect()
+    }
+
+    fn read_cache(cache_file: &Path) -> Option<IndexCache> {
+        let content = fs::read_to_string(cache_file).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn write_cache(cache_file: &Path, cache: &IndexCache) {
+        match serde_json::to_string(cache) {
+            Ok(json) => {
+                if let Err(e) = fs::write(cache_file, json) {
+                    eprintln!("Failed to write app index cache: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize app index cache: {}", e),
+        }
+    }
+}
+
+impl Scanner {
+    fn rescan(&self) {
+        let stamps = AppIndex::dir_stamps(&self.app_dirs);
+        let scanned = scan_applications(&self.app_dirs, &self.locale, &self.desktops);
+        AppIndex::write_cache(&self.cache_file, &IndexCache {
+            environment: self.environment.clone(),
+            dirs: stamps,
+            apps: scanned.clone(),
+        });
+
+        if let Ok(mut apps) = self.apps.write() {
+            *apps = scanned;
+        }
+    }
+
+    // Rescans whenever something in the application dirs changes. Dirs that don't exist yet aren't
+    // watched, apps installed into them show up on the next start.
+    fn watch(&self) {
+        let mut inotify = match Inotify::init() {
+            Ok(inotify) => inotify,
+            Err(e) => {
+                eprintln!("Installed apps will not update live, inotify failed: {}", e);
+                return;
+            }
+        };
+        let watched = self
+            .app_dirs
+            .iter()
+            .filter(|dir| inotify.watches().add(dir, WATCH_MASK).is_ok())
+            .count();
+        if watched == 0 {
+            return;
+        }
+
+        let mut buffer = [0u8; 4096];
+        loop {
+            if let Err(e) = inotify.read_events_blocking(&mut buffer) {
+                eprintln!("Stopped watching the application dirs: {}", e);
+                return;
+            }
+
+            std::thread::sleep(SETTLE_DELAY);
+            // Whatever arrived meanwhile is covered by the same rescan
+            while inotify.read_events(&mut buffer).is_ok_and(|events| events.count() > 0) {}
+
+            self.rescan();
+        }
+    }
+}
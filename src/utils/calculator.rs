@@ -0,0 +1,1103 @@
+// The calculator behind the math provider. Numbers stay exact fractions while they fit in
+// an i128 and fall back to f64 after that, or after anything irrational like sqrt or sin.
+
+use std::collections::BTreeMap;
+use std::f64::consts::{E, PI, TAU};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::units::{find_unit, Unit};
+
+// Digits kept when a result can't be shown exactly
+const SIGNIFICANT_DIGITS: usize = 12;
+const MAX_DECIMALS: usize = 12;
+// At most this many rows per calculation
+const MAX_ANSWERS: usize = 3;
+
+const KEYWORDS: &[&str] = &["to", "in", "as", "of", "mod"];
+const FUNCTIONS: &[&str] = &[
+    "sqrt", "cbrt", "abs", "floor", "ceil", "round", "trunc", "sin", "cos", "tan", "asin", "acos", "atan", "sinh", "cosh",
+    "tanh", "ln", "log", "log2", "log10", "exp", "min", "max",
+];
+const CONSTANTS: &[&str] = &["pi", "π", "tau", "τ", "e", "phi", "φ"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+enum Number {
+    // Numerator and denominator, reduced, with a positive denominator
+    Ratio(i128, i128),
+    Float(f64),
+}
+
+use Number::{Float, Ratio};
+
+impl Number {
+    fn ratio(num: i128, den: i128) -> Option<Self> {
+        if den == 0 {
+            return None;
+        }
+        let divisor = i128::try_from(gcd(num.unsigned_abs(), den.unsigned_abs())).ok()?;
+        let (num, den) = (num / divisor, den / divisor);
+        if den < 0 {
+            Some(Ratio(num.checked_neg()?, den.checked_neg()?))
+        } else {
+            Some(Ratio(num, den))
+        }
+    }
+
+    fn to_f64(self) -> f64 {
+        match self {
+            Ratio(num, den) => num as f64 / den as f64,
+            Float(value) => value,
+        }
+    }
+
+    fn as_integer(self) -> Option<i128> {
+        match self {
+            Ratio(num, 1) => Some(num),
+            // Beyond 2^53 a float no longer knows its last digits
+            Float(value) if value.fract() == 0.0 && value.abs() < 9007199254740992.0 => Some(value as i128),
+            _ => None,
+        }
+    }
+
+    // Runs `exact` when both sides are fractions, `float` when either isn't or `exact` overflows
+    fn combine(
+        self,
+        other: Number,
+        exact: impl Fn(i128, i128, i128, i128) -> Option<(i128, i128)>,
+        float: impl Fn(f64, f64) -> f64,
+    ) -> Number {
+        if let (Ratio(a, b), Ratio(c, d)) = (self, other) {
+            if let Some(number) = exact(a, b, c, d).and_then(|(num, den)| Number::ratio(num, den)) {
+                return number;
+            }
+        }
+        Float(float(self.to_f64(), other.to_f64()))
+    }
+
+    fn add(self, other: Number) -> Number {
+        self.combine(
+            other,
+            |a, b, c, d| Some((a.checked_mul(d)?.checked_add(c.checked_mul(b)?)?, b.checked_mul(d)?)),
+            |x, y| x + y,
+        )
+    }
+
+    fn sub(self, other: Number) -> Number {
+        self.add(other.neg())
+    }
+
+    fn mul(self, other: Number) -> Number {
+        self.combine(other, |a, b, c, d| Some((a.checked_mul(c)?, b.checked_mul(d)?)), |x, y| x * y)
+    }
+
+    fn div(self, other: Number) -> Number {
+        self.combine(other, |a, b, c, d| Some((a.checked_mul(d)?, b.checked_mul(c)?)), |x, y| x / y)
+    }
+
+    // Truncating remainder, the sign follows the dividend
+    fn rem(self, other: Number) -> Number {
+        self.combine(
+            other,
+            |a, b, c, d| Some((a.checked_mul(d)?.checked_rem(c.checked_mul(b)?)?, b.checked_mul(d)?)),
+            |x, y| x % y,
+        )
+    }
+
+    fn neg(self) -> Number {
+        match self {
+            Ratio(num, den) => num.checked_neg().map_or(Float(-self.to_f64()), |num| Ratio(num, den)),
+            Float(value) => Float(-value),
+        }
+    }
+
+    fn pow(self, exponent: Number) -> Number {
+        if let (Ratio(num, den), Ratio(power, 1)) = (self, exponent) {
+            let exact = u32::try_from(power.unsigned_abs())
+                .ok()
+                .and_then(|power| Some((num.checked_pow(power)?, den.checked_pow(power)?)))
+                .and_then(|(num, den)| if power < 0 { Number::ratio(den, num) } else { Number::ratio(num, den) });
+            if let Some(number) = exact {
+                return number;
+            }
+        }
+        Float(self.to_f64().powf(exponent.to_f64()))
+    }
+
+    fn factorial(self) -> Option<Number> {
+        let n = u32::try_from(self.as_integer()?).ok()?;
+
+        let mut exact = Some(1i128);
+        let mut float = 1.0f64;
+        for factor in 2..=n {
+            exact = exact.and_then(|product| product.checked_mul(factor as i128));
+            float *= factor as f64;
+            if float.is_infinite() {
+                return None;
+            }
+        }
+        Some(exact.map_or(Float(float), |product| Ratio(product, 1)))
+    }
+
+    // Rounds to an integer, exactly for fractions
+    fn round_with(self, exact: fn(i128, i128) -> Option<i128>, float: fn(f64) -> f64) -> Number {
+        match self {
+            Ratio(num, den) => exact(num, den).map_or(Float(float(self.to_f64())), |n| Ratio(n, 1)),
+            Float(value) => Float(float(value)),
+        }
+    }
+
+    fn floor(self) -> Number {
+        self.round_with(|num, den| Some(num.div_euclid(den)), f64::floor)
+    }
+
+    fn ceil(self) -> Number {
+        self.round_with(|num, den| num.checked_neg()?.div_euclid(den).checked_neg(), f64::ceil)
+    }
+
+    fn trunc(self) -> Number {
+        self.round_with(|num, den| Some(num / den), f64::trunc)
+    }
+
+    // Halves round away from zero
+    fn round(self) -> Number {
+        self.round_with(
+            |num, den| {
+                let half_up = num.unsigned_abs().checked_mul(2)?.checked_add(den as u128)? / (den as u128 * 2);
+                let rounded = i128::try_from(half_up).ok()?;
+                Some(if num < 0 { -rounded } else { rounded })
+            },
+            f64::round,
+        )
+    }
+
+    fn abs(self) -> Number {
+        if self.to_f64() < 0.0 {
+            self.neg()
+        } else {
+            self
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ratio(num, 1) => write!(f, "{}", num),
+            Ratio(num, den) => write!(f, "{}/{}", num, den),
+            Float(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+impl From<Number> for String {
+    fn from(number: Number) -> Self {
+        number.to_string()
+    }
+}
+
+impl TryFrom<String> for Number {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid number: {}", text);
+
+        if let Some((num, den)) = text.split_once('/') {
+            let num = num.parse().map_err(|_| invalid())?;
+            let den = den.parse().map_err(|_| invalid())?;
+            return Number::ratio(num, den).ok_or_else(invalid);
+        }
+        if let Ok(integer) = text.parse() {
+            return Ok(Ratio(integer, 1));
+        }
+        text.parse().map(Float).map_err(|_| invalid())
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Value {
+    // In `unit`, when there is one
+    number: Number,
+    unit: Option<&'static Unit>,
+    // Written with a trailing '%', still counting in hundredths
+    percent: bool,
+}
+
+impl Value {
+    fn plain(number: Number) -> Self {
+        Self { number, unit: None, percent: false }
+    }
+
+    // A percentage as the fraction it stands for
+    fn settled(self) -> Self {
+        if self.percent {
+            Self { number: self.number.div(Ratio(100, 1)), unit: self.unit, percent: false }
+        } else {
+            self
+        }
+    }
+
+    // The number, if there is no unit attached
+    fn scalar(self) -> Option<Number> {
+        let value = self.settled();
+        value.unit.is_none().then_some(value.number)
+    }
+
+    fn converted(self, unit: &'static Unit) -> Option<Self> {
+        let from = self.unit?;
+        if from.dimension != unit.dimension {
+            return None;
+        }
+        let number = if from == unit { self.number } else { Float(unit.in_unit(from.to_base(self.number.to_f64()))) };
+        Some(Self { number, unit: Some(unit), percent: false })
+    }
+
+    // Brings `other` into the unit of `self`, if either has one
+    fn aligned(self, other: Self) -> Option<(Self, Self)> {
+        match (self.unit, other.unit) {
+            (None, None) => Some((self, other)),
+            (Some(unit), Some(_)) => Some((self, other.converted(unit)?)),
+            _ => None,
+        }
+    }
+
+    fn add(self, other: Self, subtract: bool) -> Option<Self> {
+        let combine = |a: Number, b: Number| if subtract { a.sub(b) } else { a.add(b) };
+
+        // "80 + 15%" adds 15% of 80
+        if other.percent && !self.percent {
+            let change = other.settled().scalar()?;
+            return Some(Self { number: self.number.mul(combine(Ratio(1, 1), change)), ..self });
+        }
+        if self.percent && other.percent {
+            return Some(Self { number: combine(self.number, other.number), ..self });
+        }
+
+        let (a, b) = self.settled().aligned(other.settled())?;
+        Some(Self { number: combine(a.number, b.number), ..a })
+    }
+
+    fn mul(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.settled(), other.settled());
+        if a.unit.is_some() && b.unit.is_some() {
+            return None;
+        }
+        Some(Self { number: a.number.mul(b.number), unit: a.unit.or(b.unit), percent: false })
+    }
+
+    fn div(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.settled(), other.settled());
+        match (a.unit, b.unit) {
+            // "1 GiB / 4 MiB" is a plain ratio
+            (Some(_), Some(_)) => {
+                let (a, b) = a.aligned(b)?;
+                Some(Self::plain(a.number.div(b.number)))
+            }
+            (_, None) => Some(Self { number: a.number.div(b.number), ..a }),
+            (None, Some(_)) => None,
+        }
+    }
+
+    fn rem(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.settled(), other.settled());
+        let (a, b) = match (a.unit, b.unit) {
+            (Some(_), None) => (a, b),
+            _ => a.aligned(b)?,
+        };
+        Some(Self { number: a.number.rem(b.number), ..a })
+    }
+
+    fn pow(self, exponent: Self) -> Option<Self> {
+        Some(Self::plain(self.scalar()?.pow(exponent.scalar()?)))
+    }
+
+    fn neg(self) -> Self {
+        Self { number: self.number.neg(), ..self }
+    }
+
+    fn percent(self) -> Option<Self> {
+        (self.unit.is_none() && !self.percent).then_some(Self { percent: true, ..self })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(Number),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+    Equals,
+}
+
+// Splits `text` into tokens. The flag tells whether a hex, binary or octal literal was used.
+fn tokenize(text: &str) -> Option<(Vec<Token>, bool)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut based = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '0' if matches!(chars.get(i + 1), Some('x' | 'X' | 'b' | 'B' | 'o' | 'O')) => {
+                let radix = match chars[i + 1].to_ascii_lowercase() {
+                    'x' => 16,
+                    'b' => 2,
+                    _ => 8,
+                };
+                let start = i + 2;
+                i = start;
+                while i < chars.len() && (chars[i].is_digit(radix) || chars[i] == '_') {
+                    i += 1;
+                }
+                let digits: String = chars[start..i].iter().filter(|&&c| c != '_').collect();
+                tokens.push(Token::Number(Ratio(i128::from_str_radix(&digits, radix).ok()?, 1)));
+                based = true;
+            }
+            _ if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '_' || chars[i] == '.') {
+                    i += 1;
+                }
+                // An exponent only when digits follow, "2e" is 2 times e
+                if matches!(chars.get(i), Some('e' | 'E')) {
+                    let digits_at = if matches!(chars.get(i + 1), Some('+' | '-')) { i + 2 } else { i + 1 };
+                    if chars.get(digits_at).is_some_and(char::is_ascii_digit) {
+                        i = digits_at;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let literal: String = chars[start..i].iter().filter(|&&c| c != '_').collect();
+                tokens.push(Token::Number(parse_decimal(&literal)?));
+            }
+            _ if c.is_alphabetic() || matches!(c, '_' | '°') => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '°')) {
+                    i += 1;
+                }
+                let mut word: String = chars[start..i].iter().collect();
+
+                // Units like "km/h" are one word
+                if chars.get(i) == Some(&'/') {
+                    let end = (i + 1..chars.len()).find(|&j| !chars[j].is_alphabetic()).unwrap_or(chars.len());
+                    let combined = format!("{}/{}", word, chars[i + 1..end].iter().collect::<String>());
+                    if end > i + 1 && find_unit(&combined).is_some() {
+                        word = combined;
+                        i = end;
+                    }
+                }
+                tokens.push(Token::Ident(word));
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                tokens.push(Token::Op('^'));
+                i += 2;
+            }
+            _ => {
+                tokens.push(match c {
+                    '+' | '^' | '%' | '!' | '/' | '*' => Token::Op(c),
+                    '-' | '−' => Token::Op('-'),
+                    '×' | '·' => Token::Op('*'),
+                    '÷' => Token::Op('/'),
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    ',' => Token::Comma,
+                    '=' => Token::Equals,
+                    _ => return None,
+                });
+                i += 1;
+            }
+        }
+    }
+
+    Some((tokens, based))
+}
+
+// "1.25" becomes 5/4 rather than the nearest float, so 0.1 + 0.2 is exactly 0.3
+fn parse_decimal(literal: &str) -> Option<Number> {
+    let (mantissa, exponent) = match literal.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().ok()?),
+        None => (literal, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if fraction.contains('.') {
+        return None;
+    }
+
+    let exact = || {
+        let digits: i128 = format!("{}{}", integer, fraction).parse().ok()?;
+        let scale = exponent - fraction.len() as i32;
+        let power = 10i128.checked_pow(scale.unsigned_abs())?;
+        if scale < 0 {
+            Number::ratio(digits, power)
+        } else {
+            Some(Ratio(digits.checked_mul(power)?, 1))
+        }
+    };
+    exact().or_else(|| literal.parse().ok().map(Float))
+}
+
+fn is_keyword(word: &str) -> bool {
+    KEYWORDS.contains(&word)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Base {
+    Decimal,
+    Hex,
+    Binary,
+    Octal,
+    Scientific,
+}
+
+impl Base {
+    fn from_word(word: &str) -> Option<Self> {
+        match word.to_lowercase().as_str() {
+            "dec" | "decimal" => Some(Self::Decimal),
+            "hex" | "hexadecimal" => Some(Self::Hex),
+            "bin" | "binary" => Some(Self::Binary),
+            "oct" | "octal" => Some(Self::Octal),
+            "sci" | "scientific" => Some(Self::Scientific),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Decimal => "Decimal",
+            Self::Hex => "Hexadecimal",
+            Self::Binary => "Binary",
+            Self::Octal => "Octal",
+            Self::Scientific => "Scientific notation",
+        }
+    }
+
+    fn format(self, number: Number) -> Option<String> {
+        let integer = || number.as_integer();
+        let sign = if number.to_f64() < 0.0 { "-" } else { "" };
+        match self {
+            Self::Decimal => Some(format_decimal(number)),
+            Self::Hex => Some(format!("{}0x{:X}", sign, integer()?.unsigned_abs())),
+            Self::Binary => Some(format!("{}0b{:b}", sign, integer()?.unsigned_abs())),
+            Self::Octal => Some(format!("{}0o{:o}", sign, integer()?.unsigned_abs())),
+            Self::Scientific => Some(format_scientific(number.to_f64())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Unit(&'static Unit),
+    Base(Base),
+}
+
+// Splits off a trailing "to km" / "in hex" / "as bin"
+fn split_target(tokens: &[Token]) -> (&[Token], Option<Target>) {
+    if let [body @ .., Token::Ident(keyword), Token::Ident(target)] = tokens {
+        if matches!(keyword.as_str(), "to" | "in" | "as") && !body.is_empty() {
+            let target = Base::from_word(target).map(Target::Base).or_else(|| find_unit(target).map(Target::Unit));
+            if target.is_some() {
+                return (body, target);
+            }
+        }
+    }
+    (tokens, None)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    memory: &'a Memory,
+}
+
+// Recursive descent, lowest precedence first:
+// sums, products (including "of", "mod" and implicit ones like "2pi"), signs, powers, then ! and %.
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Ident(next)) if next == word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn starts_operand(&self, index: usize) -> bool {
+        match self.tokens.get(index) {
+            Some(Token::Number(_) | Token::LParen) => true,
+            Some(Token::Ident(word)) => !is_keyword(word),
+            _ => false,
+        }
+    }
+
+    fn expression(&mut self) -> Option<Value> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat(&Token::Op('+')) {
+                value = value.add(self.term()?, false)?;
+            } else if self.eat(&Token::Op('-')) {
+                value = value.add(self.term()?, true)?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Option<Value> {
+        let mut value = self.unary()?;
+        loop {
+            value = if self.eat(&Token::Op('*')) || self.eat_word("of") {
+                value.mul(self.unary()?)?
+            } else if self.eat(&Token::Op('/')) {
+                value.div(self.unary()?)?
+            } else if self.eat(&Token::Op('%')) || self.eat_word("mod") {
+                value.rem(self.unary()?)?
+            } else if self.starts_operand(self.pos) && !matches!(self.peek(), Some(Token::Number(_))) {
+                value.mul(self.power()?)?
+            } else {
+                return Some(value);
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Option<Value> {
+        if self.eat(&Token::Op('-')) {
+            Some(self.unary()?.neg())
+        } else if self.eat(&Token::Op('+')) {
+            self.unary()
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Option<Value> {
+        let base = self.postfix()?;
+        if self.eat(&Token::Op('^')) {
+            base.pow(self.unary()?)
+        } else {
+            Some(base)
+        }
+    }
+
+    fn postfix(&mut self) -> Option<Value> {
+        let mut value = self.primary()?;
+        loop {
+            if self.eat(&Token::Op('!')) {
+                value = Value::plain(value.scalar()?.factorial()?);
+            } else if self.peek() == Some(&Token::Op('%')) && !self.starts_operand(self.pos + 1) {
+                // "10 % 3" is a remainder, "15%" and "15% of 80" are percentages
+                self.pos += 1;
+                value = value.percent()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Option<Value> {
+        let value = match self.next()? {
+            Token::Number(number) => Value::plain(number),
+            Token::LParen => {
+                let value = self.expression()?;
+                if !self.eat(&Token::RParen) {
+                    return None;
+                }
+                value
+            }
+            Token::Ident(name) => return self.identifier(&name),
+            _ => return None,
+        };
+
+        // A unit right after a number or parentheses: "5 km", "(2 + 3) kg"
+        if let Some(Token::Ident(name)) = self.peek() {
+            if let Some(unit) = find_unit(name) {
+                if value.unit.is_none() && !value.percent && self.tokens.get(self.pos + 1) != Some(&Token::LParen) {
+                    self.pos += 1;
+                    return Some(Value { unit: Some(unit), ..value });
+                }
+            }
+        }
+        Some(value)
+    }
+
+    fn identifier(&mut self, name: &str) -> Option<Value> {
+        if FUNCTIONS.contains(&name) {
+            let mut arguments = Vec::new();
+            if self.eat(&Token::LParen) {
+                loop {
+                    arguments.push(self.expression()?);
+                    if self.eat(&Token::RParen) {
+                        break;
+                    }
+                    if !self.eat(&Token::Comma) {
+                        return None;
+                    }
+                }
+            } else {
+                // "sqrt 16"
+                arguments.push(self.power()?);
+            }
+            return call(name, &arguments);
+        }
+
+        let number = match name {
+            "pi" | "π" => Float(PI),
+            "tau" | "τ" => Float(TAU),
+            "e" => Float(E),
+            "phi" | "φ" => Float((1.0 + 5f64.sqrt()) / 2.0),
+            "ans" => return self.memory.ans.as_ref()?.value(),
+            _ => return self.memory.variables.get(name)?.value(),
+        };
+        Some(Value::plain(number))
+    }
+}
+
+fn call(name: &str, arguments: &[Value]) -> Option<Value> {
+    // These keep the unit: "round(5.4 km)"
+    let rounding: Option<fn(Number) -> Number> = match name {
+        "abs" => Some(Number::abs),
+        "floor" => Some(Number::floor),
+        "ceil" => Some(Number::ceil),
+        "round" => Some(Number::round),
+        "trunc" => Some(Number::trunc),
+        _ => None,
+    };
+    if let (Some(rounding), [value]) = (rounding, arguments) {
+        let value = value.settled();
+        return Some(Value { number: rounding(value.number), ..value });
+    }
+
+    let numbers = arguments.iter().map(|value| value.scalar()).collect::<Option<Vec<Number>>>()?;
+    let float = match (name, numbers.as_slice()) {
+        ("min", [first, rest @ ..]) => {
+            return Some(Value::plain(rest.iter().fold(*first, |a, &b| if b.to_f64() < a.to_f64() { b } else { a })));
+        }
+        ("max", [first, rest @ ..]) => {
+            return Some(Value::plain(rest.iter().fold(*first, |a, &b| if b.to_f64() > a.to_f64() { b } else { a })));
+        }
+        ("log", [x, base]) => x.to_f64().ln() / base.to_f64().ln(),
+        (_, [x]) => {
+            let x = x.to_f64();
+            match name {
+                "sqrt" => x.sqrt(),
+                "cbrt" => x.cbrt(),
+                "sin" => x.sin(),
+                "cos" => x.cos(),
+                "tan" => x.tan(),
+                "asin" => x.asin(),
+                "acos" => x.acos(),
+                "atan" => x.atan(),
+                "sinh" => x.sinh(),
+                "cosh" => x.cosh(),
+                "tanh" => x.tanh(),
+                "ln" => x.ln(),
+                "log" | "log10" => x.log10(),
+                "log2" => x.log2(),
+                "exp" => x.exp(),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(Value::plain(Float(float)))
+}
+
+fn format_decimal(number: Number) -> String {
+    match number {
+        Ratio(num, 1) => num.to_string(),
+        Ratio(num, den) if number.to_f64().abs() >= 1e-9 => format_fraction(num, den).unwrap_or_else(|| format_float(number.to_f64())),
+        _ => format_float(number.to_f64()),
+    }
+}
+
+// Exact long division, rounded at MAX_DECIMALS
+fn format_fraction(num: i128, den: i128) -> Option<String> {
+    let den = den as u128;
+    let mut integer = num.unsigned_abs() / den;
+    let mut remainder = num.unsigned_abs() % den;
+
+    let mut digits = Vec::with_capacity(MAX_DECIMALS);
+    for _ in 0..MAX_DECIMALS {
+        remainder = remainder.checked_mul(10)?;
+        digits.push((remainder / den) as u8);
+        remainder %= den;
+    }
+
+    if remainder.checked_mul(2)? >= den {
+        // Round up, carrying through trailing nines
+        let mut carry = true;
+        for digit in digits.iter_mut().rev() {
+            if *digit == 9 {
+                *digit = 0;
+            } else {
+                *digit += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            integer = integer.checked_add(1)?;
+        }
+    }
+
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    let sign = if num < 0 && (integer != 0 || !digits.is_empty()) { "-" } else { "" };
+    let fraction: String = digits.iter().map(|digit| char::from(b'0' + digit)).collect();
+    Some(if fraction.is_empty() { format!("{}{}", sign, integer) } else { format!("{}{}.{}", sign, integer, fraction) })
+}
+
+fn format_float(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    let magnitude = value.abs().log10().floor() as i32;
+    if !(-9..15).contains(&magnitude) {
+        return format_scientific(value);
+    }
+
+    let decimals = (SIGNIFICANT_DIGITS as i32 - 1 - magnitude).clamp(0, MAX_DECIMALS as i32) as usize;
+    let text = trim_zeros(format!("{:.*}", decimals, value));
+    if text == "-0" { "0".to_string() } else { text }
+}
+
+fn format_scientific(value: f64) -> String {
+    let text = format!("{:.*e}", SIGNIFICANT_DIGITS - 1, value);
+    match text.split_once('e') {
+        Some((mantissa, exponent)) => format!("{}e{}", trim_zeros(mantissa.to_string()), exponent),
+        None => text,
+    }
+}
+
+fn trim_zeros(text: String) -> String {
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
+
+/// One way of writing a result, e.g. in hexadecimal
+pub struct Answer {
+    pub text: String,
+    pub label: String,
+}
+
+pub struct Calculation {
+    /// The variable the query assigns to, as in "x = 5 * 3"
+    pub assignment: Option<String>,
+    /// The preferred form first
+    pub answers: Vec<Answer>,
+}
+
+struct Statement {
+    assignment: Option<String>,
+    value: Value,
+    target: Option<Target>,
+    based: bool,
+}
+
+impl Statement {
+    fn parse(query: &str, memory: &Memory) -> Option<Self> {
+        let (tokens, based) = tokenize(query.trim())?;
+
+        // A bare number or quantity has nothing to calculate, unless it is written in scientific
+        // notation like the results are, then it gets written out
+        let scientific = query.contains(['e', 'E']);
+        match tokens.as_slice() {
+            [Token::Number(_)] if !based && !scientific => return None,
+            [Token::Number(_), Token::Ident(unit)] if find_unit(unit).is_some() => return None,
+            [Token::Ident(name)] if name == "e" => return None,
+            _ => {}
+        }
+
+        let (assignment, body) = match tokens.as_slice() {
+            [Token::Ident(name), Token::Equals, body @ ..] => {
+                let reserved = name == "ans" || is_keyword(name) || FUNCTIONS.contains(&name.as_str()) || CONSTANTS.contains(&name.as_str());
+                if reserved {
+                    return None;
+                }
+                (Some(name.clone()), body)
+            }
+            _ => (None, tokens.as_slice()),
+        };
+
+        let (body, target) = split_target(body);
+        let mut parser = Parser { tokens: body, pos: 0, memory };
+        let value = parser.expression()?;
+        if parser.pos != body.len() {
+            return None;
+        }
+
+        let value = match target {
+            Some(Target::Unit(unit)) => value.converted(unit)?,
+            _ => value.settled(),
+        };
+        if !value.number.to_f64().is_finite() {
+            return None;
+        }
+
+        Some(Self { assignment, value, target, based })
+    }
+
+    fn answers(&self) -> Vec<Answer> {
+        if let Some(unit) = self.value.unit {
+            let label = if self.target.is_some() { "Unit conversion" } else { "Quantity" };
+            return vec![Answer {
+                text: format!("{} {}", format_decimal(self.value.number), unit.symbol),
+                label: label.to_string(),
+            }];
+        }
+
+        let number = self.value.number;
+        let magnitude = number.to_f64().abs();
+
+        let mut bases = match self.target {
+            Some(Target::Base(base)) => vec![base, Base::Decimal],
+            _ => vec![Base::Decimal],
+        };
+        if number.as_integer().is_some() {
+            if self.based || matches!(self.target, Some(Target::Base(_))) {
+                bases.extend([Base::Hex, Base::Binary]);
+            } else if magnitude >= 256.0 {
+                bases.push(Base::Hex);
+            }
+        }
+        if magnitude >= 1e10 || (magnitude != 0.0 && magnitude < 1e-4) {
+            bases.push(Base::Scientific);
+        }
+
+        let mut answers: Vec<Answer> = Vec::new();
+        for base in bases {
+            // Long binary strings are only worth showing when asked for
+            if base == Base::Binary && magnitude >= 4294967296.0 && !matches!(self.target, Some(Target::Base(Base::Binary))) {
+                continue;
+            }
+            let Some(text) = base.format(number) else {
+                continue;
+            };
+            if answers.iter().all(|answer| answer.text != text) {
+                answers.push(Answer { text, label: base.label().to_string() });
+            }
+        }
+        answers.truncate(MAX_ANSWERS);
+        answers
+    }
+}
+
+/// Evaluates `query`, None when it isn't a calculation
+pub fn calculate(query: &str) -> Option<Calculation> {
+    let memory = Memory::load();
+    let statement = Statement::parse(query, &memory)?;
+    let answers = statement.answers();
+    if answers.is_empty() {
+        return None;
+    }
+
+    Some(Calculation { assignment: statement.assignment, answers })
+}
+
+/// Keeps the result of `query` as `ans`, and stores its variable if it is an assignment.
+/// Called once a result is used, so typing doesn't change what `ans` means.
+pub fn remember(query: &str) {
+    let mut memory = Memory::load();
+    let Some(statement) = Statement::parse(query, &memory) else {
+        return;
+    };
+
+    let stored = StoredValue {
+        number: statement.value.number,
+        unit: statement.value.unit.map(|unit| unit.symbol.to_string()),
+    };
+    if let Some(name) = statement.assignment {
+        memory.variables.insert(name, stored.clone());
+    }
+    memory.ans = Some(stored);
+    memory.save();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredValue {
+    number: Number,
+    #[serde(default)]
+    unit: Option<String>,
+}
+
+impl StoredValue {
+    fn value(&self) -> Option<Value> {
+        let unit = match &self.unit {
+            Some(symbol) => Some(find_unit(symbol)?),
+            None => None,
+        };
+        Some(Value { number: self.number, unit, percent: false })
+    }
+}
+
+// `ans` and the variables, kept between launches
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Memory {
+    #[serde(default)]
+    ans: Option<StoredValue>,
+    #[serde(default)]
+    variables: BTreeMap<String, StoredValue>,
+}
+
+impl Memory {
+    fn path() -> PathBuf {
+        if let Some(config_dir) = dirs::config_dir() {
+            let app_dir = config_dir.join("quick_search");
+            let _ = fs::create_dir_all(&app_dir);
+            return app_dir.join("calculator.json");
+        }
+
+        // Fallback
+        PathBuf::from("calculator.json")
+    }
+
+    fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => {
+                if let Err(e) = fs::write(Self::path(), json) {
+                    eprintln!("Failed to save calculator variables: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize calculator variables: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers_with(query: &str, memory: &Memory) -> Vec<String> {
+        Statement::parse(query, memory)
+            .map(|statement| statement.answers().into_iter().map(|answer| answer.text).collect())
+            .unwrap_or_default()
+    }
+
+    fn answers(query: &str) -> Vec<String> {
+        answers_with(query, &Memory::default())
+    }
+
+    fn first(query: &str) -> Option<String> {
+        answers(query).into_iter().next()
+    }
+
+    #[test]
+    fn readme_examples() {
+        assert_eq!(first("15% of 80").as_deref(), Some("12"));
+        assert_eq!(
+            answers("2^100"),
+            ["1267650600228229401496703205376", "0x10000000000000000000000000", "1.26765060023e30"]
+        );
+        assert_eq!(first("5 km to mi").as_deref(), Some("3.10685596119 mi"));
+        assert_eq!(first("100 F in C").as_deref(), Some("37.7777777778 °C"));
+        assert_eq!(answers("0xff to bin"), ["0b11111111", "255", "0xFF"]);
+    }
+
+    #[test]
+    fn decimals_stay_exact() {
+        assert_eq!(first("0.1 + 0.2").as_deref(), Some("0.3"));
+        assert_eq!(first("1/3").as_deref(), Some("0.333333333333"));
+        assert_eq!(first("2 ^ -2").as_deref(), Some("0.25"));
+    }
+
+    #[test]
+    fn bare_numbers_are_not_calculations() {
+        assert!(answers("42").is_empty());
+        assert!(answers("5 km").is_empty());
+        assert!(answers("e").is_empty());
+        assert!(answers("1 / 0").is_empty());
+    }
+
+    #[test]
+    fn scientific_notation_can_be_pasted_back() {
+        assert_eq!(first("1e3").as_deref(), Some("1000"));
+        assert_eq!(first("1e-5").as_deref(), Some("0.00001"));
+        assert_eq!(first("1.26765060023e30").as_deref(), Some("1267650600230000000000000000000"));
+        assert_eq!(first("1.60693804426e60 * 1").as_deref(), Some("1.60693804426e60"));
+        // Without digits after it, e is the constant
+        assert_eq!(first("2e").as_deref(), Some("5.43656365692"));
+    }
+
+    #[test]
+    fn percentages() {
+        assert_eq!(first("50%").as_deref(), Some("0.5"));
+        assert_eq!(first("10 + 50%").as_deref(), Some("15"));
+        assert_eq!(first("200 - 10%").as_deref(), Some("180"));
+        // Only a percentage on the right adjusts the left side, on its own it is just a fraction
+        assert_eq!(first("50% + 10").as_deref(), Some("10.5"));
+    }
+
+    #[test]
+    fn inches_are_not_mistaken_for_the_in_keyword() {
+        assert_eq!(first("5 in to cm").as_deref(), Some("12.7 cm"));
+        assert_eq!(first("5 in in cm").as_deref(), Some("12.7 cm"));
+        assert!(answers("5 ft to m + 1").is_empty());
+    }
+
+    #[test]
+    fn overflow_falls_back_to_floats() {
+        assert_eq!(first("170141183460469231731687303715884105727 + 1").as_deref(), Some("1.7014118346e38"));
+        assert_eq!(first("2^127").as_deref(), Some("1.7014118346e38"));
+        assert_eq!(first("2^200").as_deref(), Some("1.60693804426e60"));
+        assert_eq!(first("30!").as_deref(), Some("265252859812191058636308480000000"));
+    }
+
+    #[test]
+    fn variables_and_ans() {
+        let statement = Statement::parse("x = 3 * 4", &Memory::default()).unwrap();
+        assert_eq!(statement.assignment.as_deref(), Some("x"));
+
+        let twelve = StoredValue { number: Ratio(12, 1), unit: None };
+        let mut memory = Memory { ans: Some(twelve.clone()), ..Default::default() };
+        memory.variables.insert("x".to_string(), twelve);
+        assert_eq!(answers_with("x / 5", &memory), ["2.4"]);
+        assert_eq!(answers_with("ans + 1", &memory), ["13"]);
+        // Names of functions and constants can't be assigned
+        assert!(Statement::parse("pi = 3", &memory).is_none());
+    }
+}
@@ -0,0 +1,479 @@
+// Clipboard history recorded by the daemon, kept in the config dir.
+// Text is stored inline, images as PNG files next to the history.
+
+use std::fs::{self, DirBuilder, OpenOptions, Permissions};
+use std::io::{Cursor, Read, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::time::Duration;
+
+use anyhow::bail;
+use arboard::{Clipboard, ImageData};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use wl_clipboard_rs::{paste::{self, ClipboardType, MimeType, Seat}, watch};
+
+use crate::utils::{helpers::helpers::{copy_to_clipboard, is_command_available}, live_settings, settings_manager::ClipboardSettings};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(750);
+// Bigger copies are usually whole files, which the history would only bloat
+const MAX_TEXT_BYTES: usize = 1 << 20;
+const MAX_IMAGE_BYTES: usize = 32 << 20;
+
+// Serializes read-modify-write of the history file between the watcher and the bar
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ClipContent {
+    Text { text: String },
+    Image { path: PathBuf, width: u32, height: u32 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClipEntry {
+    // Hash of the content, the same copy twice gets the same id
+    pub id: String,
+    pub content: ClipContent,
+    #[serde(default)]
+    pub pinned: bool,
+    // Unix seconds of the last time it was copied
+    pub copied_at: i64,
+}
+
+// What the watcher read from the clipboard
+enum Captured {
+    Text(String),
+    // PNG encoded, as Wayland hands images over
+    Png(Vec<u8>),
+    // From X11, only encoded once it turns out to be a new copy
+    Pixels(image::RgbaImage),
+}
+
+impl Captured {
+    fn id(&self) -> String {
+        let hash = match self {
+            Captured::Text(text) => fnv1a(std::iter::once(b't').chain(text.bytes())),
+            Captured::Png(png) => fnv1a(std::iter::once(b'i').chain(png.iter().copied())),
+            Captured::Pixels(pixels) => {
+                let size = [pixels.width().to_le_bytes(), pixels.height().to_le_bytes()].concat();
+                fnv1a(std::iter::once(b'p').chain(size).chain(pixels.as_raw().iter().copied()))
+            }
+        };
+        format!("{:016x}", hash)
+    }
+}
+
+// Stable across builds, unlike `DefaultHasher`, so ids in the file stay valid
+fn fnv1a(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn app_dir() -> PathBuf {
+    if let Some(config_dir) = dirs::config_dir() {
+        let app_dir = config_dir.join("quick_search");
+        let _ = fs::create_dir_all(&app_dir);
+        return app_dir;
+    }
+
+    // Fallback
+    PathBuf::from(".")
+}
+
+fn history_path() -> PathBuf {
+    app_dir().join("clipboard_history.json")
+}
+
+fn images_dir() -> PathBuf {
+    app_dir().join("clipboard_images")
+}
+
+// Copies can be secrets, so the history and its images are only readable by the user
+fn write_private(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+    // The mode only applies to new files
+    file.set_permissions(Permissions::from_mode(0o600))?;
+    file.write_all(bytes)
+}
+
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    fs::set_permissions(dir, Permissions::from_mode(0o700))
+}
+
+/// Recorded entries, most recent first
+pub fn entries() -> Vec<ClipEntry> {
+    fs::read_to_string(history_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(entries: &[ClipEntry]) -> anyhow::Result<()> {
+    // Written whole and renamed, so a launcher reading it at the same time never sees half a file
+    let path = history_path();
+    let temp = path.with_extension("json.tmp");
+    write_private(&temp, serde_json::to_string_pretty(entries)?.as_bytes())?;
+    fs::rename(temp, path)?;
+    Ok(())
+}
+
+fn update(change: impl FnOnce(&mut Vec<ClipEntry>)) {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut all = entries();
+    change(&mut all);
+    if let Err(e) = save(&all) {
+        eprintln!("Failed to save clipboard history: {}", e);
+    }
+}
+
+fn remove_image(entry: &ClipEntry) {
+    if let ClipContent::Image { path, .. } = &entry.content {
+        let _ = fs::remove_file(path);
+    }
+}
+
+// Moves a known copy to the top, or adds it, then drops the oldest unpinned entries over the limit
+fn record(captured: Captured, max_entries: usize) {
+    let id = captured.id();
+    let now = Utc::now().timestamp();
+
+    update(|all| {
+        if let Some(index) = all.iter().position(|entry| entry.id == id) {
+            let mut entry = all.remove(index);
+            entry.copied_at = now;
+            all.insert(0, entry);
+            return;
+        }
+
+        let content = match captured {
+            Captured::Text(text) => Ok(ClipContent::Text { text }),
+            Captured::Png(png) => save_image(&id, &png),
+            Captured::Pixels(pixels) => encode_png(&pixels).and_then(|png| save_image(&id, &png)),
+        };
+        let content = match content {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Failed to save clipboard image: {}", e);
+                return;
+            }
+        };
+        all.insert(0, ClipEntry { id, content, pinned: false, copied_at: now });
+
+        while all.len() > max_entries {
+            let Some(index) = all.iter().rposition(|entry| !entry.pinned) else {
+                break;
+            };
+            remove_image(&all.remove(index));
+        }
+    });
+}
+
+fn encode_png(pixels: &image::RgbaImage) -> anyhow::Result<Vec<u8>> {
+    let mut png = Vec::new();
+    pixels.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+    Ok(png)
+}
+
+fn save_image(id: &str, png: &[u8]) -> anyhow::Result<ClipContent> {
+    if png.len() > MAX_IMAGE_BYTES {
+        bail!("larger than {} MB", MAX_IMAGE_BYTES >> 20);
+    }
+    let (width, height) = image::ImageReader::with_format(Cursor::new(png), image::ImageFormat::Png).into_dimensions()?;
+    let dir = images_dir();
+    create_private_dir(&dir)?;
+    let path = dir.join(format!("{}.png", id));
+    write_private(&path, png)?;
+    Ok(ClipContent::Image { path, width, height })
+}
+
+/// Pins or unpins an entry. Pinned entries are listed first and never dropped for new ones.
+pub fn toggle_pin(id: &str) {
+    update(|all| {
+        if let Some(entry) = all.iter_mut().find(|entry| entry.id == id) {
+            entry.pinned = !entry.pinned;
+        }
+    });
+}
+
+pub fn remove(id: &str) {
+    update(|all| {
+        if let Some(index) = all.iter().position(|entry| entry.id == id) {
+            remove_image(&all.remove(index));
+        }
+    });
+}
+
+/// Puts a recorded entry back on the clipboard and moves it to the top
+pub fn copy_entry(id: &str) {
+    let Some(entry) = entries().into_iter().find(|entry| entry.id == id) else {
+        return;
+    };
+
+    match &entry.content {
+        ClipContent::Text { text } => copy_to_clipboard(text),
+        ClipContent::Image { path, .. } => {
+            if let Err(e) = copy_image(path) {
+                eprintln!("Failed to copy image: {}", e);
+            }
+        }
+    }
+
+    update(|all| {
+        if let Some(index) = all.iter().position(|entry| entry.id == id) {
+            let mut entry = all.remove(index);
+            entry.copied_at = Utc::now().timestamp();
+            all.insert(0, entry);
+        }
+    });
+}
+
+fn copy_image(path: &Path) -> anyhow::Result<()> {
+    if is_wayland() {
+        let mut child = Command::new("wl-copy")
+            .args(["--type", "image/png"])
+            .stdin(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(&fs::read(path)?)?;
+        }
+        return Ok(());
+    }
+
+    let image = image::open(path)?.to_rgba8();
+    Clipboard::new()?.set_image(ImageData {
+        width: image.width() as usize,
+        height: image.height() as usize,
+        bytes: image.into_raw().into(),
+    })?;
+    Ok(())
+}
+
+fn is_wayland() -> bool {
+    std::env::var("WAYLAND_DISPLAY").is_ok()
+}
+
+/// Records clipboard changes on a background thread for as long as the process runs.
+/// Only the first call starts it. The settings are read again on every change, so turning
+/// the history off or changing its limits applies right away.
+pub fn start_watcher() {
+    static STARTED: Once = Once::new();
+    STARTED.call_once(|| {
+        std::thread::spawn(|| {
+            let changes = is_wayland().then(SelectionChanges::watch);
+            let mut watcher = Watcher { x11: None, changes, seen: 0, last: None, warned_unchecked: false };
+            if let Err(e) = watcher.run() {
+                eprintln!("Clipboard history stopped: {}", e);
+            }
+        });
+    });
+}
+
+// Counts the selection changes the compositor reports, so an unchanged clipboard isn't read again
+struct SelectionChanges {
+    count: AtomicU64,
+    // Cleared when the compositor can't be watched, then every poll reads the clipboard
+    watching: AtomicBool,
+}
+
+impl SelectionChanges {
+    fn watch() -> Arc<Self> {
+        let changes = Arc::new(Self { count: AtomicU64::new(0), watching: AtomicBool::new(true) });
+
+        let counter = Arc::clone(&changes);
+        std::thread::spawn(move || {
+            let watched = watch::Watcher::new(watch::ClipboardType::Regular, Seat::Unspecified).and_then(|mut watcher| {
+                // The first event is the current selection
+                while watcher.next_event()?.is_some() {
+                    counter.count.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok(())
+            });
+            if let Err(e) = watched {
+                eprintln!("Can't watch the clipboard for changes, reading it on every poll: {}", e);
+            }
+            counter.watching.store(false, Ordering::Relaxed);
+        });
+
+        changes
+    }
+}
+
+struct Watcher {
+    // Kept open between polls, arboard starts a thread per clipboard
+    x11: Option<Clipboard>,
+    // Only on Wayland, X11 clipboards are read on every poll
+    changes: Option<Arc<SelectionChanges>>,
+    // The change count at the last read
+    seen: u64,
+    // Id of the last thing seen, so an unchanged clipboard is not recorded again
+    last: Option<String>,
+    // Whether it was reported that excluded types can't be checked
+    warned_unchecked: bool,
+}
+
+impl Watcher {
+    fn run(&mut self) -> anyhow::Result<()> {
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let settings = &live_settings::current().clipboard;
+            if !settings.enabled || !self.changed() {
+                continue;
+            }
+
+            let Some(captured) = self.read(settings.record_images)? else {
+                continue;
+            };
+            let id = captured.id();
+            if self.last.as_ref() == Some(&id) {
+                continue;
+            }
+            self.last = Some(id);
+
+            if self.is_excluded(settings) {
+                continue;
+            }
+            record(captured, settings.max_entries.max(1));
+        }
+    }
+
+    fn changed(&mut self) -> bool {
+        let Some(changes) = &self.changes else {
+            return true;
+        };
+        if !changes.watching.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let count = changes.count.load(Ordering::Relaxed);
+        let changed = count != self.seen;
+        self.seen = count;
+        changed
+    }
+
+    // Password managers mark secrets with an extra type, e.g. KeePassXC offers "x-kde-passwordManagerHint".
+    // When the offered types can't be listed nothing is recorded, it could be a secret.
+    fn is_excluded(&mut self, settings: &ClipboardSettings) -> bool {
+        if settings.excluded_types.is_empty() {
+            return false;
+        }
+
+        let Some(offered) = offered_types() else {
+            if !self.warned_unchecked {
+                self.warned_unchecked = true;
+                let hint = if is_wayland() { "" } else { ", install xclip or clear the excluded types in settings" };
+                eprintln!("Not recording the clipboard, its types can't be checked for excluded ones{}", hint);
+            }
+            return true;
+        };
+        offered.iter().any(|offered| settings.excluded_types.iter().any(|excluded| excluded == offered))
+    }
+
+    // Errors are only returned when the clipboard can't be watched at all
+    fn read(&mut self, images: bool) -> anyhow::Result<Option<Captured>> {
+        if is_wayland() {
+            return read_wayland(images);
+        }
+
+        if self.x11.is_none() {
+            self.x11 = Some(Clipboard::new()?);
+        }
+        let Some(clipboard) = self.x11.as_mut() else {
+            return Ok(None);
+        };
+
+        if let Ok(text) = clipboard.get_text() {
+            return Ok(accept_text(text));
+        }
+        if !images {
+            return Ok(None);
+        }
+        let Ok(image) = clipboard.get_image() else {
+            return Ok(None);
+        };
+        // Encoding waits until the pixels turn out to be a new copy
+        Ok(image::RgbaImage::from_raw(image.width as u32, image.height as u32, image.bytes.into_owned()).map(Captured::Pixels))
+    }
+}
+
+fn accept_text(text: String) -> Option<Captured> {
+    (!text.trim().is_empty() && text.len() <= MAX_TEXT_BYTES).then_some(Captured::Text(text))
+}
+
+fn accept_image(png: Vec<u8>) -> Option<Captured> {
+    (!png.is_empty() && png.len() <= MAX_IMAGE_BYTES).then_some(Captured::Png(png))
+}
+
+fn read_wayland(images: bool) -> anyhow::Result<Option<Captured>> {
+    let types = match paste::get_mime_types(ClipboardType::Regular, Seat::Unspecified) {
+        Ok(types) => types,
+        Err(paste::Error::NoSeats | paste::Error::ClipboardEmpty) => return Ok(None),
+        // Reading the clipboard without focus needs the data control protocol, which GNOME lacks
+        Err(e) => bail!(e),
+    };
+
+    let is_text = types.iter().any(|t| t.starts_with("text/") || t == "UTF8_STRING" || t == "STRING");
+    let mime_type = if is_text {
+        MimeType::Text
+    } else if images && types.contains("image/png") {
+        MimeType::Specific("image/png")
+    } else {
+        return Ok(None);
+    };
+
+    let (pipe, _) = match paste::get_contents(ClipboardType::Regular, Seat::Unspecified, mime_type) {
+        Ok(contents) => contents,
+        Err(paste::Error::NoSeats | paste::Error::ClipboardEmpty | paste::Error::NoMimeType) => return Ok(None),
+        Err(e) => bail!(e),
+    };
+    let limit = if is_text { MAX_TEXT_BYTES } else { MAX_IMAGE_BYTES };
+    let mut bytes = Vec::new();
+    pipe.take(limit as u64 + 1).read_to_end(&mut bytes)?;
+
+    if is_text {
+        Ok(String::from_utf8(bytes).ok().and_then(accept_text))
+    } else {
+        Ok(accept_image(bytes))
+    }
+}
+
+// MIME types (or X11 targets) the current clipboard owner offers, None when they can't be listed
+fn offered_types() -> Option<Vec<String>> {
+    if is_wayland() {
+        return match paste::get_mime_types(ClipboardType::Regular, Seat::Unspecified) {
+            Ok(types) => Some(types.into_iter().collect()),
+            Err(paste::Error::NoSeats | paste::Error::ClipboardEmpty) => Some(Vec::new()),
+            Err(_) => None,
+        };
+    }
+
+    // arboard can't list targets, xclip can
+    if !is_command_available("xclip") {
+        return None;
+    }
+    let output = Command::new("xclip")
+        .args(["-selection", "clipboard", "-o", "-t", "TARGETS"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect())
+}
+
+/// "5 minutes ago", for timestamps in Unix seconds
+pub fn describe_time(timestamp: i64) -> String {
+    let seconds = (Utc::now().timestamp() - timestamp).max(0);
+    let (count, unit) = match seconds {
+        0..=59 => return "just now".to_string(),
+        60..=3599 => (seconds / 60, "minute"),
+        3600..=86399 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day"),
+    };
+    format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" })
+}
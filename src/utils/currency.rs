@@ -0,0 +1,385 @@
+// Currency conversion from a rate table cached in the config dir, refreshed in the background
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+
+use anyhow::{anyhow, bail, Context};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{helpers::helpers::is_command_available, paths::expand_user_path, settings_manager::CurrencySettings};
+
+const FETCH_TIMEOUT_SECS: u32 = 15;
+// Wait before trying a source again after it failed, so an offline machine isn't asked on every keystroke
+const RETRY_AFTER_SECS: u64 = 600;
+
+// Currency signs people type instead of codes
+const SYMBOLS: &[(char, &str)] = &[
+    ('$', "USD"),
+    ('€', "EUR"),
+    ('£', "GBP"),
+    ('¥', "JPY"),
+    ('₹', "INR"),
+    ('₩', "KRW"),
+    ('₽', "RUB"),
+    ('₺', "TRY"),
+    ('₿', "BTC"),
+];
+
+/// Exchange rates relative to one base currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateTable {
+    pub base: String,
+    // Currency code -> units of it per unit of `base`
+    pub rates: BTreeMap<String, f64>,
+    // Unix seconds: when the source published the rates, and when they were downloaded
+    pub published: i64,
+    pub fetched: i64,
+    // The configured source the rates came from, a changed source makes them stale
+    #[serde(default)]
+    pub source: String,
+}
+
+impl RateTable {
+    fn path() -> PathBuf {
+        if let Some(config_dir) = dirs::config_dir() {
+            let app_dir = config_dir.join("quick_search");
+            let _ = fs::create_dir_all(&app_dir);
+            return app_dir.join("currency_rates.json");
+        }
+
+        // Fallback
+        PathBuf::from("currency_rates.json")
+    }
+
+    // When the cache file was last written, also by another process like `--refresh-rates`
+    fn modified() -> Option<SystemTime> {
+        fs::metadata(Self::path()).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(Self::path()).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        fs::write(Self::path(), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn rate(&self, code: &str) -> Option<f64> {
+        self.rates.get(code).copied().filter(|rate| *rate > 0.0)
+    }
+
+    fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        Some(amount / self.rate(from)? * self.rate(to)?)
+    }
+
+    fn is_stale(&self, settings: &CurrencySettings) -> bool {
+        if settings.refresh_hours == 0 {
+            return false;
+        }
+        Utc::now().timestamp() - self.fetched >= (settings.refresh_hours * 3600) as i64 || self.source != settings.source.trim()
+    }
+}
+
+/// Gets the raw JSON of a rate table from somewhere
+pub trait RateFetcher {
+    fn fetch(&self) -> anyhow::Result<String>;
+}
+
+/// Downloads rates from an HTTP endpoint with curl
+pub struct HttpFetcher {
+    url: String,
+}
+
+impl RateFetcher for HttpFetcher {
+    fn fetch(&self) -> anyhow::Result<String> {
+        if !is_command_available("curl") {
+            bail!("curl is needed to download exchange rates");
+        }
+
+        let output = Command::new("curl")
+            .args(["--fail", "--silent", "--show-error", "--location", "--max-time"])
+            .arg(FETCH_TIMEOUT_SECS.to_string())
+            .arg(&self.url)
+            .output()?;
+        if !output.status.success() {
+            bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(String::from_utf8(output.stdout)?)
+    }
+}
+
+/// Reads rates from a local file, for offline setups or rates kept up to date by another tool
+pub struct FileFetcher {
+    path: PathBuf,
+}
+
+impl RateFetcher for FileFetcher {
+    fn fetch(&self) -> anyhow::Result<String> {
+        fs::read_to_string(&self.path).with_context(|| format!("can't read {}", self.path.display()))
+    }
+}
+
+/// The fetcher for a configured source: a http(s) URL, or a file path
+pub fn fetcher_for(source: &str) -> Box<dyn RateFetcher> {
+    let source = source.trim();
+    if source.starts_with("http://") || source.starts_with("https://") {
+        Box::new(HttpFetcher { url: source.to_string() })
+    } else {
+        let path = source.strip_prefix("file://").unwrap_or(source);
+        Box::new(FileFetcher { path: expand_user_path(path) })
+    }
+}
+
+/// Reads the usual shapes of rate APIs: a base currency under "base", "base_code" or "source",
+/// rates under "rates" or "conversion_rates", and a time under "time_last_update_unix",
+/// "timestamp" or "date".
+pub fn parse_rates(json: &str) -> anyhow::Result<RateTable> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+
+    let base = ["base", "base_code", "source"]
+        .iter()
+        .find_map(|key| value.get(key)?.as_str())
+        .ok_or_else(|| anyhow!("no base currency in the rates"))?
+        .to_uppercase();
+
+    let mut rates: BTreeMap<String, f64> = ["rates", "conversion_rates"]
+        .iter()
+        .find_map(|key| value.get(key)?.as_object())
+        .ok_or_else(|| anyhow!("no rates in the response"))?
+        .iter()
+        .filter_map(|(code, rate)| Some((code.to_uppercase(), rate.as_f64()?)))
+        .collect();
+    // Some APIs leave the base out
+    rates.insert(base.clone(), 1.0);
+
+    let now = Utc::now().timestamp();
+    let published = ["time_last_update_unix", "timestamp"]
+        .iter()
+        .find_map(|key| value.get(key)?.as_i64())
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(value.get("date")?.as_str()?, "%Y-%m-%d").ok()?;
+            Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp())
+        })
+        .unwrap_or(now);
+
+    Ok(RateTable { base, rates, published, fetched: now, source: String::new() })
+}
+
+/// Fetches the configured source and caches the result
+pub fn refresh_rates(settings: &CurrencySettings) -> anyhow::Result<RateTable> {
+    let json = fetcher_for(&settings.source).fetch()?;
+    let table = RateTable { source: settings.source.trim().to_string(), ..parse_rates(&json)? };
+    table.save()?;
+    Ok(table)
+}
+
+/// "100 usd to eur"
+pub struct CurrencyQuery {
+    pub amount: f64,
+    pub from: String,
+    pub to: String,
+}
+
+impl CurrencyQuery {
+    pub fn parse(query: &str) -> Option<Self> {
+        let query = query.trim();
+        let (source, target) = [" to ", " in ", " as "]
+            .iter()
+            .find_map(|separator| query.to_ascii_lowercase().rfind(separator).map(|index| (index, separator.len())))
+            .map(|(index, length)| (&query[..index], &query[index + length..]))?;
+
+        let (amount, from) = parse_amount(source.trim())?;
+        let to = parse_code(target.trim())?;
+        Some(Self { amount, from, to })
+    }
+}
+
+// "100 usd", "usd 100", "100usd", "$100", "100€" or just "usd"
+fn parse_amount(text: &str) -> Option<(f64, String)> {
+    let is_amount = |c: char| c.is_ascii_digit() || matches!(c, '.' | ',' | '_');
+
+    let (number, code): (String, String) = match text.chars().next()? {
+        c if is_amount(c) => {
+            let split = text.find(|c: char| !is_amount(c)).unwrap_or(text.len());
+            (text[..split].to_string(), text[split..].trim().to_string())
+        }
+        _ => {
+            let split = text.find(is_amount).unwrap_or(text.len());
+            (text[split..].trim().to_string(), text[..split].trim().to_string())
+        }
+    };
+
+    let amount = if number.is_empty() {
+        1.0
+    } else {
+        // Commas are thousands separators, "1,000.50"
+        number.chars().filter(|c| !matches!(c, ',' | '_')).collect::<String>().parse().ok()?
+    };
+    Some((amount, parse_code(&code)?))
+}
+
+fn parse_code(text: &str) -> Option<String> {
+    let mut chars = text.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return SYMBOLS.iter().find(|(symbol, _)| *symbol == c).map(|(_, code)| code.to_string());
+    }
+
+    (text.len() == 3 && text.chars().all(|c| c.is_ascii_alphabetic())).then(|| text.to_uppercase())
+}
+
+pub struct Conversion {
+    pub amount: f64,
+    pub from: String,
+    pub result: f64,
+    pub to: String,
+    // Units of `to` per unit of `from`
+    pub rate: f64,
+    pub published: i64,
+}
+
+/// The cached rate table, reloaded when the cache file changes and refreshed in the background when stale.
+/// Clones share the same table.
+#[derive(Clone)]
+pub struct CurrencyRates {
+    state: Arc<Mutex<RatesState>>,
+}
+
+#[derive(Default)]
+struct RatesState {
+    table: Option<RateTable>,
+    // Modification time of the cache file `table` was read from
+    loaded: Option<SystemTime>,
+    refreshing: bool,
+    // Set by the first conversion, nothing is downloaded before one asked for rates
+    used: bool,
+    // When which source last failed to fetch
+    failed: Option<(Instant, String)>,
+}
+
+impl CurrencyRates {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(RatesState::default())),
+        }
+    }
+
+    /// Reads the cache file if it changed since the last call, without downloading anything
+    pub fn reload(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.reload();
+        }
+    }
+
+    /// Like `reload` and, if the rates are missing, old or from another source, fetches new ones on a
+    /// background thread. Cheap enough to call on every conversion.
+    pub fn update(&self, settings: &CurrencySettings) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+
+        state.used = true;
+        state.reload();
+
+        let source = settings.source.trim();
+        let needs_refresh = state.table.as_ref().is_none_or(|table| table.is_stale(settings));
+        let failed_recently = state
+            .failed
+            .as_ref()
+            .is_some_and(|(at, failed)| failed == source && at.elapsed().as_secs() < RETRY_AFTER_SECS);
+        if !needs_refresh || failed_recently || state.refreshing || source.is_empty() {
+            return;
+        }
+
+        state.refreshing = true;
+        let shared = Arc::clone(&self.state);
+        let settings = settings.clone();
+        std::thread::spawn(move || {
+            let result = refresh_rates(&settings);
+            let Ok(mut state) = shared.lock() else {
+                return;
+            };
+            state.refreshing = false;
+
+            match result {
+                Ok(fresh) => {
+                    state.table = Some(fresh);
+                    state.loaded = RateTable::modified();
+                    state.failed = None;
+                }
+                // Offline is fine, the cached table keeps working
+                Err(e) => {
+                    eprintln!("Failed to refresh exchange rates: {}", e);
+                    state.failed = Some((Instant::now(), settings.source.trim().to_string()));
+                }
+            }
+        });
+    }
+
+    /// `update`, but only once a conversion asked for rates
+    pub fn update_if_used(&self, settings: &CurrencySettings) {
+        if self.state.lock().is_ok_and(|state| state.used) {
+            self.update(settings);
+        }
+    }
+
+    pub fn convert(&self, query: &CurrencyQuery) -> Option<Conversion> {
+        let state = self.state.lock().ok()?;
+        let table = state.table.as_ref()?;
+
+        Some(Conversion {
+            amount: query.amount,
+            from: query.from.clone(),
+            result: table.convert(query.amount, &query.from, &query.to)?,
+            to: query.to.clone(),
+            rate: table.convert(1.0, &query.from, &query.to)?,
+            published: table.published,
+        })
+    }
+}
+
+impl RatesState {
+    fn reload(&mut self) {
+        let modified = RateTable::modified();
+        if modified.is_some() && modified != self.loaded {
+            self.loaded = modified;
+            if let Some(table) = RateTable::load() {
+                self.table = Some(table);
+            }
+        }
+    }
+}
+
+/// Two decimals, more for amounts that would round to 0.00
+pub fn format_amount(value: f64) -> String {
+    let decimals = if value == 0.0 || value.abs() >= 0.01 {
+        2
+    } else {
+        // Three significant digits
+        (-value.abs().log10().floor()) as usize + 2
+    };
+    format!("{:.*}", decimals.min(10), value)
+}
+
+/// Rates get four significant digits past the point, "0.9235" or "156.4200"
+pub fn format_rate(rate: f64) -> String {
+    let decimals = if rate == 0.0 || rate.abs() >= 1.0 { 4 } else { (-rate.abs().log10().floor()) as usize + 3 };
+    format!("{:.*}", decimals.min(12), rate)
+}
+
+/// "3 hours ago", for timestamps in Unix seconds
+pub fn describe_age(timestamp: i64) -> String {
+    let hours = (Utc::now().timestamp() - timestamp).max(0) / 3600;
+    match hours {
+        0 => "less than an hour ago".to_string(),
+        1 => "1 hour ago".to_string(),
+        2..=47 => format!("{} hours ago", hours),
+        _ => format!("{} days ago", hours / 24),
+    }
+}
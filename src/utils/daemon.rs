@@ -0,0 +1,138 @@
+// Resident launcher: a hidden window that clients show and hide over a Unix socket
+
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+const SOCKET_NAME: &str = "quick_search.sock";
+const CLIENT_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DaemonCommand {
+    Show,
+    Hide,
+    Toggle,
+}
+
+impl DaemonCommand {
+    pub fn from_flag(flag: &str) -> Option<Self> {
+        match flag {
+            "--show" => Some(Self::Show),
+            "--hide" => Some(Self::Hide),
+            "--toggle" => Some(Self::Toggle),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Show => "show",
+            Self::Hide => "hide",
+            Self::Toggle => "toggle",
+        }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "show" => Some(Self::Show),
+            "hide" => Some(Self::Hide),
+            "toggle" => Some(Self::Toggle),
+            _ => None,
+        }
+    }
+}
+
+pub fn socket_path() -> PathBuf {
+    match dirs::runtime_dir() {
+        Some(dir) => dir.join(SOCKET_NAME),
+        // Without XDG_RUNTIME_DIR, keep sockets of different users apart in /tmp
+        None => {
+            let user = std::env::var("USER").unwrap_or_default();
+            std::env::temp_dir().join(format!("quick_search-{}.sock", user))
+        }
+    }
+}
+
+/// Sends a command to a running daemon, failing if none is listening
+pub fn send_command(command: DaemonCommand) -> Result<()> {
+    let mut stream = UnixStream::connect(socket_path())?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+
+    writeln!(stream, "{}", command.as_str())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+
+    match reply.trim() {
+        "ok" => Ok(()),
+        other => Err(anyhow!("Unexpected reply from daemon: {}", other)),
+    }
+}
+
+pub struct DaemonServer {
+    listener: UnixListener,
+}
+
+impl DaemonServer {
+    /// Binds the socket, replacing a stale one left behind by a daemon that crashed
+    pub fn bind() -> Result<Self> {
+        let path = socket_path();
+
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Err(anyhow!("A daemon is already listening on {}", path.display()));
+            }
+            fs::remove_file(&path)?;
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        Ok(Self { listener })
+    }
+
+    /// Accepts clients on a background thread. `wake` is called after each command is queued,
+    /// so the UI can pick it up even while the window is hidden and not repainting.
+    pub fn listen(self, wake: impl Fn() + Send + 'static) -> Receiver<DaemonCommand> {
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for stream in self.listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+
+                let mut line = String::new();
+                if BufReader::new(&stream).read_line(&mut line).is_err() {
+                    continue;
+                }
+
+                match DaemonCommand::parse(&line) {
+                    Some(command) => {
+                        if sender.send(command).is_err() {
+                            // The window is gone, nothing left to serve
+                            break;
+                        }
+                        let _ = writeln!(stream, "ok");
+                        wake();
+                    }
+                    None => {
+                        let _ = writeln!(stream, "error: unknown command");
+                    }
+                }
+            }
+        });
+
+        receiver
+    }
+}
+
+impl Drop for DaemonServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(socket_path());
+    }
+}
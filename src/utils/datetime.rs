@@ -0,0 +1,619 @@
+// Date and time answers: the time anywhere, conversions between zones, date arithmetic
+// and Unix timestamps
+
+use chrono::{Days, Months, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+use crate::utils::{calculator::Answer, zoneinfo::{find_zone, TimeZone}};
+
+// Timestamps this large are taken as milliseconds
+const MILLISECOND_THRESHOLD: i64 = 100_000_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Number(i64),
+    // Lowercase
+    Word(String),
+    Plus,
+    Minus,
+}
+
+// Months and days move the calendar, so a day is still a day across a DST change
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    months: i32,
+    days: i64,
+    seconds: i64,
+}
+
+impl Span {
+    fn checked_add(self, other: Span) -> Option<Span> {
+        Some(Span {
+            months: self.months.checked_add(other.months)?,
+            days: self.days.checked_add(other.days)?,
+            seconds: self.seconds.checked_add(other.seconds)?,
+        })
+    }
+
+    fn checked_neg(self) -> Option<Span> {
+        Some(Span { months: self.months.checked_neg()?, days: self.days.checked_neg()?, seconds: self.seconds.checked_neg()? })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Value {
+    // A Unix time, and whether a time of day was given rather than just a date
+    Moment { timestamp: i64, has_time: bool },
+    Span(Span),
+}
+
+/// Answers for `query`, empty when it isn't about dates or times
+pub fn evaluate(query: &str) -> Vec<Answer> {
+    evaluate_at(query, &TimeZone::local(), Utc::now().timestamp())
+}
+
+// `evaluate` in the zone `local`, at the Unix time `now`
+fn evaluate_at(query: &str, local: &TimeZone, now: i64) -> Vec<Answer> {
+    let query = query.trim();
+    let lower = query.to_ascii_lowercase();
+
+    // "unix 1760000000" or "unix 2026-12-25"
+    for prefix in ["unix ", "epoch ", "timestamp "] {
+        if let Some(rest) = lower.strip_prefix(prefix) {
+            return match rest.trim().parse::<i64>() {
+                Ok(number) => {
+                    let timestamp = if number.unsigned_abs() >= MILLISECOND_THRESHOLD as u64 { number / 1000 } else { number };
+                    moment_answers(timestamp, true, local, now)
+                }
+                Err(_) => match Expression::moment(rest, local, now) {
+                    Some((timestamp, _)) => vec![unix_answer(timestamp)],
+                    None => Vec::new(),
+                },
+            };
+        }
+    }
+
+    // "3pm PST to CET", "now in Tokyo", "2026-12-25 to unix"
+    for separator in [" to ", " in ", " as "] {
+        let Some(index) = lower.rfind(separator) else {
+            continue;
+        };
+        let (source, target) = (&query[..index], query[index + separator.len()..].trim());
+
+        if matches!(target.to_lowercase().as_str(), "unix" | "epoch" | "timestamp") {
+            if let Some((timestamp, _)) = Expression::moment(source, local, now) {
+                return vec![unix_answer(timestamp)];
+            }
+        }
+        if let Some(zone) = find_zone(target) {
+            if let Some((timestamp, has_time)) = moment_in_some_zone(source, local, now) {
+                return moment_answers(timestamp, has_time, &zone, now);
+            }
+        }
+    }
+
+    // "Tokyo time"
+    if let Some(place) = lower.strip_suffix(" time") {
+        if let Some(zone) = find_zone(place) {
+            return moment_answers(now, true, &zone, now);
+        }
+    }
+
+    match Expression::evaluate(query, local, now) {
+        Some(Value::Moment { timestamp, has_time }) => moment_answers(timestamp, has_time, local, now),
+        Some(Value::Span(span)) => span_answers(span),
+        None => Vec::new(),
+    }
+}
+
+// The source side of a conversion, which may end in its own zone: "3pm PST"
+fn moment_in_some_zone(text: &str, local: &TimeZone, now: i64) -> Option<(i64, bool)> {
+    if let Some(moment) = Expression::moment(text, local, now) {
+        return Some(moment);
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    (1..words.len().min(4)).find_map(|count| {
+        let (moment, zone) = words.split_at(words.len() - count);
+        let zone = find_zone(&zone.join(" "))?;
+        Expression::moment(&moment.join(" "), &zone, now)
+    })
+}
+
+struct Expression<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    zone: &'a TimeZone,
+    now: i64,
+}
+
+impl<'a> Expression<'a> {
+    fn parse(text: &str, zone: &'a TimeZone, now: i64) -> Option<Self> {
+        Some(Self { tokens: tokenize(text)?, pos: 0, zone, now })
+    }
+
+    fn evaluate(text: &str, zone: &TimeZone, now: i64) -> Option<Value> {
+        let mut expression = Expression::parse(text, zone, now)?;
+        let value = expression.sum()?;
+        let involves_moment = expression.tokens.iter().any(|token| match token {
+            Token::Date(_) | Token::Time(_) => true,
+            Token::Word(word) => matches!(word.as_str(), "now" | "time" | "ago") || moment_word(word).is_some(),
+            _ => false,
+        });
+        // Pure spans like "3 days + 4 hours" are left to the calculator
+        (expression.pos == expression.tokens.len() && involves_moment).then_some(value)
+    }
+
+    fn moment(text: &str, zone: &TimeZone, now: i64) -> Option<(i64, bool)> {
+        match Expression::evaluate(text, zone, now)? {
+            Value::Moment { timestamp, has_time } => Some((timestamp, has_time)),
+            Value::Span(_) => None,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn word(&self) -> Option<&str> {
+        match self.peek()? {
+            Token::Word(word) => Some(word),
+            _ => None,
+        }
+    }
+
+    fn sum(&mut self) -> Option<Value> {
+        let mut value = self.term()?;
+        loop {
+            let subtract = match self.peek() {
+                Some(Token::Plus) => false,
+                Some(Token::Minus) => true,
+                _ => return Some(value),
+            };
+            self.pos += 1;
+            let other = self.term()?;
+            value = self.combine(value, other, subtract)?;
+        }
+    }
+
+    fn combine(&self, a: Value, b: Value, subtract: bool) -> Option<Value> {
+        let signed = |span: Span| if subtract { span.checked_neg() } else { Some(span) };
+        match (a, b) {
+            (Value::Moment { timestamp, has_time }, Value::Span(span)) => {
+                let span = signed(span)?;
+                Some(Value::Moment { timestamp: self.shift(timestamp, span)?, has_time: has_time || span.seconds != 0 })
+            }
+            (Value::Span(span), Value::Moment { .. }) if !subtract => self.combine(b, Value::Span(span), false),
+            // Two dates are whole days apart by the calendar, even when a DST change makes one of them 23 hours
+            (Value::Moment { timestamp: a, has_time: false }, Value::Moment { timestamp: b, has_time: false }) if subtract => {
+                let days = (self.zone.local_time(a)?.0.date() - self.zone.local_time(b)?.0.date()).num_days();
+                Some(Value::Span(Span { months: 0, days, seconds: 0 }))
+            }
+            (Value::Moment { timestamp: a, .. }, Value::Moment { timestamp: b, .. }) if subtract => {
+                Some(Value::Span(Span { months: 0, days: 0, seconds: a.checked_sub(b)? }))
+            }
+            (Value::Span(a), Value::Span(b)) => Some(Value::Span(a.checked_add(signed(b)?)?)),
+            _ => None,
+        }
+    }
+
+    // Months and days move the calendar date in the zone, seconds move the instant
+    fn shift(&self, timestamp: i64, span: Span) -> Option<i64> {
+        let timestamp = if span.months == 0 && span.days == 0 {
+            timestamp
+        } else {
+            let (mut local, _) = self.zone.local_time(timestamp)?;
+            let months = Months::new(span.months.unsigned_abs());
+            local = if span.months >= 0 { local.checked_add_months(months)? } else { local.checked_sub_months(months)? };
+            let days = Days::new(span.days.unsigned_abs());
+            local = if span.days >= 0 { local.checked_add_days(days)? } else { local.checked_sub_days(days)? };
+            self.zone.timestamp(local)
+        };
+        timestamp.checked_add(span.seconds)
+    }
+
+    fn term(&mut self) -> Option<Value> {
+        let token = self.peek()?.clone();
+        self.pos += 1;
+
+        match token {
+            Token::Date(date) => Some(self.on_date(date)),
+            Token::Time(time) => self.at_time(self.today()?, time),
+            Token::Number(count) => {
+                let unit = self.word().and_then(span_unit)?;
+                self.pos += 1;
+                let span = Span {
+                    months: i32::try_from(count).ok()?.checked_mul(unit.months)?,
+                    days: count.checked_mul(unit.days)?,
+                    seconds: count.checked_mul(unit.seconds)?,
+                };
+                if self.word() == Some("ago") {
+                    self.pos += 1;
+                    return self.combine(Value::Moment { timestamp: self.now, has_time: true }, Value::Span(span), true);
+                }
+                Some(Value::Span(span))
+            }
+            Token::Word(word) if word == "now" || word == "time" => Some(Value::Moment { timestamp: self.now, has_time: true }),
+            Token::Word(word) => {
+                let days = moment_word(&word)?;
+                let date = self.today()?.checked_add_signed(chrono::Duration::days(days))?;
+                Some(self.on_date(date))
+            }
+            _ => None,
+        }
+    }
+
+    // A date, optionally followed by a time: "tomorrow 3pm"
+    fn on_date(&mut self, date: NaiveDate) -> Value {
+        if let Some(&Token::Time(time)) = self.peek() {
+            self.pos += 1;
+            if let Some(value) = self.at_time(date, time) {
+                return value;
+            }
+        }
+        Value::Moment { timestamp: self.zone.timestamp(date.and_time(NaiveTime::MIN)), has_time: false }
+    }
+
+    fn at_time(&self, date: NaiveDate, time: NaiveTime) -> Option<Value> {
+        Some(Value::Moment { timestamp: self.zone.timestamp(NaiveDateTime::new(date, time)), has_time: true })
+    }
+
+    fn today(&self) -> Option<NaiveDate> {
+        Some(self.zone.local_time(self.now)?.0.date())
+    }
+}
+
+// Days from today for words naming a day
+fn moment_word(word: &str) -> Option<i64> {
+    match word {
+        "today" | "date" => Some(0),
+        "tomorrow" => Some(1),
+        "yesterday" => Some(-1),
+        _ => None,
+    }
+}
+
+fn span_unit(word: &str) -> Option<Span> {
+    let (months, days, seconds) = match word {
+        "s" | "sec" | "secs" | "second" | "seconds" => (0, 0, 1),
+        "min" | "mins" | "minute" | "minutes" => (0, 0, 60),
+        "h" | "hr" | "hrs" | "hour" | "hours" => (0, 0, 3600),
+        "d" | "day" | "days" => (0, 1, 0),
+        "w" | "wk" | "week" | "weeks" => (0, 7, 0),
+        "mo" | "month" | "months" => (1, 0, 0),
+        "y" | "yr" | "year" | "years" => (12, 0, 0),
+        _ => return None,
+    };
+    Some(Span { months, days, seconds })
+}
+
+// Dates, times, numbers, words and +/-
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let text = text.to_lowercase();
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() || c == ',' {
+            i += 1;
+            continue;
+        }
+
+        let rest: String = chars[i..].iter().collect();
+        let token = if let Some((date, length)) = parse_date(&rest) {
+            i += length;
+            // "2026-12-25T15:00"
+            if chars.get(i) == Some(&'t') {
+                i += 1;
+            }
+            Token::Date(date)
+        } else if let Some((time, length)) = parse_time(&rest) {
+            i += length;
+            Token::Time(time)
+        } else if c.is_ascii_digit() {
+            let length = rest.chars().take_while(char::is_ascii_digit).count();
+            i += length;
+            Token::Number(rest[..length].parse().ok()?)
+        } else if c.is_alphabetic() {
+            let word: String = rest.chars().take_while(|c| c.is_alphabetic()).collect();
+            i += word.chars().count();
+            match word.as_str() {
+                "noon" => Token::Time(NaiveTime::from_hms_opt(12, 0, 0)?),
+                "midnight" => Token::Time(NaiveTime::MIN),
+                _ => Token::Word(word),
+            }
+        } else {
+            i += 1;
+            match c {
+                '+' => Token::Plus,
+                '-' | '−' => Token::Minus,
+                _ => return None,
+            }
+        };
+        tokens.push(token);
+    }
+
+    Some(tokens)
+}
+
+// "2026-12-25" at the start of `text`, and its length in chars
+fn parse_date(text: &str) -> Option<(NaiveDate, usize)> {
+    let mut parts = text.splitn(3, '-');
+    let year = parts.next()?;
+    let month = parts.next()?;
+    let rest = parts.next()?;
+    let day_length = rest.chars().take_while(char::is_ascii_digit).count();
+
+    if year.len() != 4 || !year.chars().all(|c| c.is_ascii_digit()) || !(1..=2).contains(&month.len()) || !(1..=2).contains(&day_length) {
+        return None;
+    }
+    let date = NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, rest[..day_length].parse().ok()?)?;
+    Some((date, year.len() + month.len() + day_length + 2))
+}
+
+// "15:00", "15:00:30", "3pm", "3:30 pm" at the start of `text`, and its length in chars
+fn parse_time(text: &str) -> Option<(NaiveTime, usize)> {
+    let chars: Vec<char> = text.chars().collect();
+    let number_at = |start: usize| -> (u32, usize) {
+        let length = chars[start..].iter().take_while(|c| c.is_ascii_digit()).count();
+        (chars[start..start + length].iter().collect::<String>().parse().unwrap_or(u32::MAX), length)
+    };
+
+    let (hour, mut length) = number_at(0);
+    if !(1..=2).contains(&length) {
+        return None;
+    }
+
+    let mut minute = 0;
+    let mut second = 0;
+    let mut has_minutes = false;
+    if chars.get(length) == Some(&':') {
+        let (value, digits) = number_at(length + 1);
+        if digits != 2 {
+            return None;
+        }
+        minute = value;
+        length += 3;
+        has_minutes = true;
+
+        if chars.get(length) == Some(&':') {
+            let (value, digits) = number_at(length + 1);
+            if digits != 2 {
+                return None;
+            }
+            second = value;
+            length += 3;
+        }
+    }
+
+    // An optional am/pm, maybe after a space
+    let spaces = chars[length..].iter().take_while(|c| **c == ' ').count();
+    let suffix: String = chars[length + spaces..].iter().take(2).collect();
+    let after_suffix = chars.get(length + spaces + 2);
+    let hour = if matches!(suffix.as_str(), "am" | "pm") && !after_suffix.is_some_and(|c| c.is_alphanumeric()) {
+        length += spaces + 2;
+        if !(1..=12).contains(&hour) {
+            return None;
+        }
+        hour % 12 + if suffix == "pm" { 12 } else { 0 }
+    } else if has_minutes {
+        hour
+    } else {
+        // A bare number is not a time
+        return None;
+    };
+
+    Some((NaiveTime::from_hms_opt(hour, minute, second)?, length))
+}
+
+fn moment_answers(timestamp: i64, has_time: bool, zone: &TimeZone, now: i64) -> Vec<Answer> {
+    let Some((local, kind)) = zone.local_time(timestamp) else {
+        return Vec::new();
+    };
+
+    if !has_time {
+        return vec![
+            Answer { text: local.format("%A, %-d %B %Y").to_string(), label: relative_day(local.date(), zone, now) },
+            Answer { text: local.format("%Y-%m-%d").to_string(), label: "ISO 8601 date".to_string() },
+        ];
+    }
+
+    vec![
+        Answer {
+            text: format!("{} {}", local.format("%a %-d %b %Y, %H:%M"), kind.abbreviation),
+            label: format!("Time in {}", zone.name),
+        },
+        Answer {
+            text: format!("{}{}", local.format("%Y-%m-%dT%H:%M:%S"), format_offset(kind.offset)),
+            label: "ISO 8601".to_string(),
+        },
+        unix_answer(timestamp),
+    ]
+}
+
+fn unix_answer(timestamp: i64) -> Answer {
+    Answer { text: timestamp.to_string(), label: "Unix timestamp".to_string() }
+}
+
+fn relative_day(date: NaiveDate, zone: &TimeZone, now: i64) -> String {
+    let Some((now, _)) = zone.local_time(now) else {
+        return String::new();
+    };
+
+    match (date - now.date()).num_days() {
+        0 => "Today".to_string(),
+        1 => "Tomorrow".to_string(),
+        -1 => "Yesterday".to_string(),
+        days if days > 0 => format!("In {} days", days),
+        days => format!("{} days ago", -days),
+    }
+}
+
+fn format_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    format!("{}{:02}:{:02}", sign, offset / 3600, offset % 3600 / 60)
+}
+
+fn span_answers(span: Span) -> Vec<Answer> {
+    // Months have no fixed length to count in days
+    if span.months != 0 {
+        return Vec::new();
+    }
+
+    // Days are shown as 24 hours here, the span is no longer tied to a date
+    let Some(seconds) = span.days.checked_mul(86400).and_then(|days| days.checked_add(span.seconds)) else {
+        return Vec::new();
+    };
+    let sign = if seconds < 0 { "-" } else { "" };
+    let total = seconds.unsigned_abs();
+    let (days, hours, minutes) = (total / 86400, total % 86400 / 3600, total % 3600 / 60);
+
+    let mut answers = Vec::new();
+    if total.is_multiple_of(86400) {
+        answers.push(Answer { text: format!("{}{}", sign, plural(days, "day")), label: "Days".to_string() });
+        if days >= 7 {
+            let weeks = format!("{}{} {}", sign, plural(days / 7, "week"), plural(days % 7, "day"));
+            answers.push(Answer { text: weeks, label: "Weeks".to_string() });
+        }
+    } else {
+        let text = if days > 0 {
+            format!("{}{} {} {}", sign, plural(days, "day"), plural(hours, "hour"), plural(minutes, "minute"))
+        } else {
+            format!("{}{} {}", sign, plural(hours, "hour"), plural(minutes, "minute"))
+        };
+        answers.push(Answer { text, label: "Duration".to_string() });
+        answers.push(Answer { text: format!("{}{}", sign, plural(total, "second")), label: "Seconds".to_string() });
+    }
+    answers
+}
+
+fn plural(count: u64, unit: &str) -> String {
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Berlin's rules: CEST from the last Sunday of March to the last Sunday of October
+    fn berlin() -> TimeZone {
+        TimeZone::from_posix("CET-1CEST,M3.5.0,M10.5.0/3").unwrap()
+    }
+
+    fn utc_timestamp(date: &str, time: &str) -> i64 {
+        NaiveDateTime::new(date.parse().unwrap(), time.parse().unwrap()).and_utc().timestamp()
+    }
+
+    // Sunday 2026-10-18, 12:00 in Berlin
+    fn now() -> i64 {
+        utc_timestamp("2026-10-18", "10:00:00")
+    }
+
+    fn texts(query: &str) -> Vec<String> {
+        evaluate_at(query, &berlin(), now()).into_iter().map(|answer| answer.text).collect()
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    fn time(hour: u32, minute: u32, second: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, second).unwrap()
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(parse_date("2026-12-25"), Some((date("2026-12-25"), 10)));
+        assert_eq!(parse_date("2026-2-5 + 3 days"), Some((date("2026-02-05"), 8)));
+        assert_eq!(parse_date("2026-12-25t15:00"), Some((date("2026-12-25"), 10)));
+        assert_eq!(parse_date("26-12-25"), None);
+        assert_eq!(parse_date("2026-13-01"), None);
+        assert_eq!(parse_date("2026-02-30"), None);
+        assert_eq!(parse_date("2026-12"), None);
+    }
+
+    #[test]
+    fn parses_times() {
+        assert_eq!(parse_time("15:00"), Some((time(15, 0, 0), 5)));
+        assert_eq!(parse_time("15:00:30 utc"), Some((time(15, 0, 30), 8)));
+        assert_eq!(parse_time("3pm"), Some((time(15, 0, 0), 3)));
+        assert_eq!(parse_time("3:30 pm"), Some((time(15, 30, 0), 7)));
+        assert_eq!(parse_time("12am"), Some((time(0, 0, 0), 4)));
+        assert_eq!(parse_time("12pm"), Some((time(12, 0, 0), 4)));
+        assert_eq!(parse_time("13pm"), None);
+        assert_eq!(parse_time("9:5"), None);
+        assert_eq!(parse_time("25:00"), None);
+        // A bare number is not a time, nor is "3 pmx"
+        assert_eq!(parse_time("15"), None);
+        assert_eq!(parse_time("3 pmx"), None);
+    }
+
+    #[test]
+    fn dates_are_whole_days_apart_across_dst() {
+        // Both ranges cross a DST change in Berlin
+        assert_eq!(texts("2026-12-25 - today"), ["68 days", "9 weeks 5 days"]);
+        assert_eq!(texts("2027-04-01 - 2027-03-01"), ["31 days", "4 weeks 3 days"]);
+        assert_eq!(texts("today - 2026-12-25"), ["-68 days", "-9 weeks 5 days"]);
+    }
+
+    #[test]
+    fn times_count_real_hours_across_dst() {
+        assert_eq!(texts("2027-03-28 12:00 - 2027-03-27 12:00"), ["23 hours 0 minutes", "82800 seconds"]);
+    }
+
+    #[test]
+    fn adding_days_keeps_the_date_across_dst() {
+        assert_eq!(texts("2027-03-20 + 14 days")[1], "2027-04-03");
+        assert_eq!(texts("2027-04-03 - 2 weeks")[1], "2027-03-20");
+        assert_eq!(texts("2026-01-31 + 1 month")[1], "2026-02-28");
+        // The wall clock time stays too
+        assert_eq!(texts("2027-03-27 09:00 + 1 day")[1], "2027-03-28T09:00:00+02:00");
+    }
+
+    #[test]
+    fn relative_days() {
+        let answers = evaluate_at("tomorrow", &berlin(), now());
+        assert_eq!(answers[0].text, "Monday, 19 October 2026");
+        assert_eq!(answers[0].label, "Tomorrow");
+        assert_eq!(texts("3 days ago")[1], "2026-10-15T12:00:00+02:00");
+    }
+
+    #[test]
+    fn unix_timestamps() {
+        let expected = ["Thu 9 Oct 2025, 10:53 CEST", "2025-10-09T10:53:20+02:00", "1760000000"];
+        assert_eq!(texts("unix 1760000000"), expected);
+        assert_eq!(texts("unix 1760000000000"), expected);
+        assert_eq!(texts("2025-10-09 10:53:20 to unix"), ["1760000000"]);
+    }
+
+    #[test]
+    fn conversions_between_zones() {
+        assert_eq!(texts("2026-10-18 3pm UTC to UTC+9")[0], "Mon 19 Oct 2026, 00:00 UTC+9");
+        assert_eq!(texts("noon in +05:30")[1], "2026-10-18T15:30:00+05:30");
+    }
+
+    #[test]
+    fn out_of_range_input_is_not_an_answer() {
+        assert!(texts("unix -9223372036854775808").is_empty());
+        assert!(texts("now + 9223372036854775807 seconds").is_empty());
+        assert!(texts("now + 9223372036854775807 days").is_empty());
+        assert!(texts("now - 9223372036854775807 seconds - 9223372036854775807 seconds").is_empty());
+    }
+
+    #[test]
+    fn non_ascii_input_is_not_an_answer() {
+        // Lowercasing 'İ' changes its length in bytes
+        assert!(texts("İ to €").is_empty());
+        assert!(texts("now in +a€").is_empty());
+    }
+
+    #[test]
+    fn pure_spans_are_left_to_the_calculator() {
+        assert!(texts("3 days + 4 hours").is_empty());
+        assert!(texts("hello").is_empty());
+    }
+}
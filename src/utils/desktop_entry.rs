@@ -0,0 +1,422 @@
+// Desktop Entry Specification parser:
+// https://specifications.freedesktop.org/desktop-entry-spec/latest/
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::utils::LaunchSpec;
+
+const MAIN_GROUP: &str = "Desktop Entry";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DesktopEntry {
+    /// Desktop file ID, e.g. `org.gnome.Nautilus.desktop` or `kde4-kate.desktop`
+    pub id: String,
+    pub path: PathBuf,
+    pub name: String,
+    pub generic_name: Option<String>,
+    pub comment: Option<String>,
+    pub keywords: Vec<String>,
+    pub categories: Vec<String>,
+    pub icon: Option<String>,
+    pub exec: Option<String>,
+    pub try_exec: Option<String>,
+    pub working_dir: Option<PathBuf>,
+    pub terminal: bool,
+    pub no_display: bool,
+    pub hidden: bool,
+    pub only_show_in: Vec<String>,
+    pub not_show_in: Vec<String>,
+    pub actions: Vec<DesktopAction>,
+}
+
+/// An additional way to start the application, from a `[Desktop Action <id>]` group
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DesktopAction {
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub exec: Option<String>,
+}
+
+impl DesktopEntry {
+    /// Parses a desktop file. Only `Type=Application` entries are accepted.
+    pub fn parse(path: &Path, id: &str, locale: &Locale) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let groups = parse_groups(&content);
+        let main = groups.get(MAIN_GROUP)?;
+
+        let string = |key: &str| main.get(key).map(|value| unescape(value));
+        let localized = |key: &str| locale.lookup(main, key).map(unescape);
+        let list = |key: &str| main.get(key).map(|value| split_list(value)).unwrap_or_default();
+        let localized_list = |key: &str| locale.lookup(main, key).map(split_list).unwrap_or_default();
+        let boolean = |key: &str| main.get(key).is_some_and(|value| value.trim() == "true");
+
+        if string("Type").as_deref() != Some("Application") {
+            return None;
+        }
+
+        let actions = list("Actions")
+            .into_iter()
+            .filter_map(|action_id| {
+                let group = groups.get(&format!("Desktop Action {action_id}"))?;
+                Some(DesktopAction {
+                    name: locale.lookup(group, "Name").map(unescape)?,
+                    icon: locale.lookup(group, "Icon").map(unescape),
+                    exec: group.get("Exec").map(|value| unescape(value)),
+                    id: action_id,
+                })
+            })
+            .collect();
+
+        Some(Self {
+            id: id.to_string(),
+            path: path.to_path_buf(),
+            name: localized("Name")?,
+            generic_name: localized("GenericName"),
+            comment: localized("Comment"),
+            keywords: localized_list("Keywords"),
+            categories: list("Categories"),
+            icon: localized("Icon"),
+            exec: string("Exec"),
+            try_exec: string("TryExec"),
+            working_dir: string("Path").filter(|dir| !dir.is_empty()).map(PathBuf::from),
+            terminal: boolean("Terminal"),
+            no_display: boolean("NoDisplay"),
+            hidden: boolean("Hidden"),
+            only_show_in: list("OnlyShowIn"),
+            not_show_in: list("NotShowIn"),
+            actions,
+        })
+    }
+
+    /// Whether the entry should be listed in the given desktop environments (`XDG_CURRENT_DESKTOP`)
+    pub fn is_shown_in(&self, desktops: &[String]) -> bool {
+        if self.hidden || self.no_display {
+            return false;
+        }
+
+        if !self.only_show_in.is_empty()
+            && !self.only_show_in.iter().any(|d| desktops.contains(d))
+        {
+            return false;
+        }
+
+        !self.not_show_in.iter().any(|d| desktops.contains(d))
+    }
+
+    /// The Exec line split into arguments, with field codes expanded for a launch without files or URLs
+    pub fn command_line(&self) -> Option<Vec<String>> {
+        self.expand_exec(self.exec.as_deref()?, self.icon.as_deref())
+    }
+
+    pub fn launch_spec(&self) -> Option<LaunchSpec> {
+        Some(LaunchSpec {
+            argv: self.command_line()?,
+            working_dir: self.working_dir.clone(),
+            env: Vec::new(),
+            terminal: self.terminal,
+        })
+    }
+
+    /// Actions run in the same environment as their application, only the command line differs
+    pub fn action_launch_spec(&self, action: &DesktopAction) -> Option<LaunchSpec> {
+        let icon = action.icon.as_deref().or(self.icon.as_deref());
+        Some(LaunchSpec {
+            argv: self.expand_exec(action.exec.as_deref()?, icon)?,
+            ..self.launch_spec().unwrap_or_default()
+        })
+    }
+
+    fn expand_exec(&self, exec: &str, icon: Option<&str>) -> Option<Vec<String>> {
+        let args = expand_field_codes(&split_exec(exec)?, icon, &self.name, &self.path);
+        (!args.is_empty()).then_some(args)
+    }
+}
+
+/// The `lang_COUNTRY.ENCODING@MODIFIER` locale used for `Key[locale]=` lookups
+pub struct Locale {
+    lang: Option<String>,
+    country: Option<String>,
+    modifier: Option<String>,
+}
+
+impl Locale {
+    pub fn from_env() -> Self {
+        let value = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+
+        Self::parse(&value)
+    }
+
+    pub fn parse(value: &str) -> Self {
+        let (rest, modifier) = match value.split_once('@') {
+            Some((rest, modifier)) => (rest, Some(modifier.to_string())),
+            None => (value, None),
+        };
+        let rest = rest.split('.').next().unwrap_or_default();
+        let (lang, country) = match rest.split_once('_') {
+            Some((lang, country)) => (lang, Some(country.to_string())),
+            None => (rest, None),
+        };
+
+        // "C" and "POSIX" mean untranslated
+        let lang = (!lang.is_empty() && lang != "C" && lang != "POSIX").then(|| lang.to_string());
+
+        Self { lang, country, modifier }
+    }
+
+    /// Identifies the locale for caching purposes
+    pub fn tag(&self) -> String {
+        format!(
+            "{}_{}@{}",
+            self.lang.as_deref().unwrap_or(""),
+            self.country.as_deref().unwrap_or(""),
+            self.modifier.as_deref().unwrap_or("")
+        )
+    }
+
+    // Spec order: lang_COUNTRY@MODIFIER, lang_COUNTRY, lang@MODIFIER, lang, then the untranslated key
+    fn lookup<'a>(&self, group: &'a HashMap<String, String>, key: &str) -> Option<&'a str> {
+        let mut candidates = Vec::new();
+
+        if let Some(lang) = &self.lang {
+            if let (Some(country), Some(modifier)) = (&self.country, &self.modifier) {
+                candidates.push(format!("{key}[{lang}_{country}@{modifier}]"));
+            }
+            if let Some(country) = &self.country {
+                candidates.push(format!("{key}[{lang}_{country}]"));
+            }
+            if let Some(modifier) = &self.modifier {
+                candidates.push(format!("{key}[{lang}@{modifier}]"));
+            }
+            candidates.push(format!("{key}[{lang}]"));
+        }
+        candidates.push(key.to_string());
+
+        candidates
+            .iter()
+            .find_map(|candidate| group.get(candidate))
+            .map(String::as_str)
+    }
+}
+
+/// XDG base data directories in precedence order: `$XDG_DATA_HOME` first, then `$XDG_DATA_DIRS`
+pub fn data_dirs() -> Vec<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local/share")));
+
+    let system_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in data_home.into_iter().chain(system_dirs.split(':').map(PathBuf::from)) {
+        if dir.is_absolute() && !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+
+    dirs
+}
+
+/// The `applications` directories in precedence order
+pub fn application_dirs() -> Vec<PathBuf> {
+    data_dirs().iter().map(|dir| dir.join("applications")).collect()
+}
+
+/// Desktop environments named in `$XDG_CURRENT_DESKTOP`
+pub fn current_desktops() -> Vec<String> {
+    std::env::var("XDG_CURRENT_DESKTOP")
+        .map(|value| value.split(':').filter(|d| !d.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Desktop file ID of `path` below `app_dir`: subdirectory separators become dashes
+pub fn desktop_file_id(app_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(app_dir).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    Some(parts.join("-"))
+}
+
+pub fn parse_groups(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut groups: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            if let Some(name) = header.trim_end().strip_suffix(']') {
+                current = Some(name.to_string());
+                groups.entry(name.to_string()).or_default();
+            }
+            continue;
+        }
+
+        let (Some(group), Some((key, value))) = (&current, line.split_once('=')) else {
+            continue;
+        };
+
+        // First occurrence of a key wins, duplicates are invalid per spec
+        groups
+            .entry(group.clone())
+            .or_default()
+            .entry(key.trim().to_string())
+            .or_insert_with(|| value.trim().to_string());
+    }
+
+    groups
+}
+
+// Escapes allowed in string values: \s \n \t \r \\
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('\\') => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+// Lists are separated by unescaped semicolons, "\;" is a literal semicolon
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&';') => {
+                current.push(';');
+                chars.next();
+            }
+            ';' => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+
+    items
+        .iter()
+        .map(|item| unescape(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Splits an (already unescaped) Exec value into arguments following the spec's quoting rules.
+/// Returns `None` for unterminated quotes.
+pub fn split_exec(exec: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => {
+                            let escaped = chars.next()?;
+                            if !matches!(escaped, '"' | '`' | '$' | '\\') {
+                                current.push('\\');
+                            }
+                            current.push(escaped);
+                        }
+                        other => current.push(other),
+                    }
+                }
+            }
+            ' ' | '\t' => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            _ => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(current);
+    }
+
+    Some(args)
+}
+
+// Expands field codes for a launch without files: %f %F %u %U vanish, %i %c %k are filled in
+fn expand_field_codes(args: &[String], icon: Option<&str>, name: &str, path: &Path) -> Vec<String> {
+    let mut expanded = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "%f" | "%F" | "%u" | "%U" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => continue,
+            "%i" => {
+                if let Some(icon) = icon {
+                    expanded.push("--icon".to_string());
+                    expanded.push(icon.to_string());
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let mut result = String::with_capacity(arg.len());
+        let mut chars = arg.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                result.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('%') => result.push('%'),
+                Some('c') => result.push_str(name),
+                Some('k') => result.push_str(&path.to_string_lossy()),
+                // Unknown and file codes expand to nothing inside an argument
+                _ => {}
+            }
+        }
+        expanded.push(result);
+    }
+
+    expanded
+}
@@ -1,12 +1,12 @@
 use crate::utils::{
-    helpers::helpers::{self, copy_to_clipboard}, paths::open_path_intelligently, run_commands::run_command, utils::{ActionType, SearchResult}
+    calculator::remember, clipboard_history, helpers::helpers::copy_to_clipboard, live_settings, paths::open_path_intelligently, query_mode::split_mode, run_commands::{launch_app, run_command}, utils::{ActionType, SearchResult}
 };
 
 pub fn execute_action(result: &SearchResult, query: &str) {
-    // Save to history (except for special commands)
+    // Save to history (except for special commands and clipboard entries, which have their own history)
     let should_save_history = !matches!(
         result.action,
-        ActionType::OpenHistory | ActionType::OpenSettings | ActionType::OpenInfo
+        ActionType::OpenHistory | ActionType::OpenSettings | ActionType::OpenInfo | ActionType::CopyClip(_)
     );
 
     if should_save_history {
@@ -35,12 +35,9 @@ pub fn execute_action(result: &SearchResult, query: &str) {
                 let _ = std::process::Command::new(exe).arg("--info").spawn();
             }
         }
-        ActionType::OpenApp(path) => {
-            let path_str = path.to_string_lossy();
-            // Extract the executable from the Exec line
-            let exec_parts: Vec<&str> = path_str.split_whitespace().collect();
-            if let Some(exec) = exec_parts.first() {
-                let _ = std::process::Command::new(exec).spawn();
+        ActionType::OpenApp(spec) | ActionType::OpenAppAction(spec) => {
+            if let Err(e) = launch_app(spec) {
+                eprintln!("Failed to launch application: {}", e);
             }
         }
         ActionType::OpenPath(path) => {
@@ -54,15 +51,22 @@ pub fn execute_action(result: &SearchResult, query: &str) {
             let _ = webbrowser::open(&url_to_open);
         }
         ActionType::MathResult(result) => {
-            copy_to_clipboard(&result);
+            // A mode prefix like "=" is not part of the calculation
+            let settings = live_settings::current();
+            remember(split_mode(query, &settings.query_modes).map_or(query, |(_, rest)| rest));
+            copy_to_clipboard(result);
             println!("Math result: {}", result);
         }
         ActionType::WebSearch(query) => {
-            let search_url = format!("https://www.google.com/search?q={}", helpers::encode(query));
+            let settings = live_settings::current();
+            let search_url = settings.engine_for(query).search_url(&query.query);
             let _ = webbrowser::open(&search_url);
         }
         ActionType::RunCommand(command) => {
             let _ = run_command(command);
         }
+        ActionType::CopyClip(id) => {
+            clipboard_history::copy_entry(id);
+        }
     }
 }
\ No newline at end of file
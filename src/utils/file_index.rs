@@ -0,0 +1,589 @@
+// Background index of the files under the configured roots, kept current with inotify
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io::{ErrorKind, PipeReader, PipeWriter};
+use std::os::fd::AsFd;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
+use nix::{errno::Errno, poll::{poll, PollFd, PollFlags, PollTimeout}};
+use walkdir::WalkDir;
+
+use crate::utils::{fuzzy::fuzzy_score, ignore_rules::{in_git_repo, is_ignored, IgnoreRules}, mime_types::icon_names_for_path, paths::{display_path, expand_user_path}, settings_manager::FileIndexSettings, utils::{ActionType, SearchResult}};
+
+// File scores land in FILE_SCORE_BASE..=FILE_SCORE_BASE + FILE_SCORE_RANGE, below a good app match
+const FILE_SCORE_BASE: f32 = 30.0;
+const FILE_SCORE_RANGE: f32 = 40.0;
+const MAX_FILE_RESULTS: usize = 5;
+// Shorter queries match too much of the index to be useful
+const MIN_QUERY_LENGTH: usize = 2;
+
+const WATCH_MASK: WatchMask = WatchMask::CREATE
+    .union(WatchMask::DELETE)
+    .union(WatchMask::MOVED_FROM)
+    .union(WatchMask::MOVED_TO)
+    .union(WatchMask::ONLYDIR)
+    .union(WatchMask::DONT_FOLLOW);
+
+struct IndexedFile {
+    path: PathBuf,
+    // Lowercase file name, for the cheap pre-filter before fuzzy matching
+    name: String,
+    is_dir: bool,
+}
+
+/// Files and folders under the configured roots, searched by fuzzy file name.
+/// Clones share the same index.
+#[derive(Clone)]
+pub struct FileIndex {
+    files: Arc<RwLock<Vec<IndexedFile>>>,
+    running: Arc<Mutex<Option<RunningIndexer>>>,
+}
+
+// The background indexer and the settings it was started with
+struct RunningIndexer {
+    settings: FileIndexSettings,
+    stopped: Arc<AtomicBool>,
+    // Never written, closing it wakes the indexer while it waits for changes
+    wake: PipeWriter,
+}
+
+impl FileIndex {
+    pub fn new() -> Self {
+        Self {
+            files: Arc::new(RwLock::new(Vec::new())),
+            running: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Builds the index on a background thread and keeps watching it for changes.
+    /// Does nothing if it already runs with these settings, otherwise restarts with them.
+    pub fn start(&self, settings: &FileIndexSettings) {
+        let Ok(mut running) = self.running.lock() else {
+            return;
+        };
+        if running.as_ref().is_some_and(|current| current.settings == *settings) {
+            return;
+        }
+        if let Some(previous) = running.take() {
+            previous.stop();
+        }
+
+        let (woken, wake) = match std::io::pipe() {
+            Ok(pipe) => pipe,
+            Err(e) => {
+                eprintln!("Failed to start the file index: {}", e);
+                return;
+            }
+        };
+
+        // The previous index stays searchable until the new one is built
+        let stopped = Arc::new(AtomicBool::new(false));
+        let indexer = Indexer::new(settings.clone(), Arc::clone(&self.files), Arc::clone(&stopped), woken);
+        std::thread::spawn(move || indexer.run());
+
+        *running = Some(RunningIndexer {
+            settings: settings.clone(),
+            stopped,
+            wake,
+        });
+    }
+
+    /// Stops the indexer and empties the index
+    pub fn stop(&self) {
+        if let Ok(mut running) = self.running.lock() {
+            if let Some(previous) = running.take() {
+                previous.stop();
+            }
+        }
+        if let Ok(mut files) = self.files.write() {
+            files.clear();
+        }
+    }
+
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        if query.chars().count() < MIN_QUERY_LENGTH {
+            return Vec::new();
+        }
+
+        let Ok(files) = self.files.read() else {
+            return Vec::new();
+        };
+
+        let pattern: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+
+        let mut matches: Vec<(f32, &IndexedFile)> = files
+            .iter()
+            .filter(|file| is_subsequence(&pattern, &file.name))
+            .filter_map(|file| {
+                let name = file.path.file_name()?.to_str()?;
+                Some((fuzzy_score(query, name)?, file))
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.path.cmp(&b.1.path)));
+        matches.truncate(MAX_FILE_RESULTS);
+
+        matches
+            .into_iter()
+            .map(|(score, file)| {
+                let parent = file.path.parent().unwrap_or(Path::new("/"));
+
+                SearchResult {
+                    title: file.path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    subtitle: display_path(parent),
+                    icon: if file.is_dir { "[DIR]" } else { "[FILE]" }.to_string(),
+                    icon_names: icon_names_for_path(&file.path),
+                    action: ActionType::OpenPath(file.path.clone()),
+                    score: FILE_SCORE_BASE + FILE_SCORE_RANGE * score,
+                }
+            })
+            .collect()
+    }
+}
+
+fn is_subsequence(pattern: &[char], text: &str) -> bool {
+    let mut chars = text.chars();
+    pattern.iter().all(|p| chars.any(|c| c == *p))
+}
+
+impl RunningIndexer {
+    fn stop(self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        drop(self.wake);
+    }
+}
+
+struct Indexer {
+    settings: FileIndexSettings,
+    roots: Vec<PathBuf>,
+    files: Arc<RwLock<Vec<IndexedFile>>>,
+    inotify: Option<Inotify>,
+    watches: HashMap<WatchDescriptor, PathBuf>,
+    // Set once the kernel refuses more watches, so the error is only reported once
+    watch_limit_reached: bool,
+    // Set when the settings changed and another indexer took over, this one must not touch `files` again
+    stopped: Arc<AtomicBool>,
+    // Becomes readable (at end of file) once the indexer is stopped
+    woken: PipeReader,
+}
+
+impl Indexer {
+    fn new(settings: FileIndexSettings, files: Arc<RwLock<Vec<IndexedFile>>>, stopped: Arc<AtomicBool>, woken: PipeReader) -> Self {
+        let mut roots: Vec<PathBuf> = settings
+            .roots
+            .iter()
+            .map(|root| expand_user_path(root))
+            .filter(|root| root.is_dir())
+            .collect();
+        roots.sort();
+        roots.dedup();
+
+        let inotify = Inotify::init()
+            .map_err(|e| eprintln!("File index will not update live, inotify failed: {}", e))
+            .ok();
+
+        Self {
+            settings,
+            roots,
+            files,
+            inotify,
+            watches: HashMap::new(),
+            watch_limit_reached: false,
+            stopped,
+            woken,
+        }
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    fn run(mut self) {
+        self.rebuild();
+
+        let mut buffer = [0u8; 4096];
+        loop {
+            let ready = self.inotify.as_ref().is_some_and(|inotify| wait_for_events(inotify, &self.woken));
+            if !ready || self.is_stopped() {
+                return;
+            }
+            let Some(inotify) = &mut self.inotify else {
+                return;
+            };
+            let events = match inotify.read_events(&mut buffer) {
+                Ok(events) => events,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => continue,
+                Err(e) => {
+                    eprintln!("Stopped watching files for the index: {}", e);
+                    return;
+                }
+            };
+
+            let mut changes = Vec::new();
+            let mut overflowed = false;
+            for event in events {
+                if event.mask.contains(EventMask::Q_OVERFLOW) {
+                    overflowed = true;
+                } else if event.mask.contains(EventMask::IGNORED) {
+                    self.watches.remove(&event.wd);
+                } else if let (Some(dir), Some(name)) = (self.watches.get(&event.wd), event.name) {
+                    changes.push((dir.join(name), event.mask));
+                }
+            }
+
+            if overflowed {
+                // Events were lost, nothing to do but start over
+                self.rebuild();
+            } else {
+                for (path, mask) in changes {
+                    self.apply_change(&path, mask);
+                }
+            }
+        }
+    }
+
+    fn rebuild(&mut self) {
+        if let Some(inotify) = &mut self.inotify {
+            for wd in self.watches.drain().map(|(wd, _)| wd) {
+                let _ = inotify.watches().remove(wd);
+            }
+        }
+
+        let mut files = Vec::new();
+        for root in self.roots.clone() {
+            let in_repo = in_git_repo(&root);
+            self.scan(&root, &root, &[], in_repo, &mut files);
+        }
+
+        if let Ok(mut index) = self.files.write() {
+            if !self.is_stopped() {
+                *index = files;
+            }
+        }
+    }
+
+    // Walks `dir` (which is under `root`), adding every entry the rules allow to `files`.
+    // `inherited` holds the ignore rules of the directories between `root` and `dir`.
+    fn scan(&mut self, root: &Path, dir: &Path, inherited: &[IgnoreRules], in_repo: bool, files: &mut Vec<IndexedFile>) {
+        let base_depth = depth_below(root, dir);
+        if base_depth > self.settings.max_depth {
+            return;
+        }
+
+        // Every directory entered so far: its walk depth, ignore rules and whether it is in a git repository
+        let mut dirs: Vec<(usize, Option<IgnoreRules>, bool)> = Vec::new();
+        let mut walker = WalkDir::new(dir)
+            .max_depth(self.settings.max_depth - base_depth)
+            .sort_by_file_name()
+            .into_iter();
+
+        while let Some(entry) = walker.next() {
+            if self.is_stopped() {
+                return;
+            }
+            let Ok(entry) = entry else {
+                continue;
+            };
+            let depth = entry.depth();
+            let is_dir = entry.file_type().is_dir();
+
+            // Leaving a directory drops its rules
+            dirs.retain(|(dir_depth, _, _)| *dir_depth < depth);
+            let parent_in_repo = dirs.last().map_or(in_repo, |(_, _, in_repo)| *in_repo);
+
+            if depth > 0 {
+                let rules = inherited.iter().chain(dirs.iter().filter_map(|(_, rules, _)| rules.as_ref()));
+                if !self.is_included(entry.file_name(), entry.path(), is_dir, rules) {
+                    if is_dir {
+                        walker.skip_current_dir();
+                    }
+                    continue;
+                }
+
+                if files.len() >= self.settings.max_entries {
+                    return;
+                }
+                files.push(IndexedFile::new(entry.path(), is_dir));
+            }
+
+            if is_dir {
+                // A root inside another root is indexed by its own scan, with its own depth limit
+                if depth > 0 && self.roots.iter().any(|root| root == entry.path()) {
+                    walker.skip_current_dir();
+                    continue;
+                }
+                self.watch(entry.path());
+
+                let dir_in_repo = parent_in_repo || entry.path().join(".git").exists();
+                dirs.push((depth, IgnoreRules::load(entry.path(), dir_in_repo), dir_in_repo));
+            }
+        }
+    }
+
+    fn is_included<'a>(
+        &self,
+        name: &OsStr,
+        path: &Path,
+        is_dir: bool,
+        rules: impl DoubleEndedIterator<Item = &'a IgnoreRules>,
+    ) -> bool {
+        if name == ".git" {
+            return false;
+        }
+        if !self.settings.include_hidden && name.to_string_lossy().starts_with('.') {
+            return false;
+        }
+        !is_ignored(rules, path, is_dir)
+    }
+
+    fn watch(&mut self, dir: &Path) {
+        if self.watch_limit_reached {
+            return;
+        }
+        let Some(inotify) = &mut self.inotify else {
+            return;
+        };
+
+        match inotify.watches().add(dir, WATCH_MASK) {
+            Ok(wd) => {
+                self.watches.insert(wd, dir.to_path_buf());
+            }
+            Err(e) => {
+                self.watch_limit_reached = true;
+                eprintln!("File index stops watching new folders at {}: {}", dir.display(), e);
+            }
+        }
+    }
+
+    fn apply_change(&mut self, path: &Path, mask: EventMask) {
+        if mask.intersects(EventMask::DELETE | EventMask::MOVED_FROM) {
+            if let Ok(mut files) = self.files.write() {
+                if !self.is_stopped() {
+                    files.retain(|file| !file.path.starts_with(path));
+                }
+            }
+            if mask.contains(EventMask::ISDIR) {
+                self.unwatch(path);
+            }
+            return;
+        }
+
+        if !mask.intersects(EventMask::CREATE | EventMask::MOVED_TO) {
+            return;
+        }
+
+        let Some(root) = self.root_of(path) else {
+            return;
+        };
+        let Some(parent) = path.parent() else {
+            return;
+        };
+        let is_dir = mask.contains(EventMask::ISDIR);
+
+        let (inherited, in_repo) = Self::rules_between(&root, parent);
+        let Some(name) = path.file_name() else {
+            return;
+        };
+        if depth_below(&root, path) > self.settings.max_depth || !self.is_included(name, path, is_dir, inherited.iter()) {
+            return;
+        }
+
+        let mut added = Vec::new();
+        if is_dir {
+            // A moved-in directory can bring a whole tree with it
+            self.scan(&root, path, &inherited, in_repo, &mut added);
+            added.insert(0, IndexedFile::new(path, true));
+        } else {
+            added.push(IndexedFile::new(path, false));
+        }
+
+        if let Ok(mut files) = self.files.write() {
+            if self.is_stopped() {
+                return;
+            }
+            // Saving by renaming a temp file over the original moves in a path that is already indexed
+            files.retain(|file| !file.path.starts_with(path));
+            let room = self.settings.max_entries.saturating_sub(files.len());
+            files.extend(added.into_iter().take(room));
+        }
+    }
+
+    fn unwatch(&mut self, dir: &Path) {
+        let removed: Vec<WatchDescriptor> = self
+            .watches
+            .iter()
+            .filter(|(_, watched)| watched.starts_with(dir))
+            .map(|(wd, _)| wd.clone())
+            .collect();
+
+        for wd in removed {
+            self.watches.remove(&wd);
+            // Fails for deleted directories, whose watches the kernel already dropped
+            if let Some(inotify) = &mut self.inotify {
+                let _ = inotify.watches().remove(wd);
+            }
+        }
+    }
+
+    fn root_of(&self, path: &Path) -> Option<PathBuf> {
+        self.roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .cloned()
+    }
+
+    // Ignore rules that apply inside `dir`, loaded from `root` down to `dir`
+    fn rules_between(root: &Path, dir: &Path) -> (Vec<IgnoreRules>, bool) {
+        let mut in_repo = root.parent().is_some_and(in_git_repo);
+        let mut rules = Vec::new();
+
+        let mut current = root.to_path_buf();
+        let relative = dir.strip_prefix(root).unwrap_or(Path::new(""));
+        let mut components = relative.components();
+        loop {
+            in_repo |= current.join(".git").exists();
+            rules.extend(IgnoreRules::load(&current, in_repo));
+
+            match components.next() {
+                Some(component) => current.push(component),
+                None => break,
+            }
+        }
+
+        (rules, in_repo)
+    }
+}
+
+impl IndexedFile {
+    fn new(path: &Path, is_dir: bool) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            name: path.file_name().unwrap_or_default().to_string_lossy().to_lowercase(),
+            is_dir,
+        }
+    }
+}
+
+// Blocks until `inotify` has events to read, false once the indexer was stopped
+fn wait_for_events(inotify: &Inotify, woken: &PipeReader) -> bool {
+    let mut fds = [PollFd::new(inotify.as_fd(), PollFlags::POLLIN), PollFd::new(woken.as_fd(), PollFlags::POLLIN)];
+    loop {
+        match poll(&mut fds, PollTimeout::NONE) {
+            // A closed pipe reports POLLHUP
+            Ok(_) => return !fds[1].any().unwrap_or(true),
+            Err(Errno::EINTR) => continue,
+            Err(e) => {
+                eprintln!("Stopped watching files for the index: {}", e);
+                return false;
+            }
+        }
+    }
+}
+
+fn depth_below(root: &Path, path: &Path) -> usize {
+    path.strip_prefix(root).map_or(0, |relative| relative.components().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexed_paths(index: &FileIndex) -> Vec<PathBuf> {
+        index.files.read().unwrap().iter().map(|file| file.path.clone()).collect()
+    }
+
+    #[test]
+    fn saving_through_a_renamed_temp_file_keeps_one_entry() {
+        let root = std::env::temp_dir().join(format!("quick_search_index_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let notes = root.join("notes.txt");
+        let temp = root.join("notes.txt.tmp");
+        std::fs::write(&notes, "first").unwrap();
+
+        let index = FileIndex::new();
+        let settings = FileIndexSettings { roots: vec![root.to_string_lossy().to_string()], ..Default::default() };
+        let (woken, _wake) = std::io::pipe().unwrap();
+        let mut indexer = Indexer::new(settings, Arc::clone(&index.files), Arc::default(), woken);
+        indexer.rebuild();
+        assert_eq!(indexed_paths(&index), vec![notes.clone()]);
+
+        // What an editor does on each save, with the events inotify reports for it
+        for save in ["second", "third"] {
+            std::fs::write(&temp, save).unwrap();
+            indexer.apply_change(&temp, EventMask::CREATE);
+            std::fs::rename(&temp, &notes).unwrap();
+            indexer.apply_change(&temp, EventMask::MOVED_FROM);
+            indexer.apply_change(&notes, EventMask::MOVED_TO);
+        }
+
+        assert_eq!(indexed_paths(&index), vec![notes]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn overlapping_roots_index_each_file_once() {
+        let root = std::env::temp_dir().join(format!("quick_search_overlap_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let nested = root.join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("notes.txt"), "").unwrap();
+
+        let index = FileIndex::new();
+        let roots = [&root, &nested, &root].iter().map(|path| path.to_string_lossy().to_string()).collect();
+        let settings = FileIndexSettings { roots, ..Default::default() };
+        let (woken, _wake) = std::io::pipe().unwrap();
+        let mut indexer = Indexer::new(settings, Arc::clone(&index.files), Arc::default(), woken);
+        indexer.rebuild();
+
+        let mut paths = indexed_paths(&index);
+        paths.sort();
+        assert_eq!(paths, vec![nested.clone(), nested.join("notes.txt")]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    // Polls the index built in the background until it holds `expected`
+    fn wait_for(index: &FileIndex, expected: &[PathBuf]) -> bool {
+        for _ in 0..100 {
+            if indexed_paths(index) == expected {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        false
+    }
+
+    #[test]
+    fn changed_settings_restart_the_indexer() {
+        let base = std::env::temp_dir().join(format!("quick_search_restart_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let (first, second) = (base.join("first"), base.join("second"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        std::fs::write(first.join("a.txt"), "").unwrap();
+        std::fs::write(second.join("b.txt"), "").unwrap();
+        let settings_for = |root: &Path| FileIndexSettings { roots: vec![root.to_string_lossy().to_string()], ..Default::default() };
+
+        let index = FileIndex::new();
+        index.start(&settings_for(&first));
+        assert!(wait_for(&index, &[first.join("a.txt")]));
+
+        index.start(&settings_for(&second));
+        assert!(wait_for(&index, &[second.join("b.txt")]));
+
+        // The first indexer no longer follows its root
+        std::fs::write(first.join("c.txt"), "").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert_eq!(indexed_paths(&index), vec![second.join("b.txt")]);
+
+        index.stop();
+        assert!(indexed_paths(&index).is_empty());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
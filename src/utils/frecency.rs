@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use chrono::{Local, NaiveDateTime};
+
+use crate::utils::{history_manager::{HistoryEntry, HistoryManager}, utils::{ActionType, SearchResult}};
+
+// A launch loses half of its weight every HALF_LIFE_DAYS
+const HALF_LIFE_DAYS: f32 = 7.0;
+const FRECENCY_WEIGHT: f32 = 6.0;
+const MAX_FRECENCY_BOOST: f32 = 20.0;
+const PREFIX_PICK_WEIGHT: f32 = 15.0;
+const MAX_PREFIX_BOOST: f32 = 30.0;
+
+struct Pick {
+    query: String,
+    key: String,
+    weight: f32,
+}
+
+/// Frequency weighted by recency, built from the launch history
+pub struct Frecency {
+    scores: HashMap<String, f32>,
+    picks: Vec<Pick>,
+}
+
+impl Frecency {
+    pub fn from_history(history: &[HistoryEntry]) -> Self {
+        let now = Local::now().naive_local();
+        let mut scores: HashMap<String, f32> = HashMap::new();
+        let mut picks = Vec::new();
+
+        for entry in history {
+            let Ok(timestamp) = NaiveDateTime::parse_from_str(&entry.timestamp, "%Y-%m-%d %H:%M:%S") else {
+                continue;
+            };
+
+            let age_days = (now - timestamp).num_seconds().max(0) as f32 / 86_400.0;
+            let weight = 0.5_f32.powf(age_days / HALF_LIFE_DAYS);
+            let key = Self::key(&entry.action_type, &entry.action_data);
+
+            *scores.entry(key.clone()).or_default() += weight;
+
+            let query = entry.query.trim().to_lowercase();
+            if !query.is_empty() {
+                picks.push(Pick { query, key, weight });
+            }
+        }
+
+        Self { scores, picks }
+    }
+
+    /// Extra score for `result`, given what the user picked in the past
+    pub fn boost(&self, query: &str, result: &SearchResult) -> f32 {
+        let key = Self::key_for(&result.action);
+
+        let frecency = self.scores.get(&key).copied().unwrap_or(0.0);
+        let frecency_boost = (FRECENCY_WEIGHT * frecency.ln_1p()).min(MAX_FRECENCY_BOOST);
+
+        // Past picks for a query that starts with what is typed now ("fi" -> picked on "fire")
+        let query = query.trim().to_lowercase();
+        let prefix_weight: f32 = if query.is_empty() {
+            0.0
+        } else {
+            self.picks
+                .iter()
+                .filter(|pick| pick.key == key && pick.query.starts_with(&query))
+                .map(|pick| if pick.query == query { pick.weight * 2.0 } else { pick.weight })
+                .sum()
+        };
+        let prefix_boost = (PREFIX_PICK_WEIGHT * prefix_weight).min(MAX_PREFIX_BOOST);
+
+        frecency_boost + prefix_boost
+    }
+
+    fn key_for(action: &ActionType) -> String {
+        let (action_type, action_data) = HistoryEntry::action_parts(action);
+        Self::key(&action_type, &action_data)
+    }
+
+    // Identifies the launched target independently of the query that found it
+    fn key(action_type: &str, action_data: &str) -> String {
+        format!("{}:{}", action_type, action_data)
+    }
+}
+
+/// The `Frecency` of the history file, only rebuilt after the history was written
+#[derive(Default)]
+pub struct FrecencyCache {
+    // Modification time of the history file it was built from
+    cached: Mutex<Option<(Option<SystemTime>, Arc<Frecency>)>>,
+}
+
+impl FrecencyCache {
+    pub fn current(&self) -> Arc<Frecency> {
+        let manager = HistoryManager::new();
+        let modified = manager.modified();
+
+        let Ok(mut cached) = self.cached.lock() else {
+            return Arc::new(Frecency::from_history(&manager.load_history()));
+        };
+        match cached.as_ref() {
+            Some((built_from, frecency)) if *built_from == modified => Arc::clone(frecency),
+            _ => {
+                let frecency = Arc::new(Frecency::from_history(&manager.load_history()));
+                *cached = Some((modified, Arc::clone(&frecency)));
+                frecency
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+// Subsequence matcher in the spirit of fzf: every query char must appear in order,
+// matches on word boundaries and consecutive runs score higher, gaps cost points.
+
+const SCORE_MATCH: i32 = 16;
+const SCORE_GAP_START: i32 = -3;
+const SCORE_GAP_EXTENSION: i32 = -1;
+const BONUS_BOUNDARY: i32 = 8;
+const BONUS_CAMEL: i32 = 7;
+const BONUS_CONSECUTIVE: i32 = 4;
+const BONUS_FIRST_CHAR_MULTIPLIER: i32 = 2;
+
+const UNREACHABLE: i32 = i32::MIN / 2;
+
+/// Scores `query` against `candidate`, case-insensitively.
+/// Returns `None` if the query is not a subsequence or has nothing to match, otherwise a score in `0.0..=1.0`.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<f32> {
+    let pattern: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(lower)
+        .collect();
+
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let original: Vec<char> = candidate.chars().collect();
+    let text: Vec<char> = original.iter().copied().map(lower).collect();
+
+    if !is_subsequence(&pattern, &text) {
+        return None;
+    }
+
+    let bonuses: Vec<i32> = (0..original.len())
+        .map(|j| position_bonus(&original, j))
+        .collect();
+
+    let n = pattern.len();
+    let m = text.len();
+
+    // best[j]: best score with the current pattern char matched at text[j]
+    let mut previous = vec![UNREACHABLE; m];
+    let mut current = vec![UNREACHABLE; m];
+
+    for (i, &pc) in pattern.iter().enumerate() {
+        // Best score of the previous row ending before j - 1, with the gap already paid
+        let mut gap_carry = UNREACHABLE;
+
+        for j in 0..m {
+            current[j] = UNREACHABLE;
+
+            if i > 0 && j >= 2 {
+                gap_carry = (gap_carry + SCORE_GAP_EXTENSION).max(previous[j - 2] + SCORE_GAP_START);
+            }
+
+            if text[j] != pc {
+                continue;
+            }
+
+            let bonus = bonuses[j];
+
+            if i == 0 {
+                current[j] = SCORE_MATCH + bonus * BONUS_FIRST_CHAR_MULTIPLIER;
+                continue;
+            }
+
+            let mut best = UNREACHABLE;
+            if j >= 1 && previous[j - 1] > UNREACHABLE {
+                best = previous[j - 1] + SCORE_MATCH + bonus.max(BONUS_CONSECUTIVE);
+            }
+            if gap_carry > UNREACHABLE {
+                best = best.max(gap_carry + SCORE_MATCH + bonus);
+            }
+            current[j] = best;
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let raw = previous.iter().copied().max().unwrap_or(UNREACHABLE);
+    if raw <= UNREACHABLE {
+        return None;
+    }
+
+    let max_possible = n as i32 * (SCORE_MATCH + BONUS_BOUNDARY)
+        + BONUS_BOUNDARY * (BONUS_FIRST_CHAR_MULTIPLIER - 1);
+
+    // Slightly prefer shorter candidates when the alignment is equally good
+    let length_penalty = (m - n) as f32 * 0.002;
+
+    let normalized = raw as f32 / max_possible as f32 - length_penalty;
+    Some(normalized.clamp(0.01, 1.0))
+}
+
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn is_subsequence(pattern: &[char], text: &[char]) -> bool {
+    let mut chars = text.iter();
+    pattern.iter().all(|p| chars.any(|c| c == p))
+}
+
+fn position_bonus(text: &[char], j: usize) -> i32 {
+    let current = text[j];
+    if j == 0 {
+        return if current.is_alphanumeric() { BONUS_BOUNDARY } else { 0 };
+    }
+
+    let previous = text[j - 1];
+    if !previous.is_alphanumeric() && current.is_alphanumeric() {
+        BONUS_BOUNDARY
+    } else if (previous.is_lowercase() && current.is_uppercase())
+        || (previous.is_alphabetic() && current.is_numeric())
+    {
+        BONUS_CAMEL
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_patterns_match_nothing() {
+        assert_eq!(fuzzy_score("", "Firefox"), None);
+        assert_eq!(fuzzy_score("   ", "Firefox"), None);
+        assert_eq!(fuzzy_score("", ""), None);
+    }
+
+    #[test]
+    fn matches_subsequences_case_insensitively() {
+        assert!(fuzzy_score("ffx", "Firefox").is_some());
+        assert!(fuzzy_score("FIRE", "firefox").is_some());
+        assert!(fuzzy_score("fire fox", "Firefox").is_some());
+        assert_eq!(fuzzy_score("xf", "Firefox"), None);
+        assert_eq!(fuzzy_score("firefoxes", "Firefox"), None);
+    }
+
+    #[test]
+    fn scores_stay_in_range() {
+        for (query, candidate) in [("f", "Firefox"), ("firefox", "Firefox"), ("fx", "Firefox"), ("e", "a very long name with an e")] {
+            let score = fuzzy_score(query, candidate).unwrap();
+            assert!((0.0..=1.0).contains(&score), "{} in {}: {}", query, candidate, score);
+        }
+    }
+
+    #[test]
+    fn word_starts_and_runs_score_higher() {
+        let boundary = fuzzy_score("vc", "Visual Code").unwrap();
+        let inside = fuzzy_score("vc", "Invocation").unwrap();
+        assert!(boundary > inside);
+
+        let run = fuzzy_score("term", "Terminal").unwrap();
+        let scattered = fuzzy_score("term", "The Emerald Mine").unwrap();
+        assert!(run > scattered);
+    }
+}
@@ -1,109 +1,65 @@
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-use crate::utils::{helpers::helpers::is_command_available, utils::SearchResult};
+use crate::utils::{desktop_entry::{desktop_file_id, DesktopEntry, Locale}, helpers::helpers::is_command_available};
 
-pub fn get_applications(query: &str) -> Vec<SearchResult> {
-    let mut results = Vec::new();
+/// Scans the given `applications` directories, highest precedence first.
+/// An ID found in an earlier directory shadows the same ID further down, even when the earlier entry is hidden.
+pub fn scan_applications(app_dirs: &[PathBuf], locale: &Locale, desktops: &[String]) -> Vec<DesktopEntry> {
     use walkdir::WalkDir;
-    // Search in common Linux app dirs
-    let app_dirs = vec![
-        "/usr/share/applications",
-        "/usr/local/share/applications",
-    ];
-    
-    if let Some(home) = dirs::home_dir() {
-        let local_apps = home.join(".local/share/applications");
-        if local_apps.exists() {
-            for entry in WalkDir::new(local_apps).max_depth(1) {
-                if let Ok(entry) = entry {
-                    if let Some(result) = parse_desktop_file(&entry.path(), query) {
-                        results.push(result);
-                    }
-                }
-            }
-        }
-    }
-    
+
+    let mut seen_ids = HashSet::new();
+    let mut apps = Vec::new();
+
     for dir in app_dirs {
-        use std::path::Path;
+        if !dir.exists() {
+            continue;
+        }
+
+        for entry in WalkDir::new(dir).follow_links(true).sort_by_file_name().into_iter().flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "desktop") {
+                continue;
+            }
+
+            let Some(id) = desktop_file_id(dir, path) else {
+                continue;
+            };
+
+            if !seen_ids.insert(id.clone()) {
+                continue;
+            }
 
-        if Path::new(dir).exists() {
-            for entry in WalkDir::new(dir).max_depth(1) {
-                if let Ok(entry) = entry {
-                    if let Some(result) = parse_desktop_file(&entry.path(), query) {
-                        results.push(result);
-                    }
+            if let Some(app) = DesktopEntry::parse(path, &id, locale) {
+                if app.is_shown_in(desktops) && is_launchable(&app) {
+                    apps.push(app);
                 }
             }
         }
     }
-    results.truncate(5);
-    results
-}
 
-pub fn parse_desktop_file(path: &Path, query: &str) -> Option<SearchResult> {
-    use std::fs;
+    apps
+}
 
-    if path.extension()? != "desktop" {
-        return None;
-    }
-    
-    let content = fs::read_to_string(path).ok()?;
-    let mut name = None;
-    let mut exec = None;
-    let mut no_display = false;
-    let mut hidden = false;
-    
-    for line in content.lines() {
-        if line.starts_with("Name=") && name.is_none() {
-            name = Some(line[5..].to_string());
-        } else if line.starts_with("Exec=") {
-            exec = Some(line[5..].to_string());
-        } else if line.starts_with("NoDisplay=true") {
-            no_display = true;
-        } else if line.starts_with("Hidden=true") {
-            hidden = true;
+fn is_launchable(app: &DesktopEntry) -> bool {
+    if let Some(try_exec) = &app.try_exec {
+        if !is_program_available(try_exec) {
+            return false;
         }
     }
-    
-    // Skip hidden or no-display apps
-    if no_display || hidden {
-        return None;
-    }
-    
-    if let (Some(app_name), Some(exec_cmd)) = (name, exec) {
-        if app_name.to_lowercase().contains(query) {
-            use std::path::PathBuf;
-            use crate::utils::utils::{ActionType, SearchResult};
 
-            let exec_parts: Vec<&str> = exec_cmd.split_whitespace().collect();
-            if let Some(exec_bin) = exec_parts.first() {
-                // Clean up field codes like %U, %F, etc.
-                let exec_clean = exec_bin.trim_matches('"');
-                
-                // Check if it's an absolute path that exists
-                if Path::new(exec_clean).is_absolute() {
-                    use std::path::Path;
+    match app.command_line() {
+        Some(args) => is_program_available(&args[0]),
+        None => false,
+    }
+}
 
-                    if !Path::new(exec_clean).exists() {
-                        return None;
-                    }
-                } else {
-                    // Check if command is in PATH
-                    if !is_command_available(exec_clean) {
-                        return None;
-                    }
-                }
-            }
-            
-            return Some(SearchResult {
-                title: app_name.clone(),
-                subtitle: "Application".to_string(),
-                icon: "[APP]".to_string(),
-                action: ActionType::OpenApp(PathBuf::from(exec_cmd)),
-            });
-        }
+fn is_program_available(program: &str) -> bool {
+    // Check if it's an absolute path that exists
+    if Path::new(program).is_absolute() {
+        Path::new(program).exists()
+    } else {
+        // Check if command is in PATH
+        is_command_available(program)
     }
-    
-    None
-}
\ No newline at end of file
+}
@@ -1,28 +1,5 @@
+#[allow(clippy::module_inception)]
 pub mod helpers {
-    pub fn encode(s: &str) -> String {
-        s.chars()
-            .map(|c| match c {
-                'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
-                ' ' => "+".to_string(),
-                _ => format!("%{:02X}", c as u8),
-            })
-            .collect()
-    }
-    
-    pub fn evaluate_math(expr: &str) -> Option<String> {
-        match meval::eval_str(expr) {
-            Ok(result) => {
-                // Check if the result is meaningful (not just the input)
-                if result.is_finite() && !expr.chars().all(|c| c.is_numeric() || c == '.') {
-                    Some(format!("{:.6}", result).trim_end_matches('0').trim_end_matches('.').to_string())
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        }
-    }
-
     pub fn is_url(text: &str) -> bool {
         text.starts_with("http://") || 
         text.starts_with("https://") || 
@@ -62,11 +39,28 @@ pub mod helpers {
         }
     }
 
+    // Same lookup `which` does, without spawning a process for it
     pub fn is_command_available(cmd: &str) -> bool {
-        std::process::Command::new("which")
-            .arg(cmd)
-            .output()
-            .map(|output| output.status.success())
+        use std::path::Path;
+
+        if cmd.is_empty() {
+            return false;
+        }
+
+        if cmd.contains('/') {
+            return is_executable(Path::new(cmd));
+        }
+
+        std::env::var_os("PATH")
+            .map(|paths| std::env::split_paths(&paths).any(|dir| is_executable(&dir.join(cmd))))
+            .unwrap_or(false)
+    }
+
+    fn is_executable(path: &std::path::Path) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::metadata(path)
+            .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
             .unwrap_or(false)
     }
 
@@ -1,7 +1,8 @@
 use std::fs;
 use std::path::PathBuf;
+use std::time::SystemTime;
 use serde::{Deserialize, Serialize};
-use crate::{gui::history::HistoryApp, utils::{execute_action::execute_action, settings_manager::SettingsManager, utils::SearchResult}};
+use crate::{gui::history::HistoryApp, utils::{execute_action::execute_action, live_settings, utils::{ActionType, LaunchSpec, SearchResult, WebQuery}}};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -16,19 +17,7 @@ pub struct HistoryEntry {
 
 impl HistoryEntry {
     pub fn from_search(query: &str, result: &SearchResult) -> Self {
-        use crate::utils::utils::ActionType;
-        
-        let (action_type, action_data) = match &result.action {
-            ActionType::OpenSettings => ("OpenSettings".to_string(), String::new()),
-            ActionType::OpenHistory => ("OpenHistory".to_string(), String::new()),
-            ActionType::OpenInfo => ("OpenInfo".to_string(), String::new()),
-            ActionType::OpenApp(path) => ("OpenApp".to_string(), path.to_string_lossy().to_string()),
-            ActionType::OpenPath(path) => ("OpenPath".to_string(), path.to_string_lossy().to_string()),
-            ActionType::OpenUrl(url) => ("OpenUrl".to_string(), url.clone()),
-            ActionType::MathResult(res) => ("MathResult".to_string(), res.clone()),
-            ActionType::WebSearch(q) => ("WebSearch".to_string(), q.clone()),
-            ActionType::RunCommand(command) => ("Command".to_string(), command.clone()),
-        };
+        let (action_type, action_data) = Self::action_parts(&result.action);
         
         let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
         
@@ -42,29 +31,64 @@ impl HistoryEntry {
             timestamp,
         }
     }
+
+    pub fn action_parts(action: &ActionType) -> (String, String) {
+        match action {
+            ActionType::OpenSettings => ("OpenSettings".to_string(), String::new()),
+            ActionType::OpenHistory => ("OpenHistory".to_string(), String::new()),
+            ActionType::OpenInfo => ("OpenInfo".to_string(), String::new()),
+            ActionType::OpenApp(spec) => ("OpenApp".to_string(), serde_json::to_string(spec).unwrap_or_default()),
+            ActionType::OpenAppAction(spec) => ("OpenAppAction".to_string(), serde_json::to_string(spec).unwrap_or_default()),
+            ActionType::OpenPath(path) => ("OpenPath".to_string(), path.to_string_lossy().to_string()),
+            ActionType::OpenUrl(url) => ("OpenUrl".to_string(), url.clone()),
+            ActionType::MathResult(res) => ("MathResult".to_string(), res.clone()),
+            ActionType::WebSearch(query) => ("WebSearch".to_string(), serde_json::to_string(query).unwrap_or_default()),
+            ActionType::RunCommand(command) => ("Command".to_string(), command.clone()),
+            ActionType::CopyClip(id) => ("CopyClip".to_string(), id.clone()),
+        }
+    }
+    
+    fn launch_spec(&self) -> LaunchSpec {
+        // Older entries stored the raw Exec line instead of a launch spec
+        serde_json::from_str(&self.action_data).unwrap_or_else(|_| LaunchSpec {
+            argv: self.action_data.split_whitespace().map(str::to_string).collect(),
+            ..LaunchSpec::default()
+        })
+    }
+    
+    fn web_query(&self) -> WebQuery {
+        // Older entries stored only the search terms, meant for the default engine
+        serde_json::from_str(&self.action_data).unwrap_or_else(|_| WebQuery {
+            engine: String::new(),
+            query: self.action_data.clone(),
+        })
+    }
     
     pub fn to_search_result(&self) -> SearchResult {
         use std::path::PathBuf;
-        use crate::utils::utils::ActionType;
         
         let action = match self.action_type.as_str() {
             "OpenSettings" => ActionType::OpenSettings,
             "OpenHistory" => ActionType::OpenHistory,
             "OpenInfo" => ActionType::OpenInfo,
-            "OpenApp" => ActionType::OpenApp(PathBuf::from(&self.action_data)),
+            "OpenApp" => ActionType::OpenApp(self.launch_spec()),
+            "OpenAppAction" => ActionType::OpenAppAction(self.launch_spec()),
             "OpenPath" => ActionType::OpenPath(PathBuf::from(&self.action_data)),
             "OpenUrl" => ActionType::OpenUrl(self.action_data.clone()),
             "MathResult" => ActionType::MathResult(self.action_data.clone()),
-            "WebSearch" => ActionType::WebSearch(self.action_data.clone()),
+            "WebSearch" => ActionType::WebSearch(self.web_query()),
             "Command" => ActionType::RunCommand(self.action_data.clone()),
-            _ => ActionType::WebSearch(self.query.clone()),
+            "CopyClip" => ActionType::CopyClip(self.action_data.clone()),
+            _ => ActionType::WebSearch(WebQuery { engine: String::new(), query: self.query.clone() }),
         };
         
         SearchResult {
             title: self.result_title.clone(),
             subtitle: self.result_subtitle.clone(),
             icon: self.result_icon.clone(),
+            icon_names: Vec::new(),
             action,
+            score: 0.0,
         }
     }
 }
@@ -90,6 +114,11 @@ impl HistoryManager {
         PathBuf::from("history.json")
     }
 
+    // When the history was last written, by this or another process
+    pub fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.history_file).and_then(|metadata| metadata.modified()).ok()
+    }
+
     pub fn load_history(&self) -> Vec<HistoryEntry> {
         if !self.history_file.exists() {
             return Vec::new();
@@ -104,10 +133,7 @@ impl HistoryManager {
     }
     
     pub fn save_history(&self, history: &[HistoryEntry]) -> Result<(), std::io::Error> {
-        let settings_manager = SettingsManager::new();
-        let settings = settings_manager.load_settings();
-
-        if settings.enable_history == true {
+        if live_settings::current().enable_history {
             let json = serde_json::to_string_pretty(history)?;
             fs::write(&self.history_file, json)?;
         }
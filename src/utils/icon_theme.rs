@@ -0,0 +1,289 @@
+// Icon Theme Specification lookup:
+// https://specifications.freedesktop.org/icon-theme-spec/latest/
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::utils::desktop_entry::{data_dirs, parse_groups};
+
+const FALLBACK_THEME: &str = "hicolor";
+// XPM is part of the spec but not something we can decode
+const EXTENSIONS: &[&str] = &["png", "svg"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum DirKind {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+struct ThemeDir {
+    subdir: String,
+    size: u32,
+    scale: u32,
+    kind: DirKind,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+}
+
+impl ThemeDir {
+    fn matches_size(&self, size: u32, scale: u32) -> bool {
+        if self.scale != scale {
+            return false;
+        }
+
+        match self.kind {
+            DirKind::Fixed => self.size == size,
+            DirKind::Scalable => (self.min_size..=self.max_size).contains(&size),
+            DirKind::Threshold => self.size.abs_diff(size) <= self.threshold,
+        }
+    }
+
+    fn size_distance(&self, size: u32, scale: u32) -> u32 {
+        let requested = size * scale;
+        match self.kind {
+            DirKind::Fixed => (self.size * self.scale).abs_diff(requested),
+            DirKind::Scalable => {
+                let (min, max) = (self.min_size * self.scale, self.max_size * self.scale);
+                min.saturating_sub(requested).max(requested.saturating_sub(max))
+            }
+            DirKind::Threshold => {
+                let size = self.size * self.scale;
+                let threshold = self.threshold * self.scale;
+                if requested + threshold < size || requested > size + threshold {
+                    size.abs_diff(requested)
+                } else {
+                    0
+                }
+            }
+        }
+    }
+}
+
+struct Theme {
+    // Every base directory that contains this theme
+    roots: Vec<PathBuf>,
+    dirs: Vec<ThemeDir>,
+    inherits: Vec<String>,
+}
+
+/// Resolves icon names to files through the user's icon theme, its parents and hicolor
+pub struct IconTheme {
+    themes: Vec<Theme>,
+    base_dirs: Vec<PathBuf>,
+    lookups: Mutex<HashMap<(String, u32, u32), Option<PathBuf>>>,
+}
+
+impl IconTheme {
+    pub fn new() -> Self {
+        Self::with_theme(&detect_theme_name())
+    }
+
+    pub fn with_theme(name: &str) -> Self {
+        let base_dirs = base_dirs();
+        let mut themes = Vec::new();
+        let mut visited = Vec::new();
+
+        Self::load_chain(name, &base_dirs, &mut themes, &mut visited);
+        if !visited.iter().any(|theme| theme == FALLBACK_THEME) {
+            Self::load_chain(FALLBACK_THEME, &base_dirs, &mut themes, &mut visited);
+        }
+
+        Self {
+            themes,
+            base_dirs,
+            lookups: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Depth-first over Inherits=, as the spec's FindIconHelper does
+    fn load_chain(name: &str, base_dirs: &[PathBuf], themes: &mut Vec<Theme>, visited: &mut Vec<String>) {
+        if visited.iter().any(|theme| theme == name) {
+            return;
+        }
+        visited.push(name.to_string());
+
+        let Some(theme) = Self::load_theme(name, base_dirs) else {
+            return;
+        };
+
+        let parents = theme.inherits.clone();
+        themes.push(theme);
+
+        for parent in parents {
+            Self::load_chain(&parent, base_dirs, themes, visited);
+        }
+    }
+
+    fn load_theme(name: &str, base_dirs: &[PathBuf]) -> Option<Theme> {
+        let roots: Vec<PathBuf> = base_dirs
+            .iter()
+            .map(|dir| dir.join(name))
+            .filter(|dir| dir.is_dir())
+            .collect();
+
+        // The first index.theme found describes the whole theme
+        let index = roots
+            .iter()
+            .find_map(|root| fs::read_to_string(root.join("index.theme")).ok())?;
+        let groups = parse_groups(&index);
+        let header = groups.get("Icon Theme")?;
+
+        let list = |key: &str| -> Vec<String> {
+            header
+                .get(key)
+                .map(|value| value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default()
+        };
+
+        let mut subdirs = list("Directories");
+        subdirs.extend(list("ScaledDirectories"));
+
+        let dirs = subdirs
+            .into_iter()
+            .filter_map(|subdir| {
+                let group = groups.get(&subdir)?;
+                let number = |key: &str| group.get(key).and_then(|value| value.trim().parse::<u32>().ok());
+
+                let size = number("Size")?;
+                let kind = match group.get("Type").map(|value| value.trim()) {
+                    Some("Fixed") => DirKind::Fixed,
+                    Some("Scalable") => DirKind::Scalable,
+                    _ => DirKind::Threshold,
+                };
+
+                Some(ThemeDir {
+                    size,
+                    scale: number("Scale").unwrap_or(1),
+                    kind,
+                    min_size: number("MinSize").unwrap_or(size),
+                    max_size: number("MaxSize").unwrap_or(size),
+                    threshold: number("Threshold").unwrap_or(2),
+                    subdir,
+                })
+            })
+            .collect();
+
+        Some(Theme {
+            roots,
+            dirs,
+            inherits: list("Inherits"),
+        })
+    }
+
+    /// Finds the file for `name` at `size` pixels and `scale`. Absolute paths are returned as-is if they exist.
+    pub fn lookup(&self, name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        if name.is_empty() {
+            return None;
+        }
+
+        if Path::new(name).is_absolute() {
+            return Path::new(name).is_file().then(|| PathBuf::from(name));
+        }
+
+        let key = (name.to_string(), size, scale);
+        if let Some(cached) = self.lookups.lock().ok().and_then(|lookups| lookups.get(&key).cloned()) {
+            return cached;
+        }
+
+        let found = self
+            .themes
+            .iter()
+            .find_map(|theme| Self::lookup_in_theme(theme, name, size, scale))
+            .or_else(|| self.lookup_fallback(name));
+
+        if let Ok(mut lookups) = self.lookups.lock() {
+            lookups.insert(key, found.clone());
+        }
+
+        found
+    }
+
+    fn lookup_in_theme(theme: &Theme, name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        let find_in = |dir: &ThemeDir| {
+            theme.roots.iter().find_map(|root| {
+                EXTENSIONS
+                    .iter()
+                    .map(|ext| root.join(&dir.subdir).join(format!("{name}.{ext}")))
+                    .find(|path| path.is_file())
+            })
+        };
+
+        if let Some(path) = theme.dirs.iter().filter(|dir| dir.matches_size(size, scale)).find_map(find_in) {
+            return Some(path);
+        }
+
+        let mut closest: Option<(u32, PathBuf)> = None;
+        for dir in &theme.dirs {
+            let distance = dir.size_distance(size, scale);
+            if closest.as_ref().is_some_and(|(best, _)| distance >= *best) {
+                continue;
+            }
+            if let Some(path) = find_in(dir) {
+                closest = Some((distance, path));
+            }
+        }
+
+        closest.map(|(_, path)| path)
+    }
+
+    // Unthemed icons live directly in the base directories, e.g. /usr/share/pixmaps
+    fn lookup_fallback(&self, name: &str) -> Option<PathBuf> {
+        self.base_dirs.iter().find_map(|dir| {
+            EXTENSIONS
+                .iter()
+                .map(|ext| dir.join(format!("{name}.{ext}")))
+                .find(|path| path.is_file())
+        })
+    }
+}
+
+fn base_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".icons"));
+    }
+    dirs.extend(data_dirs().iter().map(|dir| dir.join("icons")));
+    dirs.push(PathBuf::from("/usr/share/pixmaps"));
+
+    dirs
+}
+
+// Tiling WMs have no settings daemon, so read the toolkit config files directly
+fn detect_theme_name() -> String {
+    let config_dir = dirs::config_dir().unwrap_or_default();
+
+    for file in ["gtk-4.0/settings.ini", "gtk-3.0/settings.ini"] {
+        if let Ok(content) = fs::read_to_string(config_dir.join(file)) {
+            let groups = parse_groups(&content);
+            if let Some(theme) = groups.get("Settings").and_then(|group| group.get("gtk-icon-theme-name")) {
+                return theme.trim_matches('"').to_string();
+            }
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(config_dir.join("kdeglobals")) {
+        let groups = parse_groups(&content);
+        if let Some(theme) = groups.get("Icons").and_then(|group| group.get("Theme")) {
+            return theme.to_string();
+        }
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        if let Ok(content) = fs::read_to_string(home.join(".gtkrc-2.0")) {
+            for line in content.lines() {
+                if let Some((key, value)) = line.split_once('=') {
+                    if key.trim() == "gtk-icon-theme-name" {
+                        return value.trim().trim_matches('"').to_string();
+                    }
+                }
+            }
+        }
+    }
+
+    FALLBACK_THEME.to_string()
+}
@@ -0,0 +1,321 @@
+// .gitignore / .ignore matching, following the pattern format in gitignore(5)
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Char(char),
+    // [abc], [a-z], [!abc]
+    Class { negated: bool, ranges: Vec<(char, char)> },
+    // `?`, any character except '/'
+    Any,
+    // `*`, any run of characters except '/'
+    Star,
+    // `**/`, zero or more whole directories
+    AnyDirs,
+    // Trailing `/**`, everything below a directory
+    Rest,
+}
+
+#[derive(Debug, Clone)]
+struct Pattern {
+    tokens: Vec<Token>,
+    negated: bool,
+    dir_only: bool,
+    // Patterns with a slash before the end match from the ignore file's directory,
+    // the others match the name at any depth
+    anchored: bool,
+}
+
+/// The rules from the ignore files of one directory
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    dir: PathBuf,
+    patterns: Vec<Pattern>,
+}
+
+impl IgnoreRules {
+    /// Reads `.gitignore` (only inside a git repository) and `.ignore` from `dir`.
+    /// `.ignore` comes last, so its rules win, as in ripgrep.
+    pub fn load(dir: &Path, in_repo: bool) -> Option<Self> {
+        let mut patterns = Vec::new();
+
+        let files: &[&str] = if in_repo { &[".gitignore", ".ignore"] } else { &[".ignore"] };
+        for file in files {
+            if let Ok(content) = fs::read_to_string(dir.join(file)) {
+                patterns.extend(content.lines().filter_map(Pattern::parse));
+            }
+        }
+
+        if patterns.is_empty() {
+            return None;
+        }
+
+        Some(Self { dir: dir.to_path_buf(), patterns })
+    }
+
+    /// Some(true) if the last matching pattern ignores `path`, Some(false) if it re-includes it
+    pub fn matches(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.dir).ok()?.to_str()?;
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+
+        self.patterns
+            .iter()
+            .rev()
+            .filter(|pattern| is_dir || !pattern.dir_only)
+            .find(|pattern| {
+                let subject = if pattern.anchored { relative } else { name };
+                glob_match(&pattern.tokens, &subject.chars().collect::<Vec<_>>())
+            })
+            .map(|pattern| !pattern.negated)
+    }
+}
+
+/// Whether `path` is ignored by `rules`, which are ordered from the outermost directory inwards
+pub fn is_ignored<'a>(rules: impl DoubleEndedIterator<Item = &'a IgnoreRules>, path: &Path, is_dir: bool) -> bool {
+    rules
+        .rev()
+        .find_map(|rules| rules.matches(path, is_dir))
+        .unwrap_or(false)
+}
+
+/// Whether `dir` is inside a git work tree
+pub fn in_git_repo(dir: &Path) -> bool {
+    dir.ancestors().any(|ancestor| ancestor.join(".git").exists())
+}
+
+impl Pattern {
+    fn parse(line: &str) -> Option<Self> {
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        // Trailing spaces are dropped unless escaped with a backslash
+        let mut line = line.to_string();
+        while line.ends_with(' ') && !line.ends_with("\\ ") {
+            line.pop();
+        }
+
+        let (negated, mut pattern) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest.to_string()),
+            None => (false, line.strip_prefix('\\').map(str::to_string).unwrap_or(line)),
+        };
+
+        let dir_only = pattern.ends_with('/');
+        if dir_only {
+            pattern.pop();
+        }
+
+        let anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(&pattern);
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            tokens: tokenize(pattern),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+}
+
+fn tokenize(pattern: &str) -> Vec<Token> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                // `**` is only special as a whole path segment, otherwise it is a plain `*`
+                let segment_start = i == 0 || chars[i - 1] == '/';
+                if segment_start && chars.get(i + 2) == Some(&'/') {
+                    tokens.push(Token::AnyDirs);
+                    i += 3;
+                } else if segment_start && i + 2 == chars.len() {
+                    tokens.push(Token::Rest);
+                    i += 2;
+                } else {
+                    tokens.push(Token::Star);
+                    i += 2;
+                }
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                i += 1;
+            }
+            '?' => {
+                tokens.push(Token::Any);
+                i += 1;
+            }
+            '[' => match parse_class(&chars[i..]) {
+                Some((token, length)) => {
+                    tokens.push(token);
+                    i += length;
+                }
+                None => {
+                    tokens.push(Token::Char('['));
+                    i += 1;
+                }
+            },
+            '\\' if i + 1 < chars.len() => {
+                tokens.push(Token::Char(chars[i + 1]));
+                i += 2;
+            }
+            c => {
+                tokens.push(Token::Char(c));
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+// Parses a bracket expression, returning the token and how many chars it used
+fn parse_class(chars: &[char]) -> Option<(Token, usize)> {
+    let mut i = 1;
+    let negated = matches!(chars.get(i), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut ranges = Vec::new();
+    let mut first = true;
+    loop {
+        let c = *chars.get(i)?;
+        // A ']' right after the opening bracket is a literal
+        if c == ']' && !first {
+            return Some((Token::Class { negated, ranges }, i + 1));
+        }
+        first = false;
+
+        if chars.get(i + 1) == Some(&'-') && chars.get(i + 2).is_some_and(|&end| end != ']') {
+            ranges.push((c, chars[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((c, c));
+            i += 1;
+        }
+    }
+}
+
+fn glob_match(tokens: &[Token], text: &[char]) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return text.is_empty();
+    };
+
+    match token {
+        Token::Char(c) => text.first() == Some(c) && glob_match(rest, &text[1..]),
+        Token::Any => text.first().is_some_and(|&c| c != '/') && glob_match(rest, &text[1..]),
+        Token::Class { negated, ranges } => {
+            text.first().is_some_and(|&c| {
+                c != '/' && ranges.iter().any(|&(start, end)| (start..=end).contains(&c)) != *negated
+            }) && glob_match(rest, &text[1..])
+        }
+        Token::Star => {
+            // Try every length up to the next '/'
+            let limit = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=limit).any(|length| glob_match(rest, &text[length..]))
+        }
+        Token::AnyDirs => {
+            // Zero directories, or skip to just after any later '/'
+            glob_match(rest, text)
+                || text
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &c)| c == '/')
+                    .any(|(index, _)| glob_match(rest, &text[index + 1..]))
+        }
+        Token::Rest => !text.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(dir: &str, lines: &[&str]) -> IgnoreRules {
+        IgnoreRules {
+            dir: PathBuf::from(dir),
+            patterns: lines.iter().filter_map(|line| Pattern::parse(line)).collect(),
+        }
+    }
+
+    fn ignores(lines: &[&str], path: &str, is_dir: bool) -> bool {
+        rules("/repo", lines).matches(&Path::new("/repo").join(path), is_dir) == Some(true)
+    }
+
+    #[test]
+    fn double_stars() {
+        assert!(ignores(&["**/build"], "build", true));
+        assert!(ignores(&["**/build"], "a/b/build", true));
+        assert!(ignores(&["docs/**"], "docs/a/b.md", false));
+        assert!(!ignores(&["docs/**"], "docs", true));
+        assert!(ignores(&["a/**/b"], "a/b", false));
+        assert!(ignores(&["a/**/b"], "a/x/y/b", false));
+        assert!(!ignores(&["a/**/b"], "ab", false));
+        // Not a whole segment, so just a `*`
+        assert!(ignores(&["foo**"], "foobar", false));
+        assert!(!ignores(&["foo**"], "foo/bar", false));
+    }
+
+    #[test]
+    fn negation_re_includes() {
+        let logs = rules("/repo", &["*.log", "!keep.log"]);
+        assert_eq!(logs.matches(Path::new("/repo/debug.log"), false), Some(true));
+        assert_eq!(logs.matches(Path::new("/repo/keep.log"), false), Some(false));
+        assert_eq!(logs.matches(Path::new("/repo/notes.txt"), false), None);
+
+        // Rules of a deeper directory win over the outer ones
+        let layers = [rules("/repo", &["*.log"]), rules("/repo/sub", &["!keep.log"])];
+        assert!(is_ignored(layers.iter(), Path::new("/repo/sub/debug.log"), false));
+        assert!(!is_ignored(layers.iter(), Path::new("/repo/sub/keep.log"), false));
+        assert!(is_ignored(layers.iter(), Path::new("/repo/keep.log"), false));
+    }
+
+    #[test]
+    fn trailing_slash_only_matches_directories() {
+        assert!(ignores(&["build/"], "build", true));
+        assert!(!ignores(&["build/"], "build", false));
+        assert!(ignores(&["build/"], "src/build", true));
+    }
+
+    #[test]
+    fn leading_slash_anchors_to_the_ignore_file() {
+        assert!(ignores(&["/foo"], "foo", false));
+        assert!(!ignores(&["/foo"], "sub/foo", false));
+        assert!(ignores(&["foo"], "sub/foo", false));
+        // A slash in the middle anchors too
+        assert!(ignores(&["src/*.rs"], "src/main.rs", false));
+        assert!(!ignores(&["src/*.rs"], "lib/src/main.rs", false));
+        assert!(!ignores(&["src/*.rs"], "src/bin/main.rs", false));
+    }
+
+    #[test]
+    fn character_classes() {
+        assert!(ignores(&["[abc].txt"], "a.txt", false));
+        assert!(!ignores(&["[abc].txt"], "d.txt", false));
+        assert!(ignores(&["file[0-9]"], "file5", false));
+        assert!(!ignores(&["file[0-9]"], "filex", false));
+        assert!(ignores(&["[!a]x"], "bx", false));
+        assert!(!ignores(&["[^a]x"], "ax", false));
+        assert!(ignores(&["[]a]"], "]", false));
+        // An unclosed bracket is a literal
+        assert!(ignores(&["[abc"], "[abc", false));
+    }
+
+    #[test]
+    fn comments_escapes_and_spaces() {
+        assert!(rules("/repo", &["", "# comment", "/"]).patterns.is_empty());
+        assert!(ignores(&["\\#notes"], "#notes", false));
+        assert!(ignores(&["\\!important"], "!important", false));
+        assert!(ignores(&["name   "], "name", false));
+        assert!(ignores(&["name\\ "], "name ", false));
+        assert!(ignores(&["?.md"], "a.md", false));
+        assert!(!ignores(&["?.md"], "ab.md", false));
+    }
+}
@@ -0,0 +1,105 @@
+// Settings shared by the whole process: read from disk once, then reloaded whenever settings.json
+// changes, including when another process (like the `--settings` window) saved it
+
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+use inotify::{Inotify, WatchMask};
+
+use crate::utils::settings_manager::{Settings, SettingsManager};
+
+type Listener = Box<dyn Fn() + Send + Sync>;
+
+struct Shared {
+    settings: RwLock<Arc<Settings>>,
+    listeners: Mutex<Vec<Listener>>,
+}
+
+fn shared() -> &'static Shared {
+    static SHARED: OnceLock<Shared> = OnceLock::new();
+    SHARED.get_or_init(|| {
+        let manager = SettingsManager::new();
+        let settings = manager.load_settings();
+        let path = manager.settings_path().to_path_buf();
+        std::thread::spawn(move || watch(manager, path));
+
+        Shared {
+            settings: RwLock::new(Arc::new(settings)),
+            listeners: Mutex::new(Vec::new()),
+        }
+    })
+}
+
+/// The current settings. Cheap enough to call whenever they are needed, the first call loads them.
+pub fn current() -> Arc<Settings> {
+    shared()
+        .settings
+        .read()
+        .map(|settings| Arc::clone(&settings))
+        .unwrap_or_default()
+}
+
+/// Calls `listener` from a background thread each time the settings changed on disk,
+/// e.g. to repaint a window so it picks up the new `current()` settings
+pub fn subscribe(listener: impl Fn() + Send + Sync + 'static) {
+    if let Ok(mut listeners) = shared().listeners.lock() {
+        listeners.push(Box::new(listener));
+    }
+}
+
+// Watches the config dir rather than the file, editors and `save_settings` replace it by renaming
+fn watch(manager: SettingsManager, path: PathBuf) {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+
+    let inotify = Inotify::init().and_then(|inotify| {
+        inotify.watches().add(dir, WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::DELETE)?;
+        Ok(inotify)
+    });
+    let mut inotify = match inotify {
+        Ok(inotify) => inotify,
+        Err(e) => {
+            eprintln!("Settings will not reload live, inotify failed: {}", e);
+            return;
+        }
+    };
+
+    let mut buffer = [0u8; 4096];
+    loop {
+        let events = match inotify.read_events_blocking(&mut buffer) {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("Stopped watching the settings file: {}", e);
+                return;
+            }
+        };
+
+        if events.into_iter().any(|event| event.name == Some(OsStr::new(name))) {
+            reload(&manager);
+        }
+    }
+}
+
+fn reload(manager: &SettingsManager) {
+    let loaded = manager.load_settings();
+    let shared = shared();
+
+    {
+        let Ok(mut settings) = shared.settings.write() else {
+            return;
+        };
+        // Saving from the settings window also lands here, often without changing anything
+        if **settings == loaded {
+            return;
+        }
+        *settings = Arc::new(loaded);
+    }
+
+    if let Ok(listeners) = shared.listeners.lock() {
+        for listener in listeners.iter() {
+            listener();
+        }
+    }
+}
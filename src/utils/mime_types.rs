@@ -0,0 +1,109 @@
+// File type detection by extension, backed by the shared-mime-info database
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::utils::desktop_entry::data_dirs;
+
+struct MimeDatabase {
+    // Lowercase extension -> (weight, mime type)
+    extensions: HashMap<String, (u32, String)>,
+    generic_icons: HashMap<String, String>,
+}
+
+impl MimeDatabase {
+    fn get() -> &'static MimeDatabase {
+        static DATABASE: OnceLock<MimeDatabase> = OnceLock::new();
+        DATABASE.get_or_init(Self::load)
+    }
+
+    fn load() -> Self {
+        let mut extensions: HashMap<String, (u32, String)> = HashMap::new();
+        let mut generic_icons = HashMap::new();
+
+        // Reverse precedence order, so the user's data dir overrides the system ones
+        for dir in data_dirs().iter().rev() {
+            let mime_dir = dir.join("mime");
+
+            // globs2 lines look like "50:text/x-rust:*.rs"
+            if let Ok(content) = fs::read_to_string(mime_dir.join("globs2")) {
+                for line in content.lines().filter(|line| !line.starts_with('#')) {
+                    let mut fields = line.split(':');
+                    let (Some(weight), Some(mime), Some(glob)) = (fields.next(), fields.next(), fields.next()) else {
+                        continue;
+                    };
+
+                    // Only simple "*.ext" globs, anything fancier needs real glob matching
+                    let Some(extension) = glob.strip_prefix("*.") else {
+                        continue;
+                    };
+                    if extension.contains(['*', '?', '[']) {
+                        continue;
+                    }
+
+                    let weight = weight.parse().unwrap_or(50);
+                    let entry = extensions.entry(extension.to_lowercase()).or_insert((0, String::new()));
+                    if weight >= entry.0 {
+                        *entry = (weight, mime.to_string());
+                    }
+                }
+            }
+
+            if let Ok(content) = fs::read_to_string(mime_dir.join("generic-icons")) {
+                for line in content.lines() {
+                    if let Some((mime, icon)) = line.split_once(':') {
+                        generic_icons.insert(mime.to_string(), icon.to_string());
+                    }
+                }
+            }
+        }
+
+        Self { extensions, generic_icons }
+    }
+}
+
+pub fn mime_type_for(path: &Path) -> Option<String> {
+    if path.is_dir() {
+        return Some("inode/directory".to_string());
+    }
+
+    let database = MimeDatabase::get();
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+
+    // Longest extension first, so "archive.tar.gz" is a tarball rather than gzip
+    name.match_indices('.')
+        .filter_map(|(index, _)| database.extensions.get(&name[index + 1..]))
+        .map(|(_, mime)| mime.clone())
+        .next()
+}
+
+/// Icon names for a mime type, most specific first, per the icon naming spec
+pub fn icon_names_for_mime(mime: &str) -> Vec<String> {
+    if mime == "inode/directory" {
+        return vec!["folder".to_string()];
+    }
+
+    let mut names = vec![mime.replace('/', "-")];
+
+    let generic = MimeDatabase::get()
+        .generic_icons
+        .get(mime)
+        .cloned()
+        .or_else(|| mime.split('/').next().map(|media| format!("{media}-x-generic")));
+    names.extend(generic);
+
+    names
+}
+
+/// Icon names for a file or directory, ending with a generic document icon
+pub fn icon_names_for_path(path: &Path) -> Vec<String> {
+    let mut names = mime_type_for(path)
+        .map(|mime| icon_names_for_mime(&mime))
+        .unwrap_or_default();
+    if !path.is_dir() {
+        names.push("text-x-generic".to_string());
+    }
+    names
+}
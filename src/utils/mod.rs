@@ -1,11 +1,32 @@
 pub mod search;
+pub mod fuzzy;
+pub mod frecency;
 pub mod get_apps;
+pub mod app_index;
+pub mod file_index;
+pub mod ignore_rules;
+pub mod desktop_entry;
+pub mod icon_theme;
+pub mod mime_types;
+#[allow(clippy::module_inception)]
 pub mod utils;
 pub mod helpers;
+pub mod calculator;
+pub mod clipboard_history;
+pub mod currency;
+pub mod datetime;
+pub mod units;
+pub mod zoneinfo;
+pub mod url_encoding;
 pub mod execute_action;
 pub mod paths;
+pub mod path_completion;
+pub mod query_mode;
 pub mod history_manager;
 pub mod settings_manager;
+pub mod live_settings;
 pub mod window_manger;
 pub mod run_commands;
+pub mod daemon;
+pub mod search_providers;
 pub mod wm_integrations;
\ No newline at end of file
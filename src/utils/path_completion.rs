@@ -0,0 +1,146 @@
+// Shell-like completion for queries that look like paths
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::utils::{mime_types::icon_names_for_path, paths::{display_path, looks_like_path, resolve_path}, utils::{ActionType, SearchResult}};
+
+// Just below an exact path match
+const COMPLETION_SCORE: f32 = 85.0;
+
+struct Completion {
+    name: String,
+    path: PathBuf,
+    is_dir: bool,
+}
+
+/// The children of the typed directory whose names start with the typed prefix
+pub fn completion_results(query: &str) -> Vec<SearchResult> {
+    completions(query)
+        .into_iter()
+        .map(|completion| SearchResult {
+            title: if completion.is_dir { format!("{}/", completion.name) } else { completion.name },
+            subtitle: display_path(&completion.path),
+            icon: if completion.is_dir { "[DIR]" } else { "[FILE]" }.to_string(),
+            icon_names: icon_names_for_path(&completion.path),
+            action: ActionType::OpenPath(completion.path),
+            score: COMPLETION_SCORE,
+        })
+        .collect()
+}
+
+/// What Tab turns `query` into: the single match, or the longest prefix all matches share.
+/// None when there is nothing to add.
+pub fn complete(query: &str) -> Option<String> {
+    if !looks_like_path(query) {
+        return None;
+    }
+
+    // "~" or "$HOME" on their own complete to the directory itself
+    if !query.contains('/') {
+        return resolve_path(query).is_dir().then(|| format!("{}/", query));
+    }
+
+    let (dir_text, prefix) = split_query(query);
+    let dir_text = tidy_dir(dir_text);
+    let matches = completions(query);
+
+    let completed = match matches.as_slice() {
+        [] => return None,
+        [only] => format!("{}{}{}", dir_text, only.name, if only.is_dir { "/" } else { "" }),
+        _ => {
+            let common = matches
+                .iter()
+                .map(|completion| completion.name.as_str())
+                .filter(|name| name.starts_with(prefix))
+                .reduce(common_prefix)
+                .unwrap_or(prefix);
+            format!("{}{}", dir_text, common)
+        }
+    };
+
+    (completed != query).then_some(completed)
+}
+
+/// The query that lists the contents of the directory `path`
+pub fn descend(query: &str, path: &Path) -> String {
+    let (dir_text, _) = split_query(query);
+
+    match path.file_name() {
+        Some(name) if !dir_text.is_empty() && path.parent() == Some(resolve_path(dir_text).as_path()) => {
+            format!("{}{}/", tidy_dir(dir_text), name.to_string_lossy())
+        }
+        _ => with_trailing_slash(display_path(path)),
+    }
+}
+
+fn completions(query: &str) -> Vec<Completion> {
+    if !looks_like_path(query) {
+        return Vec::new();
+    }
+
+    let (dir_text, prefix) = split_query(query);
+    if dir_text.is_empty() {
+        return Vec::new();
+    }
+
+    let Ok(entries) = fs::read_dir(resolve_path(dir_text)) else {
+        return Vec::new();
+    };
+
+    let prefix_lower = prefix.to_lowercase();
+    let mut completions: Vec<Completion> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Hidden entries only show up once a dot has been typed, as in shells
+            if name.starts_with('.') && !prefix.starts_with('.') {
+                return None;
+            }
+            if !name.to_lowercase().starts_with(&prefix_lower) {
+                return None;
+            }
+
+            let path = entry.path();
+            Some(Completion { is_dir: path.is_dir(), name, path })
+        })
+        .collect();
+
+    // Folders first, then alphabetically
+    completions.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    completions
+}
+
+// Splits "~/proj/sr" into the typed directory "~/proj/" and the name prefix "sr"
+fn split_query(query: &str) -> (&str, &str) {
+    match query.rfind('/') {
+        Some(index) => query.split_at(index + 1),
+        None => ("", query),
+    }
+}
+
+// Typed directories with "." or ".." in them are replaced by where they lead
+fn tidy_dir(dir_text: &str) -> String {
+    if dir_text.split('/').any(|part| part == "." || part == "..") {
+        with_trailing_slash(display_path(&resolve_path(dir_text)))
+    } else {
+        dir_text.to_string()
+    }
+}
+
+fn with_trailing_slash(mut text: String) -> String {
+    if !text.ends_with('/') {
+        text.push('/');
+    }
+    text
+}
+
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let length = a
+        .char_indices()
+        .zip(b.chars())
+        .take_while(|((_, x), y)| x == y)
+        .last()
+        .map_or(0, |((index, c), _)| index + c.len_utf8());
+    &a[..length]
+}
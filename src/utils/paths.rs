@@ -1,13 +1,12 @@
-use std::path::Path;
-use crate::utils::{helpers::helpers::{get_terminal_editor, is_command_available}, settings_manager::SettingsManager, utils::{ActionType, SearchResult}};
+use std::path::{Component, Path, PathBuf};
+use crate::utils::{helpers::helpers::{get_terminal_editor, is_command_available}, live_settings, mime_types::icon_names_for_path, utils::{ActionType, SearchResult}};
 
 pub fn check_path(text: &str) -> Option<SearchResult> {
-    // Handle tilde expansion first
-    if text.starts_with('~') {
-        return check_tilde_path(text);
-    }
-    
-    let path = Path::new(text);
+    let path = if looks_like_path(text) {
+        resolve_path(text)
+    } else {
+        PathBuf::from(text)
+    };
     
     if path.exists() {
         let is_dir = path.is_dir();
@@ -15,39 +14,85 @@ pub fn check_path(text: &str) -> Option<SearchResult> {
             title: text.to_string(),
             subtitle: if is_dir { "Open folder" } else { "Open file" }.to_string(),
             icon: if is_dir { "[DIR]" } else { "[FILE]" }.to_string(),
-            action: ActionType::OpenPath(path.to_path_buf()),
+            icon_names: icon_names_for_path(&path),
+            action: ActionType::OpenPath(path),
+            score: 90.0,
         });
     }
     
     None
 }
 
-fn check_tilde_path(text: &str) -> Option<SearchResult> {
-    let home = dirs::home_dir()?;
-    
-    let expanded = if text == "~" {
-        home
-    } else if text.starts_with("~/") {
-        if text.len() > 2 {
-            home.join(&text[2..])
-        } else {
-            home
+/// Absolute, home-relative or starting with an environment variable
+pub fn looks_like_path(text: &str) -> bool {
+    text.starts_with(['/', '~', '$'])
+}
+
+/// Expands "~" and variables, then resolves "." and ".." without touching the disk
+pub fn resolve_path(text: &str) -> PathBuf {
+    let mut resolved = PathBuf::new();
+
+    for component in expand_user_path(text).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
         }
-    } else {
-        return None;
-    };
-    
-    if expanded.exists() {
-        let is_dir = expanded.is_dir();
-        return Some(SearchResult {
-            title: text.to_string(),
-            subtitle: if is_dir { "Open folder" } else { "Open file" }.to_string(),
-            icon: if is_dir { "[DIR]" } else { "[FILE]" }.to_string(),
-            action: ActionType::OpenPath(expanded),
-        });
     }
-    
-    None
+
+    resolved
+}
+
+/// `path` as the user would type it, with the home directory shortened to "~"
+pub fn display_path(path: &Path) -> String {
+    match dirs::home_dir().as_deref().and_then(|home| path.strip_prefix(home).ok()) {
+        Some(relative) if relative.as_os_str().is_empty() => "~".to_string(),
+        Some(relative) => format!("~/{}", relative.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Expands a leading "~" and "$VAR" / "${VAR}" references. Unknown variables are left as they are.
+pub fn expand_user_path(text: &str) -> PathBuf {
+    let mut expanded = String::new();
+    let mut rest = text;
+
+    if rest == "~" || rest.starts_with("~/") {
+        if let Some(home) = dirs::home_dir() {
+            expanded.push_str(&home.to_string_lossy());
+            rest = &rest[1..];
+        }
+    }
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let (name, remainder) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => ("", after),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => expanded.push_str(&value),
+            // Unknown variables and a lone '$' stay literal
+            _ => expanded.push_str(&rest[start..rest.len() - remainder.len()]),
+        }
+        rest = remainder;
+    }
+    expanded.push_str(rest);
+
+    PathBuf::from(expanded)
 }
 
 pub fn open_path_intelligently(path: &Path) {
@@ -59,16 +104,13 @@ pub fn open_path_intelligently(path: &Path) {
         return;
     }
 
-    let settings_manager = SettingsManager::new();
-    let settings = settings_manager.load_settings();
+    let settings = live_settings::current();
 
-    if !settings.text_editor_command.is_empty() {
-        if is_command_available(&settings.text_editor_command) {
-            let _ = std::process::Command::new(settings.text_editor_command)
-                .arg(path)
-                .spawn();
-            return;
-        }
+    if !settings.text_editor_command.is_empty() && is_command_available(&settings.text_editor_command) {
+        let _ = std::process::Command::new(&settings.text_editor_command)
+            .arg(path)
+            .spawn();
+        return;
     }
 
     if let Some(ext) = path.extension() {
@@ -0,0 +1,84 @@
+// Query modes: a prefix like "=" or "/" restricts a search to a single provider
+
+use crate::utils::{paths::looks_like_path, settings_manager::QueryMode};
+
+/// The mode `query` starts with and the rest of the query after its prefix.
+/// The longest matching prefix wins, so "!!" can be a mode next to "!".
+pub fn split_mode<'m, 'q>(query: &'q str, modes: &'m [QueryMode]) -> Option<(&'m QueryMode, &'q str)> {
+    modes
+        .iter()
+        .filter(|mode| !mode.prefix.is_empty())
+        .filter_map(|mode| Some((mode, query.strip_prefix(mode.prefix.as_str())?)))
+        .filter(|(mode, rest)| is_separated(&mode.prefix, rest, query))
+        .max_by_key(|(mode, _)| mode.prefix.len())
+        .map(|(mode, rest)| (mode, rest.trim_start()))
+}
+
+// Word prefixes like "cb" need a space after them, so "cbz" is still a normal search.
+// Symbols can be followed directly by the query ("=2+2"), but a query that looks like a path
+// ("/etc", or just "/") only gets a mode with a space after the prefix ("/ report").
+fn is_separated(prefix: &str, rest: &str, query: &str) -> bool {
+    if rest.starts_with(char::is_whitespace) {
+        return true;
+    }
+    if looks_like_path(query) {
+        return false;
+    }
+    rest.is_empty() || !prefix.chars().last().is_some_and(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::settings_manager::Settings;
+
+    fn mode_of(query: &str, modes: &[QueryMode]) -> Option<(String, String)> {
+        split_mode(query, modes).map(|(mode, rest)| (mode.provider.clone(), rest.to_string()))
+    }
+
+    fn split(query: &str) -> Option<(String, String)> {
+        mode_of(query, &Settings::default().query_modes)
+    }
+
+    fn mode(provider: &str, rest: &str) -> Option<(String, String)> {
+        Some((provider.to_string(), rest.to_string()))
+    }
+
+    #[test]
+    fn symbols_can_be_followed_directly_by_the_query() {
+        assert_eq!(split("=2+2"), mode("math", "2+2"));
+        assert_eq!(split("= x = 3*4"), mode("math", "x = 3*4"));
+        assert_eq!(split(">htop"), mode("commands", "htop"));
+        assert_eq!(split("2+2"), None);
+    }
+
+    #[test]
+    fn paths_need_a_space_after_the_prefix() {
+        assert_eq!(split("/etc"), None);
+        assert_eq!(split("/"), None);
+        assert_eq!(split("/ report"), mode("files", "report"));
+    }
+
+    #[test]
+    fn words_need_a_space_after_the_prefix() {
+        assert_eq!(split("cbz"), None);
+        assert_eq!(split("cb "), mode("clipboard", ""));
+        assert_eq!(split("cb foo bar"), mode("clipboard", "foo bar"));
+        assert_eq!(split("cb"), mode("clipboard", ""));
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let mut modes = Settings::default().query_modes;
+        modes.push(QueryMode { prefix: "!!".to_string(), provider: "web".to_string() });
+
+        assert_eq!(mode_of("!!rust", &modes), mode("web", "rust"));
+        assert_eq!(mode_of("!g rust", &modes), mode("search_engines", "g rust"));
+    }
+
+    #[test]
+    fn empty_prefixes_are_ignored() {
+        let modes = [QueryMode { prefix: String::new(), provider: "math".to_string() }];
+        assert_eq!(mode_of("2+2", &modes), None);
+    }
+}
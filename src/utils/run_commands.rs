@@ -1,63 +1,84 @@
 use std::process::Command;
 
-use anyhow::{Ok, Result};
+use anyhow::{anyhow, Ok, Result};
 
-use crate::utils::{helpers::helpers::is_command_available, settings_manager::SettingsManager};
+use crate::utils::{helpers::helpers::is_command_available, live_settings, utils::LaunchSpec};
+
+const TERMINALS: &[(&str, &[&str])] = &[
+    ("kitty", &["-e"]),
+    ("alacritty", &["-e"]),
+    ("wezterm", &["start"]),
+    ("foot", &[]),
+    ("footclient", &[]),
+    ("gnome-terminal", &["--"]),
+    ("konsole", &["-e"]),
+    ("xterm", &["-e"]),
+    ("lxterminal", &["-e"]),
+    ("xfce4-terminal", &["-e"]),
+    ("tilix", &["-e"]),
+    ("terminator", &["-x"]),
+    ("tilda", &["-c"]),
+    ("urxvt", &["-e"]),
+    ("st", &["-e"]),
+    ("eterm", &["-e"]),
+    ("deepin-terminal", &["-e"]),
+    ("mate-terminal", &["-e"]),
+    ("qterminal", &["-e"]),
+    ("sakura", &["-e"]),
+    ("guake", &["-e"]),
+];
 
 pub fn run_command(command: &str) -> Result<()> {
-    let settings_manager = SettingsManager::new();
-    let settings = settings_manager.load_settings();
     let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".into());
 
     let shell_cmd = format!("{command}; exec {shell}");
 
+    terminal_command(&[shell.clone(), "-c".to_string(), shell_cmd])?.spawn()?;
+    Ok(())
+}
+
+pub fn launch_app(spec: &LaunchSpec) -> Result<()> {
+    let (program, args) = spec
+        .argv
+        .split_first()
+        .ok_or_else(|| anyhow!("Application has an empty command line"))?;
+
+    let mut command = if spec.terminal {
+        terminal_command(&spec.argv)?
+    } else {
+        let mut command = Command::new(program);
+        command.args(args);
+        command
+    };
+
+    command.envs(spec.env.iter().map(|(key, value)| (key, value)));
+
+    if let Some(dir) = spec.working_dir.as_deref().filter(|dir| dir.is_dir()) {
+        command.current_dir(dir);
+    }
+
+    command.spawn()?;
+    Ok(())
+}
+
+// Builds a command that runs `args` inside the user-defined terminal, or the first known one in PATH
+fn terminal_command(args: &[String]) -> Result<Command> {
+    let settings = live_settings::current();
+
     // User-defined terminal
     if !settings.terminal_command.is_empty() {
-        Command::new(&settings.terminal_command)
-            .arg("-e")
-            .arg(&shell)
-            .arg("-c")
-            .arg(&shell_cmd)
-            .spawn()?;
-        return Ok(());
+        let mut command = Command::new(&settings.terminal_command);
+        command.arg("-e").args(args);
+        return Ok(command);
     }
 
-    const TERMINALS: &[(&str, &[&str])] = &[
-        ("kitty", &["-e"]),
-        ("alacritty", &["-e"]),
-        ("wezterm", &["start"]),
-        ("foot", &[""]),
-        ("footclient", &[""]),
-        ("gnome-terminal", &["--"]),
-        ("konsole", &["-e"]),
-        ("xterm", &["-e"]),
-        ("lxterminal", &["-e"]),
-        ("xfce4-terminal", &["-e"]),
-        ("tilix", &["-e"]),
-        ("terminator", &["-x"]),
-        ("tilda", &["-c"]),
-        ("urxvt", &["-e"]),
-        ("st", &["-e"]),
-        ("eterm", &["-e"]),
-        ("deepin-terminal", &["-e"]),
-        ("mate-terminal", &["-e"]),
-        ("qterminal", &["-e"]),
-        ("sakura", &["-e"]),
-        ("guake", &["-e"]),
-    ];
-
     for (terminal, flags) in TERMINALS {
         if is_command_available(terminal) {
-            Command::new(terminal)
-                .args(*flags)
-                .arg(&shell)
-                .arg("-c")
-                .arg(&shell_cmd)
-                .spawn()?;
-
-            return Ok(());
+            let mut command = Command::new(terminal);
+            command.args(*flags).args(args);
+            return Ok(command);
         }
     }
 
-    Ok(())
-}
\ No newline at end of file
+    Err(anyhow!("No terminal emulator found"))
+}
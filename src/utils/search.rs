@@ -1,109 +1,338 @@
-use crate::utils::{get_apps::get_applications, helpers::helpers::{evaluate_math, is_command_available, is_url}, settings_manager::SettingsManager, utils::{ActionType, SearchResult}};
-use crate::utils::paths::check_path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
 
-pub fn perform_search(query: &str) -> Vec<SearchResult> {
-    let mut results = Vec::new();
-    let query_lower = query.to_lowercase();
-
-    let settings_manager = SettingsManager::new();
-    let settings = settings_manager.load_settings();
-
-    // Handle special commands starting with @
-    if query.starts_with('@') {
-        let command = query[1..].to_lowercase();
-
-        if command == "info" || "info".contains(&command) {
-            results.push(SearchResult {
-                title: "Informations".to_string(),
-                subtitle: "Application information".to_string(),
-                icon: "[INFO]".to_string(),
-                action: ActionType::OpenInfo,
-            });
-        }
-        
-        if command == "settings" || "settings".contains(&command) {
-            results.push(SearchResult {
-                title: "Settings".to_string(),
-                subtitle: "Configure Quick Search".to_string(),
-                icon: "[SET]".to_string(),
-                action: ActionType::OpenSettings,
-            });
-        }
+use tokio::runtime::Runtime;
+use tokio::task::{JoinHandle, JoinSet};
+
+use crate::utils::{frecency::{Frecency, FrecencyCache}, live_settings, query_mode::split_mode, search_providers::{apps, clipboard, commands, currency, datetime, engines, files, math, url, web}, settings_manager::Settings, utils::{ActionType, SearchResult}};
+
+// Fallback providers only run when the regular ones found fewer results than this
+const FALLBACK_THRESHOLD: usize = 3;
+const MAX_RESULTS: usize = 8;
+
+pub trait SearchProvider: Send + Sync {
+    /// Stable identifier, used as the key in `Settings::providers`
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn icon(&self) -> &'static str;
+    /// Providers with a higher priority are queried first and win ties on score
+    fn priority(&self) -> i32;
+    fn search(&self, query: &str, settings: &Settings) -> Vec<SearchResult>;
+
+    /// Shown in the bar while its query mode is active and nothing is typed after the prefix
+    fn hint(&self) -> &'static str {
+        self.description()
+    }
+
+    /// Called once when a search UI starts, for providers that build an index in the background
+    fn warm_up(&self, _settings: &Settings) {}
+
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+
+    fn is_fallback(&self) -> bool {
+        false
+    }
+
+    /// Only searched when its query mode is active
+    fn is_mode_only(&self) -> bool {
+        false
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings.is_provider_enabled(self.id(), self.enabled_by_default())
+    }
+}
+
+pub struct ProviderRegistry {
+    providers: Vec<Box<dyn SearchProvider>>,
+}
 
-        if command == "history" || "history".contains(&command) {
-            results.push(SearchResult {
-                title: "History".to_string(),
-                subtitle: "View search history".to_string(),
-                icon: "[HIST]".to_string(),
-                action: ActionType::OpenHistory,
-            });
+impl ProviderRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self { providers: Vec::new() };
+
+        registry.register(Box::new(math::MathProvider::new()));
+        registry.register(Box::new(currency::CurrencyProvider::new()));
+        registry.register(Box::new(datetime::DateTimeProvider::new()));
+        registry.register(Box::new(files::FileProvider::new()));
+        registry.register(Box::new(engines::SearchEngineProvider::new()));
+        registry.register(Box::new(clipboard::ClipboardProvider::new()));
+        registry.register(Box::new(url::UrlProvider::new()));
+        registry.register(Box::new(apps::AppProvider::new()));
+        registry.register(Box::new(commands::CommandProvider::new()));
+        registry.register(Box::new(web::WebSearchProvider::new()));
+
+        registry
+    }
+
+    pub fn register(&mut self, provider: Box<dyn SearchProvider>) {
+        self.providers.push(provider);
+        self.providers.sort_by_key(|p| std::cmp::Reverse(p.priority()));
+    }
+
+    pub fn providers(&self) -> &[Box<dyn SearchProvider>] {
+        &self.providers
+    }
+
+    /// Index of the provider whose query mode `query` starts with, and the query without the prefix.
+    /// Modes of disabled or unknown providers are ignored.
+    pub fn mode_for<'q>(&self, query: &'q str, settings: &Settings) -> Option<(usize, &'q str)> {
+        let (mode, rest) = split_mode(query, &settings.query_modes)?;
+        let index = self
+            .providers
+            .iter()
+            .position(|provider| provider.id() == mode.provider && provider.is_enabled(settings))?;
+        Some((index, rest))
+    }
+}
+
+/// Results known so far for one search. Snapshots of the same generation replace each other,
+/// the last one has `complete` set.
+pub struct SearchUpdate {
+    pub generation: u64,
+    pub results: Vec<SearchResult>,
+    pub complete: bool,
+}
+
+/// Runs searches on a tokio worker pool, so slow providers never block the UI thread.
+/// Starting a search cancels the previous one.
+pub struct SearchWorker {
+    runtime: Runtime,
+    registry: Arc<ProviderRegistry>,
+    frecency: Arc<FrecencyCache>,
+    generation: Arc<AtomicU64>,
+    current: Option<JoinHandle<()>>,
+    sender: Sender<SearchUpdate>,
+    receiver: Receiver<SearchUpdate>,
+    wake: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl SearchWorker {
+    /// `wake` is called from the pool whenever a new snapshot is ready
+    pub fn new(registry: ProviderRegistry, wake: impl Fn() + Send + Sync + 'static) -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("quick_search-search")
+            .build()
+            .expect("failed to start the search runtime");
+        let (sender, receiver) = mpsc::channel();
+
+        let settings = live_settings::current();
+        for provider in registry.providers().iter().filter(|p| p.is_enabled(&settings)) {
+            provider.warm_up(&settings);
         }
-        
-        if !results.is_empty() {
-            return results;
+
+        Self {
+            runtime,
+            registry: Arc::new(registry),
+            frecency: Arc::new(FrecencyCache::default()),
+            generation: Arc::new(AtomicU64::new(0)),
+            current: None,
+            sender,
+            receiver,
+            wake: Arc::new(wake),
         }
     }
 
-    // Check for math expression
-    if settings.enable_math_eval == true {
-        if let Some(math_result) = evaluate_math(query) {
-            results.push(SearchResult {
-                title: format!("= {}", math_result),
-                subtitle: "Math calculation. Click or press Enter to copy".to_string(),
-                icon: "[CALC]".to_string(),
-                action: ActionType::MathResult(math_result),
-            });
+    /// Starts searching for `query` and returns the generation its updates will carry
+    pub fn search(&mut self, query: &str) -> u64 {
+        let generation = self.cancel();
+
+        let search = Search {
+            registry: self.registry.clone(),
+            frecency: self.frecency.clone(),
+            query: query.to_string(),
+            generation,
+            current: self.generation.clone(),
+            sender: self.sender.clone(),
+            wake: self.wake.clone(),
+        };
+        self.current = Some(self.runtime.spawn(search.run()));
+
+        generation
+    }
+
+    /// Drops the running search, its pending updates will be ignored
+    pub fn cancel(&mut self) -> u64 {
+        if let Some(task) = self.current.take() {
+            task.abort();
         }
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
     }
 
-    // Check for file path
-    if settings.enable_file_search == true {
-        if let Some(path_result) = check_path(query) {
-            results.push(path_result);
+    pub fn registry(&self) -> &ProviderRegistry {
+        &self.registry
+    }
+
+    /// Newest update of the current generation, if one arrived since the last poll
+    pub fn poll(&self) -> Option<SearchUpdate> {
+        let current = self.generation.load(Ordering::SeqCst);
+        self.receiver
+            .try_iter()
+            .filter(|update| update.generation == current)
+            .last()
+    }
+}
+
+struct Search {
+    registry: Arc<ProviderRegistry>,
+    frecency: Arc<FrecencyCache>,
+    query: String,
+    generation: u64,
+    current: Arc<AtomicU64>,
+    sender: Sender<SearchUpdate>,
+    wake: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl Search {
+    fn is_current(&self) -> bool {
+        self.current.load(Ordering::SeqCst) == self.generation
+    }
+
+    fn send(&self, results: Vec<SearchResult>, complete: bool) {
+        if self.is_current() && self.sender.send(SearchUpdate { generation: self.generation, results, complete }).is_ok() {
+            (self.wake)();
         }
     }
 
-    // Check for URL
-    if settings.enable_web_search == true {
-        if is_url(query) {
-            results.push(SearchResult {
-                title: query.to_string(),
-                subtitle: "Open URL".to_string(),
-                icon: "[URL]".to_string(),
-                action: ActionType::OpenUrl(query.to_string()),
-            });
+    async fn run(self) {
+        // Handle special commands starting with @
+        if let Some(command) = self.query.strip_prefix('@') {
+            let results = special_commands(&command.to_lowercase());
+            if !results.is_empty() {
+                self.send(results, true);
+                return;
+            }
+        }
+
+        let settings = live_settings::current();
+        let Ok(frecency) = tokio::task::spawn_blocking({
+            let enable_history = settings.enable_history;
+            let frecency = self.frecency.clone();
+            move || enable_history.then(|| frecency.current())
+        })
+        .await
+        else {
+            return;
+        };
+
+        // Tagged with the provider's index, which breaks ties on score
+        let mut results: Vec<(usize, SearchResult)> = Vec::new();
+
+        // A mode prefix skips every other provider, fallbacks included
+        if let Some((index, query)) = self.registry.mode_for(&self.query, &settings) {
+            self.run_providers(&[index], query, &settings, frecency.as_deref(), &mut results).await;
+            self.send(Self::ranked(&results), true);
+            return;
         }
+
+        let (fallbacks, regular): (Vec<_>, Vec<_>) = (0..self.registry.providers().len())
+            .filter(|&index| {
+                let provider = &self.registry.providers()[index];
+                provider.is_enabled(&settings) && !provider.is_mode_only()
+            })
+            .partition(|&index| self.registry.providers()[index].is_fallback());
+
+        self.run_providers(&regular, &self.query, &settings, frecency.as_deref(), &mut results).await;
+
+        if results.len() < FALLBACK_THRESHOLD {
+            self.run_providers(&fallbacks, &self.query, &settings, frecency.as_deref(), &mut results).await;
+        }
+
+        self.send(Self::ranked(&results), true);
     }
-    
-    // Search for applications
-    if settings.enable_app_search == true {
-        let app_results = get_applications(&query_lower);
-        results.extend(app_results);
-    }
-
-    // Check if valid command
-    if settings.enable_run_commands == true {
-        if is_command_available(&query.to_string().split_ascii_whitespace().next().unwrap_or(&query)) {
-            results.push(SearchResult {
-                title: query.to_string(),
-                subtitle: "Run command".to_string(),
-                icon: "[CMD]".to_string(),
-                action: ActionType::RunCommand(query.to_string().to_lowercase()) 
-            });
+
+    // Queries the providers concurrently and sends a snapshot each time one of them answers
+    async fn run_providers(
+        &self,
+        providers: &[usize],
+        query: &str,
+        settings: &Arc<Settings>,
+        frecency: Option<&Frecency>,
+        results: &mut Vec<(usize, SearchResult)>,
+    ) {
+        let mut tasks = JoinSet::new();
+        for &index in providers {
+            let registry = self.registry.clone();
+            let query = query.to_string();
+            let settings = settings.clone();
+            tasks.spawn_blocking(move || (index, registry.providers()[index].search(&query, &settings)));
+        }
+
+        while let Some(finished) = tasks.join_next().await {
+            if !self.is_current() {
+                return;
+            }
+
+            // A panicking provider just contributes nothing
+            let Ok((index, found)) = finished else {
+                continue;
+            };
+            if found.is_empty() {
+                continue;
+            }
+
+            results.extend(found.into_iter().map(|mut result| {
+                if let Some(frecency) = frecency {
+                    result.score += frecency.boost(&self.query, &result);
+                }
+                (index, result)
+            }));
+
+            if !tasks.is_empty() {
+                self.send(Self::ranked(results), false);
+            }
         }
     }
 
-    // Add web search fallback if no other results
-    if results.is_empty() || results.len() < 3 {
+    fn ranked(results: &[(usize, SearchResult)]) -> Vec<SearchResult> {
+        let mut ranked = results.to_vec();
+        // Equal scores keep provider priority order
+        ranked.sort_by(|(a_index, a), (b_index, b)| b.score.total_cmp(&a.score).then(a_index.cmp(b_index)));
+        ranked.truncate(MAX_RESULTS);
+        ranked.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+fn special_commands(command: &str) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+
+    if "info".contains(command) {
         results.push(SearchResult {
-            title: format!("Search for \"{}\"", query),
-            subtitle: "Search on the web".to_string(),
-            icon: "[SRC]".to_string(),
-            action: ActionType::WebSearch(query.to_string()),
+            title: "Informations".to_string(),
+            subtitle: "Application information".to_string(),
+            icon: "[INFO]".to_string(),
+            icon_names: vec!["help-about".to_string()],
+            action: ActionType::OpenInfo,
+            score: 0.0,
         });
     }
-    
-    // Limit to top 8 results
-    results.truncate(8);
+
+    if "settings".contains(command) {
+        results.push(SearchResult {
+            title: "Settings".to_string(),
+            subtitle: "Configure Quick Search".to_string(),
+            icon: "[SET]".to_string(),
+            icon_names: vec!["preferences-system".to_string()],
+            action: ActionType::OpenSettings,
+            score: 0.0,
+        });
+    }
+
+    if "history".contains(command) {
+        results.push(SearchResult {
+            title: "History".to_string(),
+            subtitle: "View search history".to_string(),
+            icon: "[HIST]".to_string(),
+            icon_names: vec!["document-open-recent".to_string()],
+            action: ActionType::OpenHistory,
+            score: 0.0,
+        });
+    }
+
     results
-}
\ No newline at end of file
+}
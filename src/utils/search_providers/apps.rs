@@ -0,0 +1,43 @@
+use crate::utils::{app_index::AppIndex, search::SearchProvider, settings_manager::Settings, utils::SearchResult};
+
+pub struct AppProvider {
+    index: AppIndex,
+}
+
+impl AppProvider {
+    pub fn new() -> Self {
+        Self {
+            index: AppIndex::load(),
+        }
+    }
+}
+
+impl SearchProvider for AppProvider {
+    fn id(&self) -> &'static str {
+        "apps"
+    }
+
+    fn name(&self) -> &'static str {
+        "Application Search"
+    }
+
+    fn description(&self) -> &'static str {
+        "Search for installed applications on your system"
+    }
+
+    fn hint(&self) -> &'static str {
+        "Type an application name"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🔍"
+    }
+
+    fn priority(&self) -> i32 {
+        70
+    }
+
+    fn search(&self, query: &str, _settings: &Settings) -> Vec<SearchResult> {
+        self.index.search(query)
+    }
+}
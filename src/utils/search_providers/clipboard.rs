@@ -0,0 +1,109 @@
+use crate::utils::{clipboard_history::{self, describe_time, ClipContent, ClipEntry}, fuzzy::fuzzy_score, search::SearchProvider, settings_manager::Settings, utils::{ActionType, SearchResult}};
+
+// Longest title before it is cut off
+const TITLE_CHARS: usize = 80;
+// How much of each entry a search looks at, entries can be megabytes of text
+const MATCH_CHARS: usize = 500;
+
+pub struct ClipboardProvider;
+
+impl ClipboardProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SearchProvider for ClipboardProvider {
+    fn id(&self) -> &'static str {
+        "clipboard"
+    }
+
+    fn name(&self) -> &'static str {
+        "Clipboard History"
+    }
+
+    fn description(&self) -> &'static str {
+        "Search what you copied by typing 'cb' first, e.g. 'cb foo'. Recorded in daemon mode"
+    }
+
+    fn icon(&self) -> &'static str {
+        "📋"
+    }
+
+    fn priority(&self) -> i32 {
+        85
+    }
+
+    fn hint(&self) -> &'static str {
+        "Type to search what you copied"
+    }
+
+    fn is_mode_only(&self) -> bool {
+        true
+    }
+
+    fn search(&self, query: &str, _settings: &Settings) -> Vec<SearchResult> {
+        let terms = query.trim();
+
+        let mut matches: Vec<(ClipEntry, f32)> = clipboard_history::entries()
+            .into_iter()
+            .filter_map(|entry| {
+                // A bare "cb" lists everything
+                if terms.is_empty() {
+                    return Some((entry, 0.0));
+                }
+                let score = match &entry.content {
+                    ClipContent::Text { text } => fuzzy_score(terms, head(text, MATCH_CHARS))?,
+                    // Images can only be listed, there is nothing to match
+                    ClipContent::Image { .. } => return None,
+                };
+                Some((entry, score))
+            })
+            .collect();
+        // Entries are stored most recent first, the sort keeps that order among equals
+        matches.sort_by(|(a, a_score), (b, b_score)| b.pinned.cmp(&a.pinned).then(b_score.total_cmp(a_score)));
+
+        matches
+            .into_iter()
+            .enumerate()
+            .map(|(index, (entry, _))| to_result(entry, 100.0 - index as f32 * 0.01))
+            .collect()
+    }
+}
+
+// The first `chars` characters of `text`
+fn head(text: &str, chars: usize) -> &str {
+    text.char_indices().nth(chars).map_or(text, |(end, _)| &text[..end])
+}
+
+fn to_result(entry: ClipEntry, score: f32) -> SearchResult {
+    let age = describe_time(entry.copied_at);
+    let pinned = if entry.pinned { "📌 Pinned, " } else { "" };
+
+    let (title, subtitle, icon_names) = match &entry.content {
+        ClipContent::Text { text } => {
+            let lines = text.lines().count();
+            let mut title: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if title.chars().count() > TITLE_CHARS {
+                title = title.chars().take(TITLE_CHARS - 1).collect::<String>() + "…";
+            }
+            let length = if lines > 1 { format!(", {} lines", lines) } else { String::new() };
+            (title, format!("{}copied {}{}", pinned, age, length), vec!["edit-paste".to_string()])
+        }
+        ClipContent::Image { path, width, height } => (
+            format!("Image {}×{}", width, height),
+            format!("{}copied {}", pinned, age),
+            // The image itself makes the best icon
+            vec![path.to_string_lossy().to_string(), "image-x-generic".to_string()],
+        ),
+    };
+
+    SearchResult {
+        title,
+        subtitle,
+        icon: "[CLIP]".to_string(),
+        icon_names,
+        action: ActionType::CopyClip(entry.id),
+        score,
+    }
+}
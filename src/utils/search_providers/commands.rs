@@ -0,0 +1,55 @@
+use crate::utils::{helpers::helpers::is_command_available, search::SearchProvider, settings_manager::Settings, utils::{ActionType, SearchResult}};
+
+pub struct CommandProvider;
+
+impl CommandProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SearchProvider for CommandProvider {
+    fn id(&self) -> &'static str {
+        "commands"
+    }
+
+    fn name(&self) -> &'static str {
+        "Run Command"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run commands on a new terminal"
+    }
+
+    fn hint(&self) -> &'static str {
+        "Type a command to run in a terminal"
+    }
+
+    fn icon(&self) -> &'static str {
+        "💻"
+    }
+
+    fn priority(&self) -> i32 {
+        60
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn search(&self, query: &str, _settings: &Settings) -> Vec<SearchResult> {
+        let program = query.split_ascii_whitespace().next().unwrap_or(query);
+        if !is_command_available(program) {
+            return Vec::new();
+        }
+
+        vec![SearchResult {
+            title: query.to_string(),
+            subtitle: "Run command".to_string(),
+            icon: "[CMD]".to_string(),
+            icon_names: vec!["utilities-terminal".to_string()],
+            action: ActionType::RunCommand(query.to_lowercase()),
+            score: 60.0,
+        }]
+    }
+}
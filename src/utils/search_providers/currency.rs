@@ -0,0 +1,91 @@
+use std::sync::Once;
+
+use crate::utils::{currency::{describe_age, format_amount, format_rate, CurrencyQuery, CurrencyRates}, live_settings, search::SearchProvider, settings_manager::Settings, utils::{ActionType, SearchResult}};
+
+pub struct CurrencyProvider {
+    rates: CurrencyRates,
+    subscribed: Once,
+}
+
+impl CurrencyProvider {
+    pub fn new() -> Self {
+        Self {
+            rates: CurrencyRates::new(),
+            subscribed: Once::new(),
+        }
+    }
+
+    // Fetches rates right away when the source or refresh interval changes, not on the next conversion.
+    // Until a conversion asked for rates, nothing is downloaded.
+    fn follow_settings(&self) {
+        self.subscribed.call_once(|| {
+            let rates = self.rates.clone();
+            let (id, default) = (self.id(), self.enabled_by_default());
+            live_settings::subscribe(move || {
+                let settings = live_settings::current();
+                if settings.is_provider_enabled(id, default) {
+                    rates.update_if_used(&settings.currency);
+                }
+            });
+        });
+    }
+}
+
+impl SearchProvider for CurrencyProvider {
+    fn id(&self) -> &'static str {
+        "currency"
+    }
+
+    fn name(&self) -> &'static str {
+        "Currency Conversion"
+    }
+
+    fn description(&self) -> &'static str {
+        "Convert between currencies, e.g. '100 usd to eur'"
+    }
+
+    fn icon(&self) -> &'static str {
+        "💱"
+    }
+
+    fn priority(&self) -> i32 {
+        100
+    }
+
+    fn warm_up(&self, _settings: &Settings) {
+        self.follow_settings();
+        // Launching the bar doesn't download anything, only the first conversion does
+        self.rates.reload();
+    }
+
+    fn search(&self, query: &str, settings: &Settings) -> Vec<SearchResult> {
+        let Some(query) = CurrencyQuery::parse(query) else {
+            return Vec::new();
+        };
+
+        // Picks up rates refreshed by another process, a changed source or rates that got old
+        self.follow_settings();
+        self.rates.update(&settings.currency);
+        let Some(conversion) = self.rates.convert(&query) else {
+            return Vec::new();
+        };
+
+        let result = format_amount(conversion.result);
+        vec![SearchResult {
+            title: format!("= {} {}", result, conversion.to),
+            subtitle: format!(
+                "{} {} at 1 {} = {} {}, rates from {}. Click or press Enter to copy",
+                format_amount(conversion.amount),
+                conversion.from,
+                conversion.from,
+                format_rate(conversion.rate),
+                conversion.to,
+                describe_age(conversion.published)
+            ),
+            icon: "[CUR]".to_string(),
+            icon_names: vec!["accessories-calculator".to_string()],
+            action: ActionType::MathResult(result),
+            score: 100.0,
+        }]
+    }
+}
@@ -0,0 +1,47 @@
+use crate::utils::{datetime::evaluate, search::SearchProvider, settings_manager::Settings, utils::{ActionType, SearchResult}};
+
+pub struct DateTimeProvider;
+
+impl DateTimeProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SearchProvider for DateTimeProvider {
+    fn id(&self) -> &'static str {
+        "datetime"
+    }
+
+    fn name(&self) -> &'static str {
+        "Date & Time"
+    }
+
+    fn description(&self) -> &'static str {
+        "Time zones, date differences and Unix timestamps, e.g. 'now in Tokyo'"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🕒"
+    }
+
+    fn priority(&self) -> i32 {
+        100
+    }
+
+    fn search(&self, query: &str, _settings: &Settings) -> Vec<SearchResult> {
+        evaluate(query)
+            .into_iter()
+            .enumerate()
+            .map(|(index, answer)| SearchResult {
+                title: answer.text.clone(),
+                subtitle: format!("{}. Click or press Enter to copy", answer.label),
+                icon: "[TIME]".to_string(),
+                icon_names: vec!["preferences-system-time".to_string(), "x-office-calendar".to_string()],
+                action: ActionType::MathResult(answer.text),
+                // Just above the calculator, which reads "2026-12-25" as a subtraction
+                score: 100.5 - index as f32 * 0.1,
+            })
+            .collect()
+    }
+}
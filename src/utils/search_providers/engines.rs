@@ -0,0 +1,59 @@
+use crate::utils::{search::SearchProvider, settings_manager::Settings, utils::{ActionType, SearchResult, WebQuery}};
+
+pub struct SearchEngineProvider;
+
+impl SearchEngineProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SearchProvider for SearchEngineProvider {
+    fn id(&self) -> &'static str {
+        "search_engines"
+    }
+
+    fn name(&self) -> &'static str {
+        "Search Engine Keywords"
+    }
+
+    fn description(&self) -> &'static str {
+        "Search a specific site by typing its keyword first, e.g. 'gh rust-lang'"
+    }
+
+    fn hint(&self) -> &'static str {
+        "Type a keyword and your search, e.g. 'gh rust-lang'"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🔎"
+    }
+
+    fn priority(&self) -> i32 {
+        85
+    }
+
+    fn search(&self, query: &str, settings: &Settings) -> Vec<SearchResult> {
+        let Some((keyword, terms)) = query.split_once(' ') else {
+            return Vec::new();
+        };
+        let terms = terms.trim();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let Some(engine) = settings.search_engine(keyword) else {
+            return Vec::new();
+        };
+
+        // The keyword makes the intent explicit, so this goes above everything else
+        vec![SearchResult {
+            title: format!("Search {} for \"{}\"", engine.name, terms),
+            subtitle: engine.search_url(terms),
+            icon: "[SRC]".to_string(),
+            icon_names: vec!["system-search".to_string(), "web-browser".to_string()],
+            action: ActionType::WebSearch(WebQuery { engine: engine.keyword.clone(), query: terms.to_string() }),
+            score: 110.0,
+        }]
+    }
+}
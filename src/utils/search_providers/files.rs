@@ -0,0 +1,91 @@
+use std::sync::Once;
+
+use crate::utils::{file_index::FileIndex, live_settings, path_completion::completion_results, paths::{check_path, looks_like_path}, search::SearchProvider, settings_manager::Settings, utils::{ActionType, SearchResult}};
+
+pub struct FileProvider {
+    index: FileIndex,
+    subscribed: Once,
+}
+
+impl FileProvider {
+    pub fn new() -> Self {
+        Self {
+            index: FileIndex::new(),
+            subscribed: Once::new(),
+        }
+    }
+
+    // Restarts the index when its settings change, and stops it when the provider gets disabled
+    fn follow_settings(&self) {
+        self.subscribed.call_once(|| {
+            let index = self.index.clone();
+            let (id, default) = (self.id(), self.enabled_by_default());
+            live_settings::subscribe(move || {
+                let settings = live_settings::current();
+                if settings.is_provider_enabled(id, default) {
+                    index.start(&settings.file_index);
+                } else {
+                    index.stop();
+                }
+            });
+        });
+    }
+}
+
+impl SearchProvider for FileProvider {
+    fn id(&self) -> &'static str {
+        "files"
+    }
+
+    fn name(&self) -> &'static str {
+        "File & Folder Search"
+    }
+
+    fn description(&self) -> &'static str {
+        "Open paths directly or find files and folders by name"
+    }
+
+    fn hint(&self) -> &'static str {
+        "Type part of a file or folder name"
+    }
+
+    fn icon(&self) -> &'static str {
+        "📁"
+    }
+
+    fn priority(&self) -> i32 {
+        90
+    }
+
+    fn warm_up(&self, settings: &Settings) {
+        self.follow_settings();
+        self.index.start(&settings.file_index);
+    }
+
+    fn search(&self, query: &str, settings: &Settings) -> Vec<SearchResult> {
+        // In case the provider was only enabled after startup
+        self.follow_settings();
+        self.index.start(&settings.file_index);
+
+        let mut results: Vec<SearchResult> = check_path(query).into_iter().collect();
+
+        // Typed paths list the directory's children instead of searching file names
+        let found = if looks_like_path(query) {
+            completion_results(query)
+        } else {
+            self.index.search(query)
+        };
+
+        for result in found {
+            let duplicate = results.iter().any(|existing| match (&existing.action, &result.action) {
+                (ActionType::OpenPath(a), ActionType::OpenPath(b)) => a == b,
+                _ => false,
+            });
+            if !duplicate {
+                results.push(result);
+            }
+        }
+
+        results
+    }
+}
@@ -0,0 +1,69 @@
+use crate::utils::{calculator::calculate, search::SearchProvider, settings_manager::Settings, utils::{ActionType, SearchResult}};
+
+pub struct MathProvider;
+
+impl MathProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SearchProvider for MathProvider {
+    fn id(&self) -> &'static str {
+        "math"
+    }
+
+    fn name(&self) -> &'static str {
+        "Math Evaluation"
+    }
+
+    fn description(&self) -> &'static str {
+        "Evaluate expressions, convert units and number bases"
+    }
+
+    fn hint(&self) -> &'static str {
+        "Type an expression, e.g. '15% of 80' or '5 km to mi'"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🔢"
+    }
+
+    fn priority(&self) -> i32 {
+        100
+    }
+
+    fn search(&self, query: &str, _settings: &Settings) -> Vec<SearchResult> {
+        let Some(calculation) = calculate(query) else {
+            return Vec::new();
+        };
+
+        calculation
+            .answers
+            .into_iter()
+            .enumerate()
+            .map(|(index, answer)| {
+                let (title, subtitle) = match &calculation.assignment {
+                    Some(name) => (
+                        format!("{} = {}", name, answer.text),
+                        format!("{}. Press Enter to store as {} and copy", answer.label, name),
+                    ),
+                    None => (
+                        format!("= {}", answer.text),
+                        format!("{}. Click or press Enter to copy", answer.label),
+                    ),
+                };
+
+                SearchResult {
+                    title,
+                    subtitle,
+                    icon: "[CALC]".to_string(),
+                    icon_names: vec!["accessories-calculator".to_string()],
+                    action: ActionType::MathResult(answer.text),
+                    // Alternative forms stay right below the main one
+                    score: 100.0 - index as f32 * 0.1,
+                }
+            })
+            .collect()
+    }
+}
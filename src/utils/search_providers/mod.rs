@@ -0,0 +1,10 @@
+pub mod apps;
+pub mod clipboard;
+pub mod commands;
+pub mod currency;
+pub mod datetime;
+pub mod engines;
+pub mod files;
+pub mod math;
+pub mod url;
+pub mod web;
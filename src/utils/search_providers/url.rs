@@ -0,0 +1,46 @@
+use crate::utils::{helpers::helpers::is_url, search::SearchProvider, settings_manager::Settings, utils::{ActionType, SearchResult}};
+
+pub struct UrlProvider;
+
+impl UrlProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SearchProvider for UrlProvider {
+    fn id(&self) -> &'static str {
+        "url"
+    }
+
+    fn name(&self) -> &'static str {
+        "Open URLs"
+    }
+
+    fn description(&self) -> &'static str {
+        "Open web addresses directly in the browser"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🔗"
+    }
+
+    fn priority(&self) -> i32 {
+        80
+    }
+
+    fn search(&self, query: &str, _settings: &Settings) -> Vec<SearchResult> {
+        if !is_url(query) {
+            return Vec::new();
+        }
+
+        vec![SearchResult {
+            title: query.to_string(),
+            subtitle: "Open URL".to_string(),
+            icon: "[URL]".to_string(),
+            icon_names: vec!["text-html".to_string(), "web-browser".to_string()],
+            action: ActionType::OpenUrl(query.to_string()),
+            score: 80.0,
+        }]
+    }
+}
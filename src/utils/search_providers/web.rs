@@ -0,0 +1,52 @@
+use crate::utils::{search::SearchProvider, settings_manager::Settings, utils::{ActionType, SearchResult, WebQuery}};
+
+pub struct WebSearchProvider;
+
+impl WebSearchProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SearchProvider for WebSearchProvider {
+    fn id(&self) -> &'static str {
+        "web"
+    }
+
+    fn name(&self) -> &'static str {
+        "Web Search"
+    }
+
+    fn description(&self) -> &'static str {
+        "Fallback to web search when no local results found"
+    }
+
+    fn hint(&self) -> &'static str {
+        "Type something to search the web for"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🌐"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn is_fallback(&self) -> bool {
+        true
+    }
+
+    fn search(&self, query: &str, settings: &Settings) -> Vec<SearchResult> {
+        let engine = settings.default_engine();
+
+        vec![SearchResult {
+            title: format!("Search for \"{}\"", query),
+            subtitle: format!("Search on {}", engine.name),
+            icon: "[SRC]".to_string(),
+            icon_names: vec!["system-search".to_string(), "web-browser".to_string()],
+            action: ActionType::WebSearch(WebQuery { engine: engine.keyword, query: query.to_string() }),
+            score: 0.0,
+        }]
+    }
+}
@@ -1,32 +1,326 @@
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::utils::{url_encoding::fill_template, utils::WebQuery};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Version of the settings format this build writes, see `MIGRATIONS`
+pub const SETTINGS_VERSION: u32 = 2;
+
+// Missing fields take their value from `Settings::default()`, unknown ones are ignored
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
+    pub version: u32,
     pub enable_history: bool,
-    pub enable_web_search: bool,
-    pub enable_math_eval: bool,
-    pub enable_file_search: bool,
-    pub enable_app_search: bool,
-    pub enable_run_commands: bool,
+    // Provider id -> enabled. Providers missing from the map use their own default
+    pub providers: BTreeMap<String, bool>,
     pub terminal_command: String,
     pub text_editor_command: String,
+    pub window: WindowSettings,
+    // Name of a built-in or user theme, or "system" to follow the desktop's light or dark preference
+    pub theme: String,
+    pub file_index: FileIndexSettings,
+    pub search_engines: Vec<SearchEngine>,
+    // Keyword of the engine used for plain web searches
+    pub default_search_engine: String,
+    pub currency: CurrencySettings,
+    pub clipboard: ClipboardSettings,
+    pub query_modes: Vec<QueryMode>,
+}
+
+/// Size, font and placement of the search bar
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub width: f32,
+    pub max_visible_rows: usize,
+    // A font name fontconfig knows or the path of a font file, empty for the built-in font
+    pub font_family: String,
+    // Size of result titles, the rest of the text grows or shrinks with it. 14 keeps the theme's sizes
+    pub font_size: f32,
+    // Zoom on top of the display's own scale, for HiDPI screens the desktop doesn't scale
+    pub ui_scale: f32,
+    pub anchor: WindowAnchor,
+    // Pixels to shift the window from where the anchor puts it
+    pub offset: [i32; 2],
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: 500.0,
+            max_visible_rows: 5,
+            font_family: String::new(),
+            font_size: DEFAULT_FONT_SIZE,
+            ui_scale: 1.0,
+            anchor: WindowAnchor::TopCenter,
+            offset: [0, 0],
+        }
+    }
+}
+
+impl WindowSettings {
+    // A hand edited file may hold sizes the window can't use
+    pub fn clamped_width(&self) -> f32 {
+        self.width.max(200.0)
+    }
+
+    pub fn clamped_scale(&self) -> f32 {
+        self.ui_scale.clamp(0.5, 4.0)
+    }
+}
+
+pub const DEFAULT_FONT_SIZE: f32 = 14.0;
+
+/// Where the search bar opens, its `offset` is added on top
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindowAnchor {
+    // Centered, a little below the top edge of the screen
+    #[default]
+    TopCenter,
+    Center,
+    UnderCursor,
+    // At the offset from the top left corner of the monitor
+    Custom,
+}
+
+/// A web search engine, reachable by typing its keyword before the query
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchEngine {
+    pub name: String,
+    pub keyword: String,
+    // "{query}" is replaced by the encoded search terms
+    pub url: String,
+}
+
+impl SearchEngine {
+    fn new(name: &str, keyword: &str, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            keyword: keyword.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    pub fn search_url(&self, query: &str) -> String {
+        fill_template(&self.url, "{query}", query)
+    }
+}
+
+fn default_search_engines() -> Vec<SearchEngine> {
+    vec![
+        SearchEngine::new("Google", "g", "https://www.google.com/search?q={query}"),
+        SearchEngine::new("DuckDuckGo", "ddg", "https://duckduckgo.com/?q={query}"),
+        SearchEngine::new("Wikipedia", "wiki", "https://en.wikipedia.org/wiki/Special:Search?search={query}"),
+        SearchEngine::new("GitHub", "gh", "https://github.com/search?q={query}"),
+        SearchEngine::new("crates.io", "crates", "https://crates.io/search?q={query}"),
+    ]
+}
+
+fn default_search_engine() -> String {
+    "g".to_string()
+}
+
+/// Typing `prefix` at the start of a query searches only the provider with the id `provider`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueryMode {
+    pub prefix: String,
+    pub provider: String,
+}
+
+impl QueryMode {
+    fn new(prefix: &str, provider: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            provider: provider.to_string(),
+        }
+    }
+}
+
+fn default_query_modes() -> Vec<QueryMode> {
+    vec![
+        QueryMode::new("=", "math"),
+        QueryMode::new("/", "files"),
+        QueryMode::new(">", "commands"),
+        QueryMode::new("?", "web"),
+        QueryMode::new("!", "search_engines"),
+        QueryMode::new("cb", "clipboard"),
+    ]
+}
+
+/// What the file search provider indexes
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileIndexSettings {
+    // Directories to index, "~" and "$VAR" are expanded
+    pub roots: Vec<String>,
+    pub max_depth: usize,
+    // The size limit of the index: it stops growing once it holds this many files and folders.
+    // It counts entries, how big the files are on disk doesn't matter.
+    pub max_entries: usize,
+    pub include_hidden: bool,
+}
+
+impl Default for FileIndexSettings {
+    fn default() -> Self {
+        Self {
+            roots: vec!["~".to_string()],
+            max_depth: 8,
+            max_entries: 200_000,
+            include_hidden: false,
+        }
+    }
+}
+
+/// Where currency exchange rates come from
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CurrencySettings {
+    // A JSON endpoint (http or https) or a local file in the same format
+    pub source: String,
+    // How old the cached rates may get before a conversion fetches them again, 0 to never fetch on its own
+    pub refresh_hours: usize,
+}
+
+impl Default for CurrencySettings {
+    fn default() -> Self {
+        Self {
+            source: "https://open.er-api.com/v6/latest/USD".to_string(),
+            refresh_hours: 12,
+        }
+    }
+}
+
+/// What the clipboard history records while Quick Search runs as a daemon
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardSettings {
+    pub enabled: bool,
+    // The oldest unpinned entries are dropped beyond this
+    pub max_entries: usize,
+    pub record_images: bool,
+    // Copies offering one of these MIME types are never recorded, password managers mark secrets this way
+    pub excluded_types: Vec<String>,
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 200,
+            record_images: false,
+            excluded_types: vec!["x-kde-passwordManagerHint".to_string()],
+        }
+    }
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            version: SETTINGS_VERSION,
             enable_history: true,
-            enable_web_search: true,
-            enable_math_eval: true,
-            enable_file_search: true,
-            enable_app_search: true,
-            enable_run_commands: false,
+            providers: BTreeMap::new(),
             terminal_command: String::new(),
             text_editor_command: String::new(),
+            window: WindowSettings::default(),
+            theme: "dark".to_string(),
+            file_index: FileIndexSettings::default(),
+            search_engines: default_search_engines(),
+            default_search_engine: default_search_engine(),
+            currency: CurrencySettings::default(),
+            clipboard: ClipboardSettings::default(),
+            query_modes: default_query_modes(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn is_provider_enabled(&self, id: &str, default: bool) -> bool {
+        self.providers.get(id).copied().unwrap_or(default)
+    }
+
+    pub fn search_engine(&self, keyword: &str) -> Option<&SearchEngine> {
+        self.search_engines.iter().find(|engine| engine.keyword == keyword)
+    }
+
+    /// The engine for plain web searches. Falls back to the first configured one, then to Google.
+    pub fn default_engine(&self) -> SearchEngine {
+        self.search_engine(&self.default_search_engine)
+            .or_else(|| self.search_engines.first())
+            .cloned()
+            .unwrap_or_else(|| default_search_engines().swap_remove(0))
+    }
+
+    /// The engine a web query goes to, the default one if its keyword is unknown
+    pub fn engine_for(&self, query: &WebQuery) -> SearchEngine {
+        self.search_engine(&query.engine).cloned().unwrap_or_else(|| self.default_engine())
+    }
+}
+
+// MIGRATIONS[n] turns a version n settings file into version n + 1
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[
+    provider_flags_to_map,
+    window_settings_to_object,
+];
+
+const _: () = assert!(MIGRATIONS.len() == SETTINGS_VERSION as usize);
+
+// Older settings files had one `enable_*` flag per search source
+const LEGACY_PROVIDER_FLAGS: &[(&str, &[&str])] = &[
+    ("enable_app_search", &["apps"]),
+    ("enable_web_search", &["web", "url"]),
+    ("enable_math_eval", &["math"]),
+    ("enable_file_search", &["files"]),
+    ("enable_run_commands", &["commands"]),
+];
+
+// Version 0 to 1: the `enable_*` flags became the `providers` map
+fn provider_flags_to_map(object: &mut Map<String, Value>) {
+    let mut providers = Map::new();
+    for (flag, ids) in LEGACY_PROVIDER_FLAGS {
+        if let Some(enabled) = object.remove(*flag).and_then(|v| v.as_bool()) {
+            for id in *ids {
+                providers.insert(id.to_string(), Value::Bool(enabled));
+            }
         }
     }
+
+    // Files written before versions existed may already have the map
+    if !object.contains_key("providers") {
+        object.insert("providers".to_string(), Value::Object(providers));
+    }
+}
+
+// Version 1 to 2: the number of visible rows joined the other search bar settings in `window`
+fn window_settings_to_object(object: &mut Map<String, Value>) {
+    let mut window = match object.remove("window") {
+        Some(Value::Object(window)) => window,
+        _ => Map::new(),
+    };
+    if let Some(rows) = object.remove("max_visible_rows") {
+        window.entry("max_visible_rows").or_insert(rows);
+    }
+    object.insert("window".to_string(), Value::Object(window));
+}
+
+/// What went wrong reading the settings file. The settings that could be read are still used,
+/// everything else has its default.
+#[derive(Clone, Debug, Default)]
+pub struct SettingsProblems {
+    pub errors: Vec<String>,
+    // Copy of the file as it was, before it gets saved over
+    pub backup: Option<PathBuf>,
+}
+
+impl SettingsProblems {
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
 }
 
 pub struct SettingsManager {
@@ -48,23 +342,273 @@ impl SettingsManager {
     
         PathBuf::from("settings.json")
     }
-    
+
+    pub fn settings_path(&self) -> &Path {
+        &self.settings_file
+    }
+
+    /// Loads the settings, printing any problems with the file to stderr once
     pub fn load_settings(&self) -> Settings {
-        if !self.settings_file.exists() {
-            return Settings::default();
+        self.load_settings_checked().0
+    }
+
+    /// Loads the settings and reports what could not be read instead of silently dropping it
+    pub fn load_settings_checked(&self) -> (Settings, SettingsProblems) {
+        let content = match fs::read_to_string(&self.settings_file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return (Settings::default(), SettingsProblems::default()),
+            Err(e) => {
+                let problems = SettingsProblems {
+                    errors: vec![format!("Can't read {}: {}", self.settings_file.display(), e)],
+                    backup: None,
+                };
+                self.report(&problems, "");
+                return (Settings::default(), problems);
+            }
+        };
+
+        let (settings, errors) = parse_settings(&content);
+        let mut problems = SettingsProblems { errors, backup: None };
+        if !problems.is_empty() {
+            problems.backup = self.backup(&content);
+            self.report(&problems, &content);
         }
-        
-        match fs::read_to_string(&self.settings_file) {
-            Ok(content) => {
-                serde_json::from_str(&content).unwrap_or_else(|_| Settings::default())
+        (settings, problems)
+    }
+
+    // Keeps the unreadable file next to the settings, unless the backup already holds the same content
+    fn backup(&self, content: &str) -> Option<PathBuf> {
+        let backup = self.settings_file.with_extension("json.bak");
+        if fs::read_to_string(&backup).is_ok_and(|existing| existing == content) {
+            return Some(backup);
+        }
+        match fs::write(&backup, content) {
+            Ok(()) => Some(backup),
+            Err(e) => {
+                eprintln!("Failed to back up settings: {}", e);
+                None
             }
-            Err(_) => Settings::default(),
+        }
+    }
+
+    // Settings are loaded for every search, only the first load of a broken file prints
+    fn report(&self, problems: &SettingsProblems, content: &str) {
+        static REPORTED: Mutex<Option<String>> = Mutex::new(None);
+        let Ok(mut reported) = REPORTED.lock() else {
+            return;
+        };
+        if reported.as_deref() == Some(content) {
+            return;
+        }
+        *reported = Some(content.to_string());
+
+        eprintln!("Problems in {}:", self.settings_file.display());
+        for error in &problems.errors {
+            eprintln!("  {}", error);
+        }
+        if let Some(backup) = &problems.backup {
+            eprintln!("The original file was copied to {}", backup.display());
         }
     }
     
     pub fn save_settings(&self, settings: &Settings) -> Result<(), std::io::Error> {
+        // Written whole and renamed, so running instances reloading it never read half a file
         let json = serde_json::to_string_pretty(settings)?;
-        fs::write(&self.settings_file, json)?;
+        let temp = self.settings_file.with_extension("json.tmp");
+        fs::write(&temp, json)?;
+        fs::rename(temp, &self.settings_file)?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Reads a settings file of any version. Returns the settings and a description of every part
+/// that had to be skipped: a syntax error leaves only defaults, a bad value only resets that value.
+pub fn parse_settings(content: &str) -> (Settings, Vec<String>) {
+    let mut value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => return (Settings::default(), vec![describe_json_error(&e)]),
+    };
+    let Some(object) = value.as_object_mut() else {
+        return (Settings::default(), vec!["The settings file must hold a JSON object".to_string()]);
+    };
+
+    let mut errors = Vec::new();
+    let version = object.get("version").and_then(Value::as_u64).unwrap_or(0) as usize;
+    if version > SETTINGS_VERSION as usize {
+        errors.push(format!(
+            "The file is from a newer version of Quick Search (settings version {}, this one knows {})",
+            version, SETTINGS_VERSION
+        ));
+    }
+    for migrate in MIGRATIONS.iter().skip(version) {
+        migrate(object);
+    }
+    object.insert("version".to_string(), Value::from(SETTINGS_VERSION));
+
+    let known = serde_json::to_value(Settings::default()).unwrap_or_default();
+    for key in object.keys().filter(|key| known.get(key.as_str()).is_none()) {
+        errors.push(format!("Unknown setting \"{}\" was ignored", key));
+    }
+
+    if let Ok(settings) = serde_json::from_value::<Settings>(value.clone()) {
+        return (settings, errors);
+    }
+
+    // The text itself knows where the first bad value is
+    if let Err(e) = serde_json::from_str::<Settings>(content) {
+        errors.push(describe_json_error(&e));
+    }
+
+    let (settings, reset) = recover_settings(known, &value);
+    if !reset.is_empty() {
+        errors.push(format!("Reset to their defaults: {}", reset.join(", ")));
+    }
+    (settings, errors)
+}
+
+// "Line 4, column 21: invalid type: string "5", expected usize"
+fn describe_json_error(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    // serde_json puts the location at the end of its message
+    let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+    format!("Line {}, column {}: {}", error.line(), error.column(), message)
+}
+
+// Copies the values of `user` onto the defaults one by one, skipping those that break deserializing.
+// Objects are descended into, so one bad value only resets itself. Returns the dotted paths skipped.
+fn recover_settings(defaults: Value, user: &Value) -> (Settings, Vec<String>) {
+    let mut leaves = Vec::new();
+    collect_leaves(user, &mut Vec::new(), &mut leaves);
+
+    let mut merged = defaults;
+    let mut reset = Vec::new();
+    for (path, value) in leaves {
+        let previous = set_path(&mut merged, &path, Some(value));
+        if serde_json::from_value::<Settings>(merged.clone()).is_err() {
+            set_path(&mut merged, &path, previous);
+            reset.push(path.join("."));
+        }
+    }
+
+    (serde_json::from_value(merged).unwrap_or_default(), reset)
+}
+
+fn collect_leaves(value: &Value, path: &mut Vec<String>, leaves: &mut Vec<(Vec<String>, Value)>) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, child) in object {
+                path.push(key.clone());
+                collect_leaves(child, path, leaves);
+                path.pop();
+            }
+        }
+        _ => leaves.push((path.clone(), value.clone())),
+    }
+}
+
+// Sets or, with `None`, removes the value at `path`, creating objects on the way. Returns what was there.
+fn set_path(root: &mut Value, path: &[String], value: Option<Value>) -> Option<Value> {
+    let (last, parents) = path.split_last()?;
+    let mut current = root;
+    for key in parents {
+        let object = current.as_object_mut()?;
+        current = object.entry(key.clone()).or_insert_with(|| Value::Object(Map::new()));
+    }
+
+    let object = current.as_object_mut()?;
+    match value {
+        Some(value) => object.insert(last.clone(), value),
+        None => object.remove(last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_version_0_flags() {
+        let (settings, errors) = parse_settings(
+            r#"{
+                "enable_history": false,
+                "enable_web_search": true,
+                "enable_math_eval": true,
+                "enable_file_search": false,
+                "enable_app_search": false,
+                "enable_run_commands": true,
+                "terminal_command": "kitty",
+                "text_editor_command": ""
+            }"#,
+        );
+
+        assert_eq!(errors, Vec::<String>::new());
+        assert!(!settings.enable_history);
+        assert_eq!(settings.terminal_command, "kitty");
+        let expected = [("apps", false), ("commands", true), ("files", false), ("math", true), ("url", true), ("web", true)];
+        assert_eq!(settings.providers, expected.map(|(id, enabled)| (id.to_string(), enabled)).into());
+    }
+
+    #[test]
+    fn migrates_version_1_rows_into_window() {
+        let (settings, errors) = parse_settings(r#"{ "version": 1, "max_visible_rows": 8, "providers": { "files": false } }"#);
+
+        assert_eq!(errors, Vec::<String>::new());
+        assert_eq!(settings.window.max_visible_rows, 8);
+        assert_eq!(settings.window.width, WindowSettings::default().width);
+        assert!(!settings.is_provider_enabled("files", true));
+    }
+
+    #[test]
+    fn a_bad_value_only_resets_itself() {
+        let (settings, errors) = parse_settings(
+            r#"{
+  "version": 2,
+  "terminal_command": "kitty",
+  "window": { "width": "wide", "max_visible_rows": 7 }
+}"#,
+        );
+
+        assert_eq!(settings.terminal_command, "kitty");
+        assert_eq!(settings.window.max_visible_rows, 7);
+        assert_eq!(settings.window.width, WindowSettings::default().width);
+        assert_eq!(
+            errors,
+            [
+                "Line 4, column 29: invalid type: string \"wide\", expected f32",
+                "Reset to their defaults: window.width",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_reported() {
+        let (settings, errors) = parse_settings(r#"{ "version": 2, "colour": "red", "enable_history": false }"#);
+
+        assert!(!settings.enable_history);
+        assert_eq!(errors, ["Unknown setting \"colour\" was ignored"]);
+    }
+
+    #[test]
+    fn syntax_errors_point_at_the_line_and_column() {
+        let (settings, errors) = parse_settings("{\n  \"enable_history\": false,\n  \"terminal_command\": kitty\n}");
+
+        assert!(settings.enable_history);
+        assert_eq!(errors, ["Line 3, column 23: expected value"]);
+    }
+
+    #[test]
+    fn files_from_newer_versions_are_read_as_far_as_possible() {
+        let (settings, errors) = parse_settings(r#"{ "version": 99, "terminal_command": "foot" }"#);
+
+        assert_eq!(settings.terminal_command, "foot");
+        assert_eq!(errors, ["The file is from a newer version of Quick Search (settings version 99, this one knows 2)"]);
+    }
+
+    #[test]
+    fn non_objects_leave_the_defaults() {
+        let (settings, errors) = parse_settings("[1, 2]");
+
+        assert_eq!(settings, Settings::default());
+        assert_eq!(errors, ["The settings file must hold a JSON object"]);
+    }
+}
@@ -0,0 +1,133 @@
+// Units the calculator understands, each defined relative to its dimension's base unit
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dimension {
+    Length,
+    Mass,
+    Time,
+    Area,
+    Volume,
+    Data,
+    Speed,
+    Temperature,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    /// Shown in results
+    pub symbol: &'static str,
+    pub dimension: Dimension,
+    // base = value * factor + offset. Only temperatures have an offset.
+    factor: f64,
+    offset: f64,
+}
+
+impl Unit {
+    const fn new(symbol: &'static str, dimension: Dimension, factor: f64) -> Self {
+        Self { symbol, dimension, factor, offset: 0.0 }
+    }
+
+    pub fn to_base(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    pub fn in_unit(&self, value: f64) -> f64 {
+        (value - self.offset) / self.factor
+    }
+}
+
+use Dimension::*;
+
+// Every name a unit can be written as besides its symbol
+static UNITS: &[(&[&str], Unit)] = &[
+    // Length, in meters
+    (&["m", "meter", "meters", "metre", "metres"], Unit::new("m", Length, 1.0)),
+    (&["km", "kilometer", "kilometers", "kilometre", "kilometres"], Unit::new("km", Length, 1000.0)),
+    (&["cm", "centimeter", "centimeters"], Unit::new("cm", Length, 0.01)),
+    (&["mm", "millimeter", "millimeters"], Unit::new("mm", Length, 0.001)),
+    (&["um", "µm", "micrometer", "micrometers"], Unit::new("µm", Length, 1e-6)),
+    (&["nm", "nanometer", "nanometers"], Unit::new("nm", Length, 1e-9)),
+    (&["mi", "mile", "miles"], Unit::new("mi", Length, 1609.344)),
+    (&["yd", "yard", "yards"], Unit::new("yd", Length, 0.9144)),
+    (&["ft", "foot", "feet"], Unit::new("ft", Length, 0.3048)),
+    (&["in", "inch", "inches"], Unit::new("in", Length, 0.0254)),
+    (&["nmi"], Unit::new("nmi", Length, 1852.0)),
+    // Mass, in kilograms
+    (&["kg", "kilogram", "kilograms"], Unit::new("kg", Mass, 1.0)),
+    (&["g", "gram", "grams"], Unit::new("g", Mass, 0.001)),
+    (&["mg", "milligram", "milligrams"], Unit::new("mg", Mass, 1e-6)),
+    (&["t", "tonne", "tonnes"], Unit::new("t", Mass, 1000.0)),
+    (&["lb", "lbs", "pound", "pounds"], Unit::new("lb", Mass, 0.45359237)),
+    (&["oz", "ounce", "ounces"], Unit::new("oz", Mass, 0.028349523125)),
+    (&["st", "stone", "stones"], Unit::new("st", Mass, 6.35029318)),
+    // Time, in seconds
+    (&["s", "sec", "secs", "second", "seconds"], Unit::new("s", Time, 1.0)),
+    (&["ms", "millisecond", "milliseconds"], Unit::new("ms", Time, 0.001)),
+    (&["us", "µs", "microsecond", "microseconds"], Unit::new("µs", Time, 1e-6)),
+    (&["ns", "nanosecond", "nanoseconds"], Unit::new("ns", Time, 1e-9)),
+    (&["min", "mins", "minute", "minutes"], Unit::new("min", Time, 60.0)),
+    (&["h", "hr", "hrs", "hour", "hours"], Unit::new("h", Time, 3600.0)),
+    (&["d", "day", "days"], Unit::new("d", Time, 86400.0)),
+    (&["wk", "week", "weeks"], Unit::new("wk", Time, 604800.0)),
+    (&["month", "months"], Unit::new("month", Time, 2629746.0)),
+    (&["yr", "year", "years"], Unit::new("yr", Time, 31556952.0)),
+    // Area, in square meters
+    (&["m2", "sqm"], Unit::new("m²", Area, 1.0)),
+    (&["km2", "sqkm"], Unit::new("km²", Area, 1e6)),
+    (&["cm2"], Unit::new("cm²", Area, 1e-4)),
+    (&["ha", "hectare", "hectares"], Unit::new("ha", Area, 1e4)),
+    (&["acre", "acres"], Unit::new("acre", Area, 4046.8564224)),
+    (&["ft2", "sqft"], Unit::new("ft²", Area, 0.09290304)),
+    (&["mi2", "sqmi"], Unit::new("mi²", Area, 2589988.110336)),
+    // Volume, in liters
+    (&["l", "L", "liter", "liters", "litre", "litres"], Unit::new("L", Volume, 1.0)),
+    (&["ml", "mL", "milliliter", "milliliters"], Unit::new("mL", Volume, 0.001)),
+    (&["cl", "cL"], Unit::new("cL", Volume, 0.01)),
+    (&["dl", "dL"], Unit::new("dL", Volume, 0.1)),
+    (&["m3"], Unit::new("m³", Volume, 1000.0)),
+    (&["gal", "gallon", "gallons"], Unit::new("gal", Volume, 3.785411784)),
+    (&["qt", "quart", "quarts"], Unit::new("qt", Volume, 0.946352946)),
+    (&["pt", "pint", "pints"], Unit::new("pt", Volume, 0.473176473)),
+    (&["cup", "cups"], Unit::new("cup", Volume, 0.2365882365)),
+    (&["floz"], Unit::new("fl oz", Volume, 0.0295735295625)),
+    (&["tbsp"], Unit::new("tbsp", Volume, 0.01478676478125)),
+    (&["tsp"], Unit::new("tsp", Volume, 0.00492892159375)),
+    // Data, in bytes
+    (&["B", "byte", "bytes"], Unit::new("B", Data, 1.0)),
+    (&["bit", "bits"], Unit::new("bit", Data, 0.125)),
+    (&["kB", "KB"], Unit::new("kB", Data, 1e3)),
+    (&["MB"], Unit::new("MB", Data, 1e6)),
+    (&["GB"], Unit::new("GB", Data, 1e9)),
+    (&["TB"], Unit::new("TB", Data, 1e12)),
+    (&["PB"], Unit::new("PB", Data, 1e15)),
+    (&["KiB"], Unit::new("KiB", Data, 1024.0)),
+    (&["MiB"], Unit::new("MiB", Data, 1048576.0)),
+    (&["GiB"], Unit::new("GiB", Data, 1073741824.0)),
+    (&["TiB"], Unit::new("TiB", Data, 1099511627776.0)),
+    (&["Mbit"], Unit::new("Mbit", Data, 125000.0)),
+    (&["Gbit"], Unit::new("Gbit", Data, 125000000.0)),
+    // Speed, in meters per second
+    (&["m/s", "mps"], Unit::new("m/s", Speed, 1.0)),
+    (&["km/h", "kmh", "kph"], Unit::new("km/h", Speed, 1.0 / 3.6)),
+    (&["mph"], Unit::new("mph", Speed, 0.44704)),
+    (&["ft/s", "fps"], Unit::new("ft/s", Speed, 0.3048)),
+    (&["kn", "knot", "knots"], Unit::new("kn", Speed, 1852.0 / 3600.0)),
+    // Temperature, in kelvin
+    (&["K", "kelvin"], Unit::new("K", Temperature, 1.0)),
+    (&["C", "°C", "celsius"], Unit { symbol: "°C", dimension: Temperature, factor: 1.0, offset: 273.15 }),
+    (&["F", "°F", "fahrenheit"], Unit { symbol: "°F", dimension: Temperature, factor: 5.0 / 9.0, offset: 459.67 * 5.0 / 9.0 }),
+];
+
+pub fn find_unit(name: &str) -> Option<&'static Unit> {
+    UNITS
+        .iter()
+        .find(|(names, unit)| names.contains(&name) || unit.symbol == name)
+        .or_else(|| {
+            // Spelled out names are also accepted capitalized, "Miles" or "KM" are not ambiguous
+            let lower = name.to_lowercase();
+            (name.chars().count() > 2)
+                .then(|| UNITS.iter().find(|(names, _)| names.iter().any(|n| n.chars().count() > 2 && *n == lower)))
+                .flatten()
+        })
+        .map(|(_, unit)| unit)
+}
@@ -0,0 +1,125 @@
+// Percent-encoding per RFC 3986: https://www.rfc-editor.org/rfc/rfc3986#section-2.1
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EncodeMode {
+    /// A value inside the query string: spaces become '+', and '&', '=', '+' and '#' are escaped
+    QueryComponent,
+    /// One path segment: spaces become "%20" and '/' is escaped
+    PathSegment,
+}
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+// pchar from the RFC, minus the percent sign which always gets escaped
+fn is_path_char(byte: u8) -> bool {
+    is_unreserved(byte) || matches!(byte, b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' | b':' | b'@')
+}
+
+/// Encodes the UTF-8 bytes of `text` for use in the given part of a URL
+pub fn encode(text: &str, mode: EncodeMode) -> String {
+    let mut encoded = String::with_capacity(text.len());
+
+    for byte in text.bytes() {
+        match mode {
+            EncodeMode::QueryComponent if byte == b' ' => encoded.push('+'),
+            EncodeMode::QueryComponent if is_unreserved(byte) => encoded.push(byte as char),
+            EncodeMode::PathSegment if is_path_char(byte) => encoded.push(byte as char),
+            _ => {
+                let _ = write!(encoded, "%{:02X}", byte);
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Replaces every `placeholder` in `template` with `value`, encoded for where it appears:
+/// before the '?' it is a path segment, after it a query component.
+pub fn fill_template(template: &str, placeholder: &str, value: &str) -> String {
+    let query_start = template.find(['?', '#']).unwrap_or(template.len());
+    let mut filled = String::with_capacity(template.len() + value.len());
+    let mut rest = 0;
+
+    for (index, _) in template.match_indices(placeholder) {
+        filled.push_str(&template[rest..index]);
+
+        let mode = if index >= query_start { EncodeMode::QueryComponent } else { EncodeMode::PathSegment };
+        filled.push_str(&encode(value, mode));
+
+        rest = index + placeholder.len();
+    }
+    filled.push_str(&template[rest..]);
+
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreserved_characters_pass_through() {
+        let text = "AZaz09-._~";
+        assert_eq!(encode(text, EncodeMode::QueryComponent), text);
+        assert_eq!(encode(text, EncodeMode::PathSegment), text);
+    }
+
+    #[test]
+    fn spaces_depend_on_the_mode() {
+        assert_eq!(encode("rust lang", EncodeMode::QueryComponent), "rust+lang");
+        assert_eq!(encode("rust lang", EncodeMode::PathSegment), "rust%20lang");
+    }
+
+    #[test]
+    fn two_byte_characters() {
+        assert_eq!(encode("café", EncodeMode::QueryComponent), "caf%C3%A9");
+        assert_eq!(encode("über", EncodeMode::PathSegment), "%C3%BCber");
+    }
+
+    #[test]
+    fn three_byte_characters() {
+        assert_eq!(encode("中文", EncodeMode::QueryComponent), "%E4%B8%AD%E6%96%87");
+    }
+
+    #[test]
+    fn four_byte_characters() {
+        assert_eq!(encode("🦀", EncodeMode::QueryComponent), "%F0%9F%A6%80");
+        assert_eq!(encode("a🦀b", EncodeMode::PathSegment), "a%F0%9F%A6%80b");
+    }
+
+    #[test]
+    fn query_delimiters_are_escaped_in_query_components() {
+        assert_eq!(encode("a&b=c+d#e?f/g%", EncodeMode::QueryComponent), "a%26b%3Dc%2Bd%23e%3Ff%2Fg%25");
+    }
+
+    #[test]
+    fn path_segments_escape_slashes_but_keep_sub_delimiters() {
+        assert_eq!(encode("a/b?c#d%e", EncodeMode::PathSegment), "a%2Fb%3Fc%23d%25e");
+        assert_eq!(encode("C++:(x)@y", EncodeMode::PathSegment), "C++:(x)@y");
+    }
+
+    #[test]
+    fn templates_encode_by_position() {
+        assert_eq!(
+            fill_template("https://example.com/search?q={query}", "{query}", "crème brûlée"),
+            "https://example.com/search?q=cr%C3%A8me+br%C3%BBl%C3%A9e"
+        );
+        assert_eq!(
+            fill_template("https://en.wikipedia.org/wiki/{query}", "{query}", "Ferris the crab"),
+            "https://en.wikipedia.org/wiki/Ferris%20the%20crab"
+        );
+        assert_eq!(
+            fill_template("https://example.com/{query}?q={query}", "{query}", "a b/c"),
+            "https://example.com/a%20b%2Fc?q=a+b%2Fc"
+        );
+    }
+
+    #[test]
+    fn templates_without_placeholder_are_unchanged() {
+        assert_eq!(fill_template("https://example.com/", "{query}", "x"), "https://example.com/");
+    }
+}
@@ -1,11 +1,15 @@
 use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug)]
 pub struct SearchResult {
     pub title: String,
     pub subtitle: String,
     pub icon: String,
+    // Theme icon names or absolute paths to try in order, `icon` is the text fallback
+    pub icon_names: Vec<String>,
     pub action: ActionType,
+    pub score: f32,
 }
 
 #[derive(Clone, Debug)]
@@ -13,10 +17,30 @@ pub enum ActionType {
     OpenSettings,
     OpenHistory,
     OpenInfo,
-    OpenApp(PathBuf),
+    OpenApp(LaunchSpec),
+    OpenAppAction(LaunchSpec),
     OpenPath(PathBuf),
     OpenUrl(String),
     MathResult(String),
-    WebSearch(String),
+    WebSearch(WebQuery),
     RunCommand(String),
-}
\ No newline at end of file
+    // Id of a clipboard history entry to copy again
+    CopyClip(String),
+}
+
+/// Everything needed to start an application, taken from its desktop entry
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaunchSpec {
+    pub argv: Vec<String>,
+    pub working_dir: Option<PathBuf>,
+    pub env: Vec<(String, String)>,
+    pub terminal: bool,
+}
+
+/// A query for one of the configured search engines
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebQuery {
+    // Keyword of the engine, empty for the default engine
+    pub engine: String,
+    pub query: String,
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use std::process::Command;
 
+use crate::utils::settings_manager::{WindowAnchor, WindowSettings};
 use crate::utils::wm_integrations::{awesome, bspwm, dwm, generic_x11, herbstluftwm, hyprland, i3, leftwm, qtile, river, sway, xmonad};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -25,9 +26,33 @@ pub trait WindowManagerBackend {
     fn pin_to_all_workspaces(&self, window_id: &str) -> Result<()>;
     fn focus_window(&self, window_id: &str) -> Result<()>;
     fn move_to_position(&self, window_id: &str, x: i32, y: i32) -> Result<()>;
-    fn get_screen_dimensions(&self) -> Option<(i32, i32)>;
+    fn monitors(&self) -> Vec<Monitor>;
+    fn cursor_position(&self) -> Option<(i32, i32)>;
 }
 
+/// One output, in the coordinates the WM moves windows in
+#[derive(Clone, Debug, PartialEq)]
+pub struct Monitor {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    // Physical pixels per WM coordinate. 1.0 where the WM works in physical pixels, like on X11
+    pub scale: f32,
+    // Only set by WMs that track which output has focus
+    pub focused: bool,
+}
+
+impl Monitor {
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+// Gap between the top of the screen and a top-center window
+const TOP_MARGIN: i32 = 20;
+
 pub struct WindowManagerIntegration {
     _wm: WindowManager,
     backend: Box<dyn WindowManagerBackend>,
@@ -157,18 +182,18 @@ impl WindowManagerIntegration {
         Ok(())
     }
 
-    pub fn move_window_to_top_center(&self, width: i32) -> Result<()> {
+    /// Moves the window where `window.anchor` puts a window of `size` physical pixels
+    pub fn move_window(&self, window: &WindowSettings, size: (i32, i32)) -> Result<()> {
         if let Some(ref window_id) = self.window_id {
-            if let Some((screen_width, _)) = self.backend.get_screen_dimensions() {
-                let x_pos = (screen_width - width) / 2;
-                let y_pos = 20;
-                self.backend.move_to_position(window_id, x_pos, y_pos)?;
+            let monitors = self.backend.monitors();
+            if let Some((x, y)) = anchored_position(&monitors, self.backend.cursor_position(), window, size) {
+                self.backend.move_to_position(window_id, x, y)?;
             }
         }
         Ok(())
     }
 
-    pub fn setup_launcher_window(&mut self, title: &str, width: i32) -> Result<()> {
+    pub fn setup_launcher_window(&mut self, title: &str, window: &WindowSettings, size: (i32, i32)) -> Result<()> {
         std::thread::sleep(std::time::Duration::from_millis(150));
         
         self.find_window_by_title(title)?;
@@ -176,10 +201,141 @@ impl WindowManagerIntegration {
         if self.window_id.is_some() {
             let _ = self.make_float();
             let _ = self.pin_to_all_workspaces();
-            let _ = self.move_window_to_top_center(width);
+            let _ = self.move_window(window, size);
             let _ = self.focus_window();
         }
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    // Used by the daemon each time the hidden window is shown again. Some WMs treat a remapped
+    // window as a new one, so float and pin are only redone when the window id changed, since
+    // e.g. Hyprland's float is a toggle.
+    pub fn show_launcher_window(&mut self, title: &str, window: &WindowSettings, size: (i32, i32)) -> Result<()> {
+        let previous = self.window_id.take();
+        self.find_window_by_title(title)?;
+
+        if self.window_id.is_some() {
+            if self.window_id != previous {
+                let _ = self.make_float();
+                let _ = self.pin_to_all_workspaces();
+            }
+            let _ = self.move_window(window, size);
+            let _ = self.focus_window();
+        }
+
+        Ok(())
+    }
+}
+
+// Where `window.anchor` puts a window of `size` physical pixels, in the WM's coordinates
+fn anchored_position(monitors: &[Monitor], cursor: Option<(i32, i32)>, window: &WindowSettings, size: (i32, i32)) -> Option<(i32, i32)> {
+    let monitor = target_monitor(monitors, cursor, window.anchor)?;
+
+    // The window's size in the monitor's coordinates
+    let width = (size.0 as f32 / monitor.scale).round() as i32;
+    let height = (size.1 as f32 / monitor.scale).round() as i32;
+    let centered_x = monitor.x + (monitor.width - width) / 2;
+
+    let (x, y) = match (window.anchor, cursor) {
+        (WindowAnchor::TopCenter, _) => (centered_x, monitor.y + TOP_MARGIN),
+        (WindowAnchor::Center, _) => (centered_x, monitor.y + (monitor.height - height) / 2),
+        (WindowAnchor::UnderCursor, Some((cursor_x, cursor_y))) => (cursor_x - width / 2, cursor_y),
+        // Without a cursor position from the WM, fall back to the default spot
+        (WindowAnchor::UnderCursor, None) => (centered_x, monitor.y + TOP_MARGIN),
+        (WindowAnchor::Custom, _) => (monitor.x, monitor.y),
+    };
+
+    // Keep it on the monitor, e.g. when the cursor is close to an edge
+    let [offset_x, offset_y] = window.offset;
+    let x = (x + offset_x).clamp(monitor.x, monitor.x + (monitor.width - width).max(0));
+    let y = (y + offset_y).clamp(monitor.y, monitor.y + (monitor.height - height).max(0));
+    Some((x, y))
+}
+
+// The bar opens on the monitor with focus, or else the one with the cursor. Under the cursor always
+// means the cursor's monitor.
+fn target_monitor(monitors: &[Monitor], cursor: Option<(i32, i32)>, anchor: WindowAnchor) -> Option<&Monitor> {
+    let under_cursor = cursor.and_then(|cursor| monitors.iter().find(|monitor| monitor.contains(cursor)));
+    let focused = monitors.iter().find(|monitor| monitor.focused);
+
+    let preferred = if anchor == WindowAnchor::UnderCursor {
+        under_cursor.or(focused)
+    } else {
+        focused.or(under_cursor)
+    };
+    preferred.or(monitors.first())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, width: i32, height: i32, focused: bool) -> Monitor {
+        Monitor { name: name.to_string(), x, y: 0, width, height, scale: 1.0, focused }
+    }
+
+    // A 1920x1080 monitor left of the primary one, which has focus
+    fn two_monitors() -> Vec<Monitor> {
+        vec![monitor("DP-1", -1920, 1920, 1080, false), monitor("eDP-1", 0, 2560, 1440, true)]
+    }
+
+    fn anchored(anchor: WindowAnchor) -> WindowSettings {
+        WindowSettings { anchor, ..Default::default() }
+    }
+
+    #[test]
+    fn picks_the_focused_monitor_unless_opening_under_the_cursor() {
+        let monitors = two_monitors();
+        let on_left = Some((-100, 500));
+
+        assert_eq!(target_monitor(&monitors, on_left, WindowAnchor::TopCenter).unwrap().name, "eDP-1");
+        assert_eq!(target_monitor(&monitors, on_left, WindowAnchor::UnderCursor).unwrap().name, "DP-1");
+        assert_eq!(target_monitor(&monitors, None, WindowAnchor::UnderCursor).unwrap().name, "eDP-1");
+    }
+
+    #[test]
+    fn without_focus_the_cursor_decides() {
+        let monitors = [monitor("DP-1", -1920, 1920, 1080, false), monitor("eDP-1", 0, 2560, 1440, false)];
+
+        assert_eq!(target_monitor(&monitors, Some((-100, 500)), WindowAnchor::Center).unwrap().name, "DP-1");
+        assert_eq!(target_monitor(&monitors, Some((100, 500)), WindowAnchor::Center).unwrap().name, "eDP-1");
+        assert_eq!(target_monitor(&monitors, None, WindowAnchor::Center).unwrap().name, "DP-1");
+        assert!(target_monitor(&[], Some((0, 0)), WindowAnchor::Center).is_none());
+    }
+
+    #[test]
+    fn places_on_a_monitor_left_of_the_origin() {
+        let monitors = [monitor("DP-1", -1920, 1920, 1080, false)];
+
+        let top = anchored_position(&monitors, None, &anchored(WindowAnchor::TopCenter), (600, 50));
+        assert_eq!(top, Some((-1260, TOP_MARGIN)));
+
+        let custom = WindowSettings { anchor: WindowAnchor::Custom, offset: [40, 30], ..Default::default() };
+        assert_eq!(anchored_position(&monitors, None, &custom, (600, 50)), Some((-1880, 30)));
+    }
+
+    #[test]
+    fn stays_on_the_monitor_near_its_edges() {
+        let monitors = two_monitors();
+        let under_cursor = anchored(WindowAnchor::UnderCursor);
+
+        // Bottom right corner of the primary monitor
+        assert_eq!(anchored_position(&monitors, Some((2550, 1430)), &under_cursor, (600, 300)), Some((1960, 1140)));
+        // Left edge of the left monitor
+        assert_eq!(anchored_position(&monitors, Some((-1910, 10)), &under_cursor, (600, 300)), Some((-1920, 10)));
+
+        // An offset can't push it off either
+        let shifted = WindowSettings { anchor: WindowAnchor::TopCenter, offset: [0, -100], ..Default::default() };
+        assert_eq!(anchored_position(&monitors, None, &shifted, (600, 50)), Some((980, 0)));
+    }
+
+    #[test]
+    fn scaled_monitors_use_their_own_coordinates() {
+        // 2560x1440 pixels at scale 2
+        let monitors = [Monitor { scale: 2.0, ..monitor("eDP-1", 0, 1280, 720, true) }];
+
+        assert_eq!(anchored_position(&monitors, None, &anchored(WindowAnchor::TopCenter), (800, 100)), Some((440, TOP_MARGIN)));
+        assert_eq!(anchored_position(&monitors, None, &anchored(WindowAnchor::Center), (800, 100)), Some((440, 335)));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::utils::{window_manger::WindowManagerBackend};
+use crate::utils::{window_manger::{Monitor, WindowManagerBackend}, wm_integrations::generic_x11::x11_cursor_position};
 use anyhow::Result;
 use std::process::Command;
 
@@ -62,24 +62,37 @@ impl WindowManagerBackend for BspwmBackend {
         Ok(())
     }
 
-    fn get_screen_dimensions(&self) -> Option<(i32, i32)> {
-        let output = Command::new("xdpyinfo")
-            .output()
-            .ok()?;
-        
-        let screen_info = String::from_utf8_lossy(&output.stdout);
-        for line in screen_info.lines() {
-            if line.contains("dimensions:") {
-                if let Some(dims) = line.split_whitespace().nth(1) {
-                    let parts: Vec<&str> = dims.split('x').collect();
-                    if parts.len() == 2 {
-                        let width = parts[0].parse::<i32>().ok()?;
-                        let height = parts[1].parse::<i32>().ok()?;
-                        return Some((width, height));
-                    }
-                }
-            }
-        }
-        None
+    fn monitors(&self) -> Vec<Monitor> {
+        let output = Command::new("bspc")
+            .args(["wm", "-d"])
+            .output();
+
+        let state: serde_json::Value = match output {
+            Ok(output) if output.status.success() => serde_json::from_slice(&output.stdout).unwrap_or_default(),
+            _ => return Vec::new(),
+        };
+        let focused_id = state["focusedMonitorId"].as_i64();
+
+        state["monitors"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|monitor| {
+                let rect = &monitor["rectangle"];
+                Some(Monitor {
+                    name: monitor["name"].as_str().unwrap_or_default().to_string(),
+                    x: rect["x"].as_i64()? as i32,
+                    y: rect["y"].as_i64()? as i32,
+                    width: rect["width"].as_i64()? as i32,
+                    height: rect["height"].as_i64()? as i32,
+                    scale: 1.0,
+                    focused: focused_id.is_some() && monitor["id"].as_i64() == focused_id,
+                })
+            })
+            .collect()
     }
-}
\ No newline at end of file
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        x11_cursor_position()
+    }
+}
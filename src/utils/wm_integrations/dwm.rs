@@ -1,4 +1,4 @@
-use crate::utils::{window_manger::WindowManagerBackend, wm_integrations::generic_x11::GenericX11Backend};
+use crate::utils::{window_manger::{Monitor, WindowManagerBackend}, wm_integrations::generic_x11::GenericX11Backend};
 use anyhow::Result;
 
 // Not tested
@@ -35,7 +35,11 @@ impl WindowManagerBackend for DwmBackend {
         self.x11.move_to_position(window_id, x, y)
     }
 
-    fn get_screen_dimensions(&self) -> Option<(i32, i32)> {
-        self.x11.get_screen_dimensions()
+    fn monitors(&self) -> Vec<Monitor> {
+        self.x11.monitors()
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        self.x11.cursor_position()
     }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::utils::{window_manger::WindowManagerBackend};
+use crate::utils::window_manger::{Monitor, WindowManagerBackend};
 use anyhow::Result;
 use std::process::Command;
 
@@ -55,24 +55,121 @@ impl WindowManagerBackend for GenericX11Backend {
         Ok(())
     }
 
-    fn get_screen_dimensions(&self) -> Option<(i32, i32)> {
-        let output = Command::new("xdpyinfo")
-            .output()
-            .ok()?;
-        
-        let screen_info = String::from_utf8_lossy(&output.stdout);
-        for line in screen_info.lines() {
-            if line.contains("dimensions:") {
-                if let Some(dims) = line.split_whitespace().nth(1) {
-                    let parts: Vec<&str> = dims.split('x').collect();
-                    if parts.len() == 2 {
-                        let width = parts[0].parse::<i32>().ok()?;
-                        let height = parts[1].parse::<i32>().ok()?;
-                        return Some((width, height));
-                    }
-                }
-            }
-        }
-        None
+    fn monitors(&self) -> Vec<Monitor> {
+        x11_monitors()
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        x11_cursor_position()
+    }
+}
+
+/// Monitors as xrandr sees them, shared by the X11 window managers. X11 has no idea of a focused
+/// monitor, so the cursor decides.
+pub fn x11_monitors() -> Vec<Monitor> {
+    let output = Command::new("xrandr")
+        .arg("--listactivemonitors")
+        .output();
+
+    let monitors: Vec<Monitor> = match output {
+        // e.g. " 0: +*eDP-1 1920/344x1080/194+0+0  eDP-1" below a "Monitors: 2" line
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .skip(1)
+            .filter_map(parse_xrandr_monitor)
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    if monitors.is_empty() {
+        // Without xrandr the whole X screen counts as one monitor
+        return x11_screen().into_iter().collect();
+    }
+    monitors
+}
+
+fn parse_xrandr_monitor(line: &str) -> Option<Monitor> {
+    let mut fields = line.split_whitespace().skip(2);
+    let geometry = fields.next()?;
+    let name = fields.next()?.to_string();
+
+    let mut parts = geometry.split('+');
+    let (width, height) = parts.next()?.split_once('x')?;
+    // Sizes come with the physical size in millimeters, "1920/344"
+    let size = |part: &str| part.split('/').next()?.parse::<i32>().ok();
+
+    Some(Monitor {
+        name,
+        x: parts.next()?.parse().ok()?,
+        y: parts.next()?.parse().ok()?,
+        width: size(width)?,
+        height: size(height)?,
+        scale: 1.0,
+        focused: false,
+    })
+}
+
+fn x11_screen() -> Option<Monitor> {
+    let output = Command::new("xdpyinfo")
+        .output()
+        .ok()?;
+
+    let screen_info = String::from_utf8_lossy(&output.stdout);
+    let dimensions = screen_info
+        .lines()
+        .find(|line| line.contains("dimensions:"))?
+        .split_whitespace()
+        .nth(1)?;
+    let (width, height) = dimensions.split_once('x')?;
+
+    Some(Monitor {
+        name: "screen".to_string(),
+        x: 0,
+        y: 0,
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+        scale: 1.0,
+        focused: false,
+    })
+}
+
+/// Pointer position on the X screen, shared by the X11 window managers
+pub fn x11_cursor_position() -> Option<(i32, i32)> {
+    let output = Command::new("xdotool")
+        .args(["getmouselocation", "--shell"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    // One KEY=value line each for X, Y, SCREEN and WINDOW
+    let location = String::from_utf8_lossy(&output.stdout);
+    let value = |key: &str| {
+        location
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .and_then(|value| value.trim().parse::<i32>().ok())
+    };
+    Some((value("X")?, value("Y")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_xrandr_monitors() {
+        assert_eq!(
+            parse_xrandr_monitor(" 0: +*eDP-1 1920/344x1080/194+0+0  eDP-1"),
+            Some(Monitor { name: "eDP-1".to_string(), x: 0, y: 0, width: 1920, height: 1080, scale: 1.0, focused: false })
+        );
+
+        let right = parse_xrandr_monitor(" 1: +HDMI-1 2560/597x1440/336+1920+0  HDMI-1").unwrap();
+        assert_eq!((right.name.as_str(), right.x, right.y, right.width, right.height), ("HDMI-1", 1920, 0, 2560, 1440));
+
+        assert!(parse_xrandr_monitor("Monitors: 2").is_none());
+        assert!(parse_xrandr_monitor(" 0: +*eDP-1 1920/344x1080/194  eDP-1").is_none());
     }
 }
@@ -1,4 +1,4 @@
-use crate::utils::{window_manger::WindowManagerBackend};
+use crate::utils::{window_manger::{Monitor, WindowManagerBackend}, wm_integrations::generic_x11::x11_cursor_position};
 use anyhow::Result;
 use std::process::Command;
 
@@ -65,21 +65,36 @@ impl WindowManagerBackend for HerbstluftwmBackend {
         Ok(())
     }
 
-    fn get_screen_dimensions(&self) -> Option<(i32, i32)> {
-        let output = Command::new("herbstclient")
-            .args(["monitor_rect", ""])
-            .output()
-            .ok()?;
-        
-        let rect = String::from_utf8_lossy(&output.stdout);
-        let parts: Vec<&str> = rect.split_whitespace().collect();
-        
-        if parts.len() >= 4 {
-            let width = parts[2].parse::<i32>().ok()?;
-            let height = parts[3].parse::<i32>().ok()?;
-            return Some((width, height));
-        }
-        
-        None
+    fn monitors(&self) -> Vec<Monitor> {
+        let output = match Command::new("herbstclient").arg("list_monitors").output() {
+            Ok(output) if output.status.success() => output,
+            _ => return Vec::new(),
+        };
+
+        // e.g. "0: 1920x1080+0+0 with tag 1 [FOCUS]"
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (index, rest) = line.split_once(':')?;
+                let geometry = rest.split_whitespace().next()?;
+                let (size, position) = geometry.split_once('+')?;
+                let (width, height) = size.split_once('x')?;
+                let (x, y) = position.split_once('+')?;
+
+                Some(Monitor {
+                    name: index.trim().to_string(),
+                    x: x.parse().ok()?,
+                    y: y.parse().ok()?,
+                    width: width.parse().ok()?,
+                    height: height.parse().ok()?,
+                    scale: 1.0,
+                    focused: line.contains("[FOCUS]"),
+                })
+            })
+            .collect()
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        x11_cursor_position()
     }
 }
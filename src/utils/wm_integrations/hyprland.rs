@@ -1,4 +1,4 @@
-use crate::utils::{window_manger::WindowManagerBackend};
+use crate::utils::window_manger::{Monitor, WindowManagerBackend};
 use anyhow::Result;
 use std::process::Command;
 
@@ -61,23 +61,54 @@ impl WindowManagerBackend for HyprlandBackend {
         Ok(())
     }
 
-    fn get_screen_dimensions(&self) -> Option<(i32, i32)> {
+    fn monitors(&self) -> Vec<Monitor> {
         let output = Command::new("hyprctl")
             .args(["monitors", "-j"])
+            .output();
+
+        let monitors: serde_json::Value = match output {
+            Ok(output) if output.status.success() => serde_json::from_slice(&output.stdout).unwrap_or_default(),
+            _ => return Vec::new(),
+        };
+
+        monitors
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|monitor| {
+                // Windows are placed in layout coordinates, the size Hyprland reports is in pixels
+                let scale = monitor["scale"].as_f64().unwrap_or(1.0) as f32;
+                let mut width = (monitor["width"].as_i64()? as f32 / scale).round() as i32;
+                let mut height = (monitor["height"].as_i64()? as f32 / scale).round() as i32;
+                // Transforms 1, 3, 5 and 7 rotate by 90 or 270 degrees
+                if monitor["transform"].as_i64().unwrap_or(0) % 2 == 1 {
+                    std::mem::swap(&mut width, &mut height);
+                }
+
+                Some(Monitor {
+                    name: monitor["name"].as_str().unwrap_or_default().to_string(),
+                    x: monitor["x"].as_i64()? as i32,
+                    y: monitor["y"].as_i64()? as i32,
+                    width,
+                    height,
+                    scale,
+                    focused: monitor["focused"].as_bool().unwrap_or(false),
+                })
+            })
+            .collect()
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        let output = Command::new("hyprctl")
+            .args(["cursorpos", "-j"])
             .output()
             .ok()?;
-        
+
         if !output.status.success() {
             return None;
         }
 
-        let monitors: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
-        if let Some(monitor) = monitors.as_array()?.first() {
-            let width = monitor["width"].as_i64()? as i32;
-            let height = monitor["height"].as_i64()? as i32;
-            return Some((width, height));
-        }
-        
-        None
+        let position: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+        Some((position["x"].as_i64()? as i32, position["y"].as_i64()? as i32))
     }
 }
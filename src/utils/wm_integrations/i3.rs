@@ -1,4 +1,4 @@
-use crate::utils::{window_manger::WindowManagerBackend};
+use crate::utils::{window_manger::{Monitor, WindowManagerBackend}, wm_integrations::generic_x11::x11_cursor_position};
 use anyhow::Result;
 use std::process::Command;
 
@@ -82,24 +82,51 @@ impl WindowManagerBackend for I3Backend {
         Ok(())
     }
 
-    fn get_screen_dimensions(&self) -> Option<(i32, i32)> {
-        let output = Command::new("xdpyinfo")
-            .output()
-            .ok()?;
-        
-        let screen_info = String::from_utf8_lossy(&output.stdout);
-        for line in screen_info.lines() {
-            if line.contains("dimensions:") {
-                if let Some(dims) = line.split_whitespace().nth(1) {
-                    let parts: Vec<&str> = dims.split('x').collect();
-                    if parts.len() == 2 {
-                        let width = parts[0].parse::<i32>().ok()?;
-                        let height = parts[1].parse::<i32>().ok()?;
-                        return Some((width, height));
-                    }
-                }
+    fn monitors(&self) -> Vec<Monitor> {
+        let query = |kind: &str| -> Option<serde_json::Value> {
+            let output = Command::new("i3-msg").args(["-t", kind]).output().ok()?;
+            if !output.status.success() {
+                return None;
             }
-        }
-        None
+            serde_json::from_slice(&output.stdout).ok()
+        };
+
+        // Outputs don't say which one has focus, the focused workspace does
+        let focused_output = query("get_workspaces")
+            .and_then(|workspaces| {
+                workspaces
+                    .as_array()?
+                    .iter()
+                    .find(|workspace| workspace["focused"].as_bool().unwrap_or(false))
+                    .and_then(|workspace| workspace["output"].as_str().map(str::to_string))
+            });
+
+        let Some(outputs) = query("get_outputs") else {
+            return Vec::new();
+        };
+
+        outputs
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|output| output["active"].as_bool().unwrap_or(false))
+            .filter_map(|output| {
+                let name = output["name"].as_str()?.to_string();
+                let rect = &output["rect"];
+                Some(Monitor {
+                    focused: focused_output.as_deref() == Some(name.as_str()),
+                    name,
+                    x: rect["x"].as_i64()? as i32,
+                    y: rect["y"].as_i64()? as i32,
+                    width: rect["width"].as_i64()? as i32,
+                    height: rect["height"].as_i64()? as i32,
+                    scale: 1.0,
+                })
+            })
+            .collect()
     }
-}
\ No newline at end of file
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        x11_cursor_position()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::utils::window_manger::WindowManagerBackend;
+use crate::utils::window_manger::{Monitor, WindowManagerBackend};
 use anyhow::Result;
 use std::process::Command;
 
@@ -50,9 +50,14 @@ impl WindowManagerBackend for RiverBackend {
         Ok(())
     }
 
-    fn get_screen_dimensions(&self) -> Option<(i32, i32)> {
-        // No reliable way to get screen dimensions in River yet
-        // Return a default size
-        Some((1920, 1080))
+    fn monitors(&self) -> Vec<Monitor> {
+        // Placement needs move_to_position, so until River can move windows the anchor, offset
+        // and monitor choice don't apply there and the window goes wherever River puts it
+        Vec::new()
     }
-}
\ No newline at end of file
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        // River can't position windows anyway
+        None
+    }
+}
@@ -1,4 +1,4 @@
-use crate::utils::window_manger::WindowManagerBackend;
+use crate::utils::window_manger::{Monitor, WindowManagerBackend};
 use anyhow::Result;
 use std::process::Command;
 
@@ -82,24 +82,40 @@ impl WindowManagerBackend for SwayBackend {
         Ok(())
     }
 
-    fn get_screen_dimensions(&self) -> Option<(i32, i32)> {
+    fn monitors(&self) -> Vec<Monitor> {
         let output = Command::new("swaymsg")
             .args(["-t", "get_outputs"])
-            .output()
-            .ok()?;
-        
-        if !output.status.success() {
-            return None;
-        }
+            .output();
 
-        let outputs: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
-        if let Some(output) = outputs.as_array()?.iter().find(|o| o["focused"].as_bool().unwrap_or(false)) {
-            let rect = &output["rect"];
-            let width = rect["width"].as_i64()? as i32;
-            let height = rect["height"].as_i64()? as i32;
-            return Some((width, height));
-        }
-        
+        let outputs: serde_json::Value = match output {
+            Ok(output) if output.status.success() => serde_json::from_slice(&output.stdout).unwrap_or_default(),
+            _ => return Vec::new(),
+        };
+
+        outputs
+            .as_array()
+            .into_iter()
+            .flatten()
+            // Disabled outputs are listed too
+            .filter(|output| output["active"].as_bool().unwrap_or(true))
+            .filter_map(|output| {
+                // The rect is already in layout coordinates
+                let rect = &output["rect"];
+                Some(Monitor {
+                    name: output["name"].as_str().unwrap_or_default().to_string(),
+                    x: rect["x"].as_i64()? as i32,
+                    y: rect["y"].as_i64()? as i32,
+                    width: rect["width"].as_i64()? as i32,
+                    height: rect["height"].as_i64()? as i32,
+                    scale: output["scale"].as_f64().unwrap_or(1.0) as f32,
+                    focused: output["focused"].as_bool().unwrap_or(false),
+                })
+            })
+            .collect()
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        // Sway's IPC doesn't tell where the pointer is
         None
     }
-}
\ No newline at end of file
+}
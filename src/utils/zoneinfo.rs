@@ -0,0 +1,673 @@
+// Time zones from the system zoneinfo database, read from TZif files: https://www.rfc-editor.org/rfc/rfc8536
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime};
+use walkdir::WalkDir;
+
+const ZONEINFO_DIRS: &[&str] = &["/usr/share/zoneinfo", "/usr/lib/zoneinfo", "/usr/share/lib/zoneinfo"];
+
+// Abbreviations people use for a zone, mapped to a zone that observes it
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("UTC", "UTC"),
+    ("GMT", "UTC"),
+    ("Z", "UTC"),
+    ("PST", "America/Los_Angeles"),
+    ("PDT", "America/Los_Angeles"),
+    ("PT", "America/Los_Angeles"),
+    ("MST", "America/Denver"),
+    ("MDT", "America/Denver"),
+    ("MT", "America/Denver"),
+    ("CST", "America/Chicago"),
+    ("CDT", "America/Chicago"),
+    ("CT", "America/Chicago"),
+    ("EST", "America/New_York"),
+    ("EDT", "America/New_York"),
+    ("ET", "America/New_York"),
+    ("AKST", "America/Anchorage"),
+    ("HST", "Pacific/Honolulu"),
+    ("WET", "Europe/Lisbon"),
+    ("WEST", "Europe/Lisbon"),
+    ("BST", "Europe/London"),
+    ("CET", "Europe/Berlin"),
+    ("CEST", "Europe/Berlin"),
+    ("EET", "Europe/Athens"),
+    ("EEST", "Europe/Athens"),
+    ("MSK", "Europe/Moscow"),
+    ("IST", "Asia/Kolkata"),
+    ("SGT", "Asia/Singapore"),
+    ("HKT", "Asia/Hong_Kong"),
+    ("KST", "Asia/Seoul"),
+    ("JST", "Asia/Tokyo"),
+    ("AWST", "Australia/Perth"),
+    ("ACST", "Australia/Adelaide"),
+    ("AEST", "Australia/Sydney"),
+    ("AEDT", "Australia/Sydney"),
+    ("NZST", "Pacific/Auckland"),
+    ("NZDT", "Pacific/Auckland"),
+];
+
+/// The offset from UTC in effect at some moment, and what it is called
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalTimeType {
+    // Seconds east of UTC
+    pub offset: i32,
+    pub is_dst: bool,
+    pub abbreviation: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimeZone {
+    pub name: String,
+    // Unix times at which the offset changes, ascending
+    transitions: Vec<i64>,
+    // The index into `types` that starts at each transition
+    transition_types: Vec<usize>,
+    types: Vec<LocalTimeType>,
+    // Covers the times after the last transition
+    rule: Option<PosixRule>,
+}
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        Self::fixed("UTC", 0)
+    }
+
+    /// A zone that is always `offset` seconds east of UTC
+    pub fn fixed(name: &str, offset: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            types: vec![LocalTimeType { offset, is_dst: false, abbreviation: name.to_string() }],
+            rule: None,
+        }
+    }
+
+    /// Loads an IANA zone like "Europe/Paris" from the zoneinfo database
+    pub fn load(name: &str) -> Option<Self> {
+        if name.split('/').any(|part| part.is_empty() || part == "..") {
+            return None;
+        }
+        let path = zoneinfo_dir()?.join(name);
+        Self::from_file(name, &path)
+    }
+
+    fn from_file(name: &str, path: &Path) -> Option<Self> {
+        let data = fs::read(path).ok()?;
+        let mut zone = parse_tzif(&data)?;
+        zone.name = name.to_string();
+        Some(zone)
+    }
+
+    /// The zone from $TZ, or else /etc/localtime, or else UTC
+    pub fn local() -> Self {
+        if let Ok(tz) = std::env::var("TZ") {
+            let tz = tz.trim_start_matches(':');
+            if let Some(zone) = Self::load(tz).or_else(|| Self::from_posix(tz)) {
+                return zone;
+            }
+        }
+
+        let localtime = Path::new("/etc/localtime");
+        let name = fs::read_link(localtime)
+            .ok()
+            .and_then(|target| {
+                let target = target.to_string_lossy().to_string();
+                target.split_once("zoneinfo/").map(|(_, name)| name.to_string())
+            })
+            .unwrap_or_else(|| "Local".to_string());
+        Self::from_file(&name, localtime).unwrap_or_else(Self::utc)
+    }
+
+    /// A zone from a POSIX TZ string like "CET-1CEST,M3.5.0,M10.5.0/3", as $TZ may hold
+    pub fn from_posix(rule: &str) -> Option<Self> {
+        let parsed = PosixRule::parse(rule)?;
+        Some(Self {
+            name: rule.to_string(),
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            types: vec![parsed.std.clone()],
+            rule: Some(parsed),
+        })
+    }
+
+    /// The local time type in effect at the Unix time `timestamp`
+    pub fn type_at(&self, timestamp: i64) -> LocalTimeType {
+        let index = self.transitions.partition_point(|&transition| transition <= timestamp);
+
+        if index == self.transitions.len() {
+            if let Some(rule) = &self.rule {
+                return rule.type_at(timestamp);
+            }
+        }
+        match index.checked_sub(1) {
+            Some(index) => self.types[self.transition_types[index]].clone(),
+            None => self.types[0].clone(),
+        }
+    }
+
+    /// The wall clock time at `timestamp`
+    pub fn local_time(&self, timestamp: i64) -> Option<(NaiveDateTime, LocalTimeType)> {
+        let kind = self.type_at(timestamp);
+        let local = DateTime::from_timestamp(timestamp.checked_add(kind.offset as i64)?, 0)?.naive_utc();
+        Some((local, kind))
+    }
+
+    /// The Unix time at which the wall clock reads `local`. Times skipped by a DST change
+    /// are taken with the offset from before the change.
+    pub fn timestamp(&self, local: NaiveDateTime) -> i64 {
+        let wall = local.and_utc().timestamp();
+        let guess = wall - self.type_at(wall).offset as i64;
+        wall - self.type_at(guess).offset as i64
+    }
+}
+
+fn zoneinfo_dir() -> Option<PathBuf> {
+    std::env::var_os("TZDIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(ZONEINFO_DIRS.iter().map(PathBuf::from))
+        .find(|dir| dir.is_dir())
+}
+
+/// Every zone name in the database, like "America/New_York"
+pub fn zone_names() -> &'static [String] {
+    static NAMES: OnceLock<Vec<String>> = OnceLock::new();
+
+    NAMES.get_or_init(|| {
+        let Some(dir) = zoneinfo_dir() else {
+            return Vec::new();
+        };
+
+        WalkDir::new(&dir)
+            .into_iter()
+            // "posix" and "right" duplicate the whole database
+            .filter_entry(|entry| entry.depth() != 1 || !matches!(entry.file_name().to_str(), Some("posix" | "right")))
+            .flatten()
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                let name = entry.path().strip_prefix(&dir).ok()?.to_str()?.to_string();
+                // Skips zone.tab, leapseconds, posixrules and the like
+                let zone_like = name.starts_with(|c: char| c.is_ascii_uppercase()) && !name.contains('.');
+                zone_like.then_some(name)
+            })
+            .collect()
+    })
+}
+
+/// Finds a zone by IANA name ("Asia/Tokyo"), city ("tokyo", "new york"), abbreviation ("PST")
+/// or offset ("UTC+2", "+05:30")
+pub fn find_zone(query: &str) -> Option<TimeZone> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+
+    if let Some(offset) = parse_offset(query) {
+        return Some(TimeZone::fixed(query, offset));
+    }
+
+    let upper = query.to_uppercase();
+    if let Some((_, name)) = ABBREVIATIONS.iter().find(|(abbreviation, _)| *abbreviation == upper) {
+        return TimeZone::load(name).or_else(|| (*name == "UTC").then(TimeZone::utc));
+    }
+
+    let wanted = query.to_lowercase().replace(' ', "_");
+    let names = zone_names();
+    let name = names
+        .iter()
+        .find(|name| name.to_lowercase() == wanted)
+        .or_else(|| {
+            // Cities, preferring "Region/City" over old aliases like "Japan"
+            names
+                .iter()
+                .filter(|name| name.rsplit('/').next().is_some_and(|city| city.to_lowercase() == wanted))
+                .max_by_key(|name| name.contains('/'))
+        })?;
+    TimeZone::load(name)
+}
+
+// "UTC+2", "GMT-03:30", "+0530"
+fn parse_offset(text: &str) -> Option<i32> {
+    let upper = text.to_uppercase();
+    let rest = upper.strip_prefix("UTC").or_else(|| upper.strip_prefix("GMT")).unwrap_or(&upper);
+    let (sign, digits) = match rest.chars().next()? {
+        '+' => (1, &rest[1..]),
+        '-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) => (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?),
+        None if digits.len() == 4 && digits.bytes().all(|b| b.is_ascii_digit()) => (digits[..2].parse().ok()?, digits[2..].parse().ok()?),
+        None => (digits.parse().ok()?, 0),
+    };
+    if hours > 14 || minutes >= 60 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(length)?)?;
+        self.pos += length;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        Some(i32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<usize> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?) as usize)
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        Some(i64::from_be_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
+
+struct Header {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl Header {
+    fn read(reader: &mut Reader) -> Option<Self> {
+        if reader.take(4)? != b"TZif" {
+            return None;
+        }
+        let version = reader.u8()?;
+        reader.take(15)?;
+
+        Some(Self {
+            version,
+            isutcnt: reader.u32()?,
+            isstdcnt: reader.u32()?,
+            leapcnt: reader.u32()?,
+            timecnt: reader.u32()?,
+            typecnt: reader.u32()?,
+            charcnt: reader.u32()?,
+        })
+    }
+
+    // Size of the data block that follows, with 4 or 8 byte times
+    fn data_length(&self, time_size: usize) -> usize {
+        self.timecnt * time_size
+            + self.timecnt
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * (time_size + 4)
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+fn parse_tzif(data: &[u8]) -> Option<TimeZone> {
+    let mut reader = Reader { data, pos: 0 };
+    let mut header = Header::read(&mut reader)?;
+
+    // Version 2+ files repeat everything with 64-bit times after the 32-bit block
+    let wide = header.version >= b'2';
+    if wide {
+        reader.take(header.data_length(4))?;
+        header = Header::read(&mut reader)?;
+    }
+
+    let transitions = (0..header.timecnt)
+        .map(|_| if wide { reader.i64() } else { reader.i32().map(i64::from) })
+        .collect::<Option<Vec<i64>>>()?;
+    let transition_types = (0..header.timecnt).map(|_| reader.u8().map(usize::from)).collect::<Option<Vec<usize>>>()?;
+
+    let raw_types = (0..header.typecnt)
+        .map(|_| Some((reader.i32()?, reader.u8()? != 0, usize::from(reader.u8()?))))
+        .collect::<Option<Vec<_>>>()?;
+    let abbreviations = reader.take(header.charcnt)?;
+    reader.take(header.leapcnt * (if wide { 12 } else { 8 }) + header.isstdcnt + header.isutcnt)?;
+
+    let types: Vec<LocalTimeType> = raw_types
+        .into_iter()
+        .map(|(offset, is_dst, index)| {
+            let abbreviation = abbreviations.get(index..).unwrap_or_default();
+            let end = abbreviation.iter().position(|&b| b == 0).unwrap_or(abbreviation.len());
+            LocalTimeType { offset, is_dst, abbreviation: String::from_utf8_lossy(&abbreviation[..end]).to_string() }
+        })
+        .collect();
+    if types.is_empty() || transition_types.iter().any(|&index| index >= types.len()) {
+        return None;
+    }
+
+    // The footer is a POSIX TZ string between newlines
+    let rule = if wide {
+        let footer = data.get(reader.pos..).unwrap_or_default();
+        std::str::from_utf8(footer)
+            .ok()
+            .and_then(|footer| footer.trim_matches('\n').lines().next().map(str::to_string))
+            .and_then(|footer| PosixRule::parse(&footer))
+    } else {
+        None
+    };
+
+    Some(TimeZone { name: String::new(), transitions, transition_types, types, rule })
+}
+
+// A day of the year a DST change happens on
+#[derive(Debug, Clone, Copy)]
+enum RuleDay {
+    // Mm.w.d: weekday d (0 = Sunday) of week w (5 = last) of month m
+    MonthWeekDay(u32, u32, u32),
+    // Jn: day 1..=365, February 29th is never counted
+    Julian(u32),
+    // n: day 0..=365, counting February 29th
+    DayOfYear(u32),
+}
+
+impl RuleDay {
+    fn date(self, year: i32) -> Option<NaiveDate> {
+        match self {
+            Self::MonthWeekDay(month, week, weekday) => {
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let first_weekday = first.weekday().num_days_from_sunday();
+                let mut day = 1 + (weekday + 7 - first_weekday) % 7 + (week - 1) * 7;
+                let days_in_month = first.checked_add_months(chrono::Months::new(1))?.pred_opt()?.day();
+                while day > days_in_month {
+                    day -= 7;
+                }
+                NaiveDate::from_ymd_opt(year, month, day)
+            }
+            Self::Julian(day) => {
+                let leap_shift = u32::from(NaiveDate::from_ymd_opt(year, 2, 29).is_some() && day >= 60);
+                NaiveDate::from_yo_opt(year, day + leap_shift)
+            }
+            Self::DayOfYear(day) => NaiveDate::from_yo_opt(year, day + 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DstRule {
+    kind: LocalTimeType,
+    start: RuleDay,
+    // Seconds after local midnight, in standard time for the start and DST for the end
+    start_time: i64,
+    end: RuleDay,
+    end_time: i64,
+}
+
+// A POSIX TZ string like "CET-1CEST,M3.5.0,M10.5.0/3"
+#[derive(Debug, Clone)]
+struct PosixRule {
+    std: LocalTimeType,
+    dst: Option<DstRule>,
+}
+
+impl PosixRule {
+    fn parse(text: &str) -> Option<Self> {
+        let mut parser = PosixParser { chars: text.chars().collect(), pos: 0 };
+
+        let std_name = parser.name()?;
+        // POSIX offsets count west of UTC
+        let std_offset = -parser.duration()?;
+        let std = LocalTimeType { offset: std_offset as i32, is_dst: false, abbreviation: std_name };
+
+        if parser.done() {
+            return Some(Self { std, dst: None });
+        }
+
+        let dst_name = parser.name()?;
+        let dst_offset = if parser.peek().is_some_and(|c| c != ',') { -parser.duration()? } else { std_offset + 3600 };
+        let kind = LocalTimeType { offset: dst_offset as i32, is_dst: true, abbreviation: dst_name };
+
+        // Without rules POSIX falls back to the US ones
+        let (start, start_time, end, end_time) = if parser.done() {
+            (RuleDay::MonthWeekDay(3, 2, 0), 7200, RuleDay::MonthWeekDay(11, 1, 0), 7200)
+        } else {
+            let (start, start_time) = parser.change()?;
+            let (end, end_time) = parser.change()?;
+            (start, start_time, end, end_time)
+        };
+
+        parser.done().then_some(Self { std, dst: Some(DstRule { kind, start, start_time, end, end_time }) })
+    }
+
+    fn type_at(&self, timestamp: i64) -> LocalTimeType {
+        let Some(dst) = &self.dst else {
+            return self.std.clone();
+        };
+        let year = timestamp.checked_add(self.std.offset as i64).and_then(|local| DateTime::from_timestamp(local, 0));
+        let Some(year) = year.map(|time| time.year()) else {
+            return self.std.clone();
+        };
+
+        let change = |day: RuleDay, time: i64, offset: i32| {
+            day.date(year).and_then(|date| date.and_hms_opt(0, 0, 0)).map(|midnight| {
+                (midnight + Duration::seconds(time)).and_utc().timestamp() - offset as i64
+            })
+        };
+        let (Some(start), Some(end)) = (change(dst.start, dst.start_time, self.std.offset), change(dst.end, dst.end_time, dst.kind.offset)) else {
+            return self.std.clone();
+        };
+
+        // Southern hemisphere rules end before they start within a year
+        let in_dst = if start < end { start <= timestamp && timestamp < end } else { !(end <= timestamp && timestamp < start) };
+        if in_dst {
+            dst.kind.clone()
+        } else {
+            self.std.clone()
+        }
+    }
+}
+
+struct PosixParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl PosixParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn done(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    // "CET", or "<+0330>" for names that aren't just letters
+    fn name(&mut self) -> Option<String> {
+        let name: String = if self.eat('<') {
+            let start = self.pos;
+            while self.peek().is_some_and(|c| c != '>') {
+                self.pos += 1;
+            }
+            let name = self.chars[start..self.pos].iter().collect();
+            self.eat('>').then_some(name)?
+        } else {
+            let start = self.pos;
+            while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                self.pos += 1;
+            }
+            self.chars[start..self.pos].iter().collect()
+        };
+        (name.len() >= 3).then_some(name)
+    }
+
+    fn number(&mut self) -> Option<i64> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect::<String>().parse().ok()
+    }
+
+    // [+-]hh[:mm[:ss]] in seconds
+    fn duration(&mut self) -> Option<i64> {
+        let sign = if self.eat('-') {
+            -1
+        } else {
+            self.eat('+');
+            1
+        };
+
+        let mut seconds = self.number()?.checked_mul(3600)?;
+        if self.eat(':') {
+            seconds = seconds.checked_add(self.number()?.checked_mul(60)?)?;
+            if self.eat(':') {
+                seconds = seconds.checked_add(self.number()?)?;
+            }
+        }
+        Some(sign * seconds)
+    }
+
+    // ",Mm.w.d/time"
+    fn change(&mut self) -> Option<(RuleDay, i64)> {
+        if !self.eat(',') {
+            return None;
+        }
+
+        let day = if self.eat('M') {
+            let month = self.number()?;
+            self.eat('.').then_some(())?;
+            let week = self.number()?;
+            self.eat('.').then_some(())?;
+            let weekday = self.number()?;
+            if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+                return None;
+            }
+            RuleDay::MonthWeekDay(month as u32, week as u32, weekday as u32)
+        } else if self.eat('J') {
+            RuleDay::Julian(self.number().filter(|day| (1..=365).contains(day))? as u32)
+        } else {
+            RuleDay::DayOfYear(self.number().filter(|day| (0..=365).contains(day))? as u32)
+        };
+
+        let time = if self.eat('/') { self.duration()? } else { 7200 };
+        Some((day, time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc_timestamp(date: &str, time: &str) -> i64 {
+        NaiveDateTime::new(date.parse().unwrap(), time.parse().unwrap()).and_utc().timestamp()
+    }
+
+    fn offset_at(rule: &PosixRule, date: &str, time: &str) -> i32 {
+        rule.type_at(utc_timestamp(date, time)).offset
+    }
+
+    #[test]
+    fn northern_rule() {
+        let rule = PosixRule::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        // 2027 changes on March 28th and October 31st, both at 01:00 UTC
+        assert_eq!(offset_at(&rule, "2027-03-28", "00:59:59"), 3600);
+        assert_eq!(offset_at(&rule, "2027-03-28", "01:00:00"), 7200);
+        assert_eq!(offset_at(&rule, "2027-10-31", "00:59:59"), 7200);
+        assert_eq!(offset_at(&rule, "2027-10-31", "01:00:00"), 3600);
+        assert_eq!(rule.type_at(utc_timestamp("2027-07-01", "12:00:00")).abbreviation, "CEST");
+    }
+
+    #[test]
+    fn southern_rule() {
+        let rule = PosixRule::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(offset_at(&rule, "2027-01-15", "12:00:00"), 39600);
+        assert_eq!(offset_at(&rule, "2027-07-15", "12:00:00"), 36000);
+        // DST ends on April 4th at 03:00 AEDT and starts on October 3rd at 02:00 AEST
+        assert_eq!(offset_at(&rule, "2027-04-03", "15:59:59"), 39600);
+        assert_eq!(offset_at(&rule, "2027-04-03", "16:00:00"), 36000);
+        assert_eq!(offset_at(&rule, "2027-10-02", "15:59:59"), 36000);
+        assert_eq!(offset_at(&rule, "2027-10-02", "16:00:00"), 39600);
+    }
+
+    #[test]
+    fn rules_without_dates_or_dst() {
+        let tokyo = PosixRule::parse("JST-9").unwrap();
+        assert_eq!(offset_at(&tokyo, "2027-07-01", "00:00:00"), 32400);
+
+        // US rules when none are given: second Sunday of March to first Sunday of November
+        let new_york = PosixRule::parse("EST5EDT").unwrap();
+        assert_eq!(offset_at(&new_york, "2027-03-14", "06:59:59"), -18000);
+        assert_eq!(offset_at(&new_york, "2027-03-14", "07:00:00"), -14400);
+
+        let tehran = PosixRule::parse("<+0330>-3:30").unwrap();
+        assert_eq!(tehran.std.abbreviation, "+0330");
+        assert_eq!(offset_at(&tehran, "2027-01-01", "00:00:00"), 12600);
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert!(PosixRule::parse("CET-1CEST,M13.5.0,M10.5.0").is_none());
+        assert!(PosixRule::parse("CET-1CEST,M3.5.0").is_none());
+        assert!(PosixRule::parse("C-1").is_none());
+        assert!(PosixRule::parse("CET").is_none());
+        assert!(PosixRule::parse("CET-9999999999999999").is_none());
+    }
+
+    #[test]
+    fn local_times_skipped_by_dst_use_the_earlier_offset() {
+        let berlin = TimeZone::from_posix("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let skipped = NaiveDateTime::new("2027-03-28".parse().unwrap(), "02:30:00".parse().unwrap());
+        assert_eq!(berlin.timestamp(skipped), utc_timestamp("2027-03-28", "01:30:00"));
+        assert!(berlin.local_time(i64::MAX).is_none());
+    }
+
+    #[test]
+    fn finds_zones_by_offset() {
+        assert_eq!(find_zone("UTC+2").unwrap().type_at(0).offset, 7200);
+        assert_eq!(find_zone("+05:30").unwrap().type_at(0).offset, 19800);
+        assert_eq!(find_zone("GMT-03:30").unwrap().type_at(0).offset, -12600);
+        assert_eq!(find_zone("+0545").unwrap().type_at(0).offset, 20700);
+        assert!(find_zone("UTC+15").is_none());
+        assert!(find_zone("+a€").is_none());
+        assert!(find_zone("").is_none());
+    }
+
+    #[test]
+    fn finds_zones_by_name() {
+        // Needs the system zoneinfo database
+        if zoneinfo_dir().is_none() {
+            return;
+        }
+
+        assert_eq!(find_zone("Asia/Tokyo").unwrap().name, "Asia/Tokyo");
+        assert_eq!(find_zone("tokyo").unwrap().name, "Asia/Tokyo");
+        assert_eq!(find_zone("New York").unwrap().name, "America/New_York");
+        assert_eq!(find_zone("pst").unwrap().name, "America/Los_Angeles");
+        assert!(find_zone("Atlantis").is_none());
+        assert!(TimeZone::load("../../etc/passwd").is_none());
+
+        // Past the last transition the footer rule takes over
+        let berlin = find_zone("Europe/Berlin").unwrap();
+        assert_eq!(berlin.type_at(utc_timestamp("2044-07-01", "12:00:00")).offset, 7200);
+        assert_eq!(berlin.type_at(utc_timestamp("2044-12-01", "12:00:00")).offset, 3600);
+        let sydney = find_zone("sydney").unwrap();
+        assert_eq!(sydney.type_at(utc_timestamp("2044-01-01", "12:00:00")).abbreviation, "AEDT");
+    }
+}